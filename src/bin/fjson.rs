@@ -1,11 +1,13 @@
-use std::fs;
-use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::Arc;
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use fracturedjson::{
-    CommentPolicy, EolStyle, Formatter, FracturedJsonOptions, NumberListAlignment,
+    CommentPolicy, ControlCharPolicy, EolStyle, Formatter, FracturedJsonOptions,
+    NumberListAlignment, SkeletonLanguage,
 };
 
 /// A human-friendly JSON formatter with smart line breaks and table alignment.
@@ -16,13 +18,19 @@ use fracturedjson::{
 #[derive(Parser, Debug)]
 #[command(name = "fjson")]
 #[command(version, about, long_about = None)]
+// An input file named like a subcommand is still read as a file when flags
+// come first, as in `fjson -w 80 types`.
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Input file(s). If not specified, reads from stdin.
     #[arg(value_name = "FILE")]
     files: Vec<PathBuf>,
 
     /// Output file. If not specified, writes to stdout.
-    #[arg(short, long, value_name = "FILE")]
+    #[arg(short, long, value_name = "FILE", global = true)]
     output: Option<PathBuf>,
 
     /// Minify output (remove all whitespace).
@@ -41,6 +49,10 @@ struct Args {
     #[arg(short = 't', long)]
     tabs: bool,
 
+    /// How string widths are measured for line length and alignment.
+    #[arg(long, value_enum, default_value = "chars")]
+    width_mode: WidthModeArg,
+
     /// Line ending style.
     #[arg(long, value_enum, default_value = "lf")]
     eol: EolStyleArg,
@@ -53,6 +65,15 @@ struct Args {
     #[arg(long)]
     trailing_commas: bool,
 
+    /// How to handle raw control characters (tabs, newlines) inside strings.
+    #[arg(long, value_enum, default_value = "error")]
+    control_chars: ControlCharPolicyArg,
+
+    /// Reject input that breaks RFC 8259 in ways other options allow:
+    /// unpaired surrogates, duplicate keys, and content after the value.
+    #[arg(long)]
+    strict: bool,
+
     /// Preserve blank lines from input.
     #[arg(long)]
     preserve_blanks: bool,
@@ -77,6 +98,11 @@ struct Args {
     #[arg(long)]
     no_nested_bracket_padding: bool,
 
+    /// File listing, one per line, the keys that object members should be
+    /// sorted into. Members with other keys follow in their original order.
+    #[arg(long, value_name = "FILE")]
+    key_order: Option<PathBuf>,
+
     /// Treat input as JSON Lines (one JSON value per line).
     #[arg(long)]
     jsonl: bool,
@@ -84,6 +110,75 @@ struct Args {
     /// How to handle JSONL parsing errors (only used with --jsonl).
     #[arg(long, value_enum, default_value = "fail")]
     jsonl_errors: JsonlErrorPolicy,
+
+    /// Check that each input is already formatted instead of printing it.
+    /// Directories are searched for .json and .jsonc files (.jsonl and
+    /// .ndjson with --jsonl). Exits with status 1 if any input would change.
+    #[arg(long)]
+    check: bool,
+
+    /// Size in bytes of the buffers used for reading input and writing output.
+    #[arg(
+        long,
+        value_name = "BYTES",
+        default_value = "65536",
+        global = true,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    buffer_size: usize,
+
+    /// How to report the results of --check.
+    #[arg(long, value_enum, default_value = "text", requires = "check")]
+    report: ReportFormat,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Infer Rust struct or TypeScript interface skeletons from a document.
+    Types {
+        /// Language to emit type definitions for.
+        #[arg(long, value_enum, default_value = "rust")]
+        lang: LangArg,
+
+        /// Input file. If not specified, reads from stdin.
+        #[arg(value_name = "FILE")]
+        files: Vec<PathBuf>,
+    },
+    /// Show how each input's formatting changes between two option sets.
+    ///
+    /// Option files are TOML documents whose keys are the library's
+    /// FracturedJsonOptions fields (for example `indent_spaces = 2`); missing
+    /// keys keep their defaults. Formatting flags such as --indent are
+    /// ignored. Directories are searched for .json and .jsonc files.
+    CompareOptions {
+        /// TOML file with the current options.
+        #[arg(long, value_name = "FILE")]
+        a: PathBuf,
+
+        /// TOML file with the proposed options.
+        #[arg(long, value_name = "FILE")]
+        b: PathBuf,
+
+        /// Input files or directories. If not specified, reads from stdin.
+        #[arg(value_name = "FILE")]
+        files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum LangArg {
+    Rust,
+    Ts,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum WidthModeArg {
+    /// Count Unicode characters.
+    Chars,
+    /// Count terminal columns, with East Asian wide characters as two.
+    Unicode,
+    /// Count UTF-8 bytes.
+    Bytes,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -97,6 +192,14 @@ enum CommentPolicyArg {
     Error,
     Remove,
     Preserve,
+    PreserveHeader,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ControlCharPolicyArg {
+    Error,
+    Escape,
+    Preserve,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -107,6 +210,15 @@ enum NumberAlignArg {
     Normalize,
 }
 
+/// Output format for --check results.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    /// List the files that would change on stderr.
+    Text,
+    /// Write a JSON array with one entry per file to stdout.
+    Json,
+}
+
 /// How to handle errors when parsing JSONL input.
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
 enum JsonlErrorPolicy {
@@ -123,102 +235,433 @@ fn main() {
     let args = Args::parse();
 
     if let Err(e) = run(args) {
+        // A closed pipe (as in `fjson big.json | head`) is not an error.
+        if e.downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+        {
+            process::exit(0);
+        }
         eprintln!("fjson: {}", e);
         process::exit(1);
     }
 }
 
 fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
-    // Read input
-    let input = if args.files.is_empty() {
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)?;
-        buffer
-    } else {
-        let mut combined = String::new();
-        for path in &args.files {
-            let content = fs::read_to_string(path)
-                .map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
-            combined.push_str(&content);
-        }
-        combined
-    };
-
     // Configure formatter
     let mut formatter = Formatter::new();
     configure_options(&mut formatter.options, &args);
+    formatter.string_length_func = match args.width_mode {
+        WidthModeArg::Chars => Arc::new(Formatter::string_length_by_char_count),
+        WidthModeArg::Unicode => Arc::new(Formatter::string_length_by_display_width),
+        WidthModeArg::Bytes => Arc::new(Formatter::string_length_by_byte_count),
+    };
+    if let Some(path) = &args.key_order {
+        formatter.options.key_order = read_key_order(path)?;
+    }
+
+    if args.check && args.command.is_none() {
+        return run_check(&args, &mut formatter);
+    }
 
-    // Format
-    let output = if args.jsonl {
-        process_jsonl(&input, &mut formatter, args.compact, args.jsonl_errors)?
-    } else if args.compact {
-        formatter.minify(&input)?
+    if let Some(Command::Types { lang, files }) = &args.command {
+        let input = read_input(files, args.buffer_size)?;
+        let language = match lang {
+            LangArg::Rust => SkeletonLanguage::Rust,
+            LangArg::Ts => SkeletonLanguage::TypeScript,
+        };
+        let output = formatter.type_skeleton(&input, language)?;
+        return write_output(&args, output.as_bytes());
+    }
+
+    if let Some(Command::CompareOptions { a, b, files }) = &args.command {
+        return run_compare_options(&args, &mut formatter, [a, b], files);
+    }
+
+    // JSONL is streamed line by line rather than read in whole.
+    if args.jsonl {
+        let reader = open_input(&args.files, args.buffer_size)?;
+        if let Some(path) = &args.output {
+            return process_jsonl_to_file(reader, path, &mut formatter, &args);
+        }
+        let mut writer = open_output(&args)?;
+        process_jsonl(reader, &mut writer, &mut formatter, &args)?;
+        writer.flush()?;
+        return Ok(());
+    }
+
+    let mut input = Vec::new();
+    open_input(&args.files, args.buffer_size)?.read_to_end(&mut input)?;
+    let output = format_input(&input, &mut formatter, &args)?;
+    write_output(&args, &output)
+}
+
+/// Write the whole output to the --output file or stdout.
+fn write_output(args: &Args, output: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = open_output(args)?;
+    writer.write_all(output)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Open the output file given with --output, or stdout.
+fn open_output(args: &Args) -> Result<Box<dyn Write>, Box<dyn std::error::Error>> {
+    Ok(match &args.output {
+        Some(path) => {
+            let file = File::create(path)
+                .map_err(|e| format!("cannot write '{}': {}", path.display(), e))?;
+            Box::new(BufWriter::with_capacity(args.buffer_size, file))
+        }
+        None => Box::new(BufWriter::with_capacity(
+            args.buffer_size,
+            io::stdout().lock(),
+        )),
+    })
+}
+
+/// Format one input according to the command-line flags.
+fn format_input(
+    input: &[u8],
+    formatter: &mut Formatter,
+    args: &Args,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if args.jsonl {
+        let mut output = Vec::new();
+        process_jsonl(input, &mut output, formatter, args)?;
+        return Ok(output);
+    }
+
+    let input = decode_utf8(input)?;
+    let output = if args.compact {
+        formatter.minify(input)?
     } else {
-        formatter.reformat(&input, 0)?
+        formatter.reformat(input, 0)?
     };
+    Ok(output.into_bytes())
+}
+
+/// Verdict of --check for a single input.
+struct CheckResult {
+    file: String,
+    formatted: bool,
+    error: Option<String>,
+    lines_changed: usize,
+}
+
+/// Check each input file (or stdin) separately against its formatted form.
+fn run_check(args: &Args, formatter: &mut Formatter) -> Result<(), Box<dyn std::error::Error>> {
+    let mut inputs = Vec::new();
+    if args.files.is_empty() {
+        let mut buffer = Vec::new();
+        open_input(&[], args.buffer_size)?.read_to_end(&mut buffer)?;
+        inputs.push(("<stdin>".to_string(), Ok(buffer)));
+    } else {
+        for path in collect_files(&args.files, args.jsonl)? {
+            let content = fs::read(&path).map_err(|e| e.to_string());
+            inputs.push((path.display().to_string(), content));
+        }
+    }
+
+    let mut results = Vec::new();
+    for (file, content) in inputs {
+        let result = match content.and_then(|input| {
+            format_input(&input, formatter, args)
+                .map(|output| (input, output))
+                .map_err(|e| e.to_string())
+        }) {
+            Ok((input, output)) => CheckResult {
+                file,
+                formatted: input == output,
+                error: None,
+                lines_changed: count_changed_lines(
+                    &String::from_utf8_lossy(&input),
+                    &String::from_utf8_lossy(&output),
+                ),
+            },
+            Err(error) => CheckResult {
+                file,
+                formatted: false,
+                error: Some(error),
+                lines_changed: 0,
+            },
+        };
+        results.push(result);
+    }
+
+    match args.report {
+        ReportFormat::Text => {
+            for result in &results {
+                match &result.error {
+                    Some(error) => eprintln!("error: {}: {}", result.file, error),
+                    None if !result.formatted => eprintln!("would reformat: {}", result.file),
+                    None => {}
+                }
+            }
+        }
+        ReportFormat::Json => {
+            // Built as text so the fields keep this order.
+            let entries: Vec<String> = results
+                .iter()
+                .map(|result| {
+                    format!(
+                        r#"{{"file": {}, "formatted": {}, "error": {}, "lines_changed": {}}}"#,
+                        serde_json::Value::from(result.file.as_str()),
+                        result.formatted,
+                        serde_json::Value::from(result.error.clone()),
+                        result.lines_changed
+                    )
+                })
+                .collect();
+            let report = format!("[{}]", entries.join(","));
+            let output = Formatter::new().reformat(&report, 0)?;
+            io::stdout().write_all(output.as_bytes())?;
+        }
+    }
+
+    let failures = results.iter().filter(|result| !result.formatted).count();
+    if failures > 0 {
+        return Err(format!("{} of {} file(s) not formatted", failures, results.len()).into());
+    }
+    Ok(())
+}
 
-    // Write output
-    if let Some(path) = args.output {
-        fs::write(&path, &output)
-            .map_err(|e| format!("cannot write '{}': {}", path.display(), e))?;
+/// Write the diff between the output of two option files for each input.
+fn run_compare_options(
+    args: &Args,
+    formatter: &mut Formatter,
+    option_files: [&PathBuf; 2],
+    files: &[PathBuf],
+) -> Result<(), Box<dyn std::error::Error>> {
+    formatter.options = read_options(option_files[0])?;
+    let other_options = read_options(option_files[1])?;
+
+    let mut inputs = Vec::new();
+    if files.is_empty() {
+        inputs.push(("<stdin>".to_string(), read_input(&[], args.buffer_size)?));
     } else {
-        io::stdout().write_all(output.as_bytes())?;
+        for file in collect_files(files, false)? {
+            let input = read_input(std::slice::from_ref(&file), args.buffer_size)?;
+            inputs.push((file.display().to_string(), input));
+        }
     }
 
+    let mut output = String::new();
+    let mut differing = 0;
+    for (name, input) in &inputs {
+        let comparison = formatter
+            .compare_options(input, &other_options)
+            .map_err(|e| format!("{}: {}", name, e))?;
+        if comparison.has_differences() {
+            differing += 1;
+            output.push_str(&comparison.unified_diff(
+                &format!("{} ({})", name, option_files[0].display()),
+                &format!("{} ({})", name, option_files[1].display()),
+            ));
+        }
+    }
+    write_output(args, output.as_bytes())?;
+    eprintln!(
+        "{} of {} file(s) format differently",
+        differing,
+        inputs.len()
+    );
     Ok(())
 }
 
-/// Process JSONL input (one JSON value per line).
+/// Read a TOML file of formatting options.
+fn read_options(path: &PathBuf) -> Result<FracturedJsonOptions, Box<dyn std::error::Error>> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    let options = toml::from_str(&text)
+        .map_err(|e| format!("invalid options file '{}': {}", path.display(), e))?;
+    Ok(options)
+}
+
+/// Expand directories into the JSON files beneath them, in sorted order.
+fn collect_files(
+    paths: &[PathBuf],
+    jsonl: bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let extensions: &[&str] = if jsonl {
+        &["jsonl", "ndjson"]
+    } else {
+        &["json", "jsonc"]
+    };
+
+    let mut files = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+
+        let mut entries = fs::read_dir(path)
+            .map_err(|e| format!("cannot read '{}': {}", path.display(), e))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+        let (dirs, entries): (Vec<_>, Vec<_>) = entries.into_iter().partition(|p| p.is_dir());
+        files.extend(entries.into_iter().filter(|p| {
+            p.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| extensions.contains(&ext))
+        }));
+        files.extend(collect_files(&dirs, jsonl)?);
+    }
+    Ok(files)
+}
+
+/// Size of the region that differs between two texts, in lines: everything
+/// between their common leading and trailing lines.
+fn count_changed_lines(before: &str, after: &str) -> usize {
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+    let prefix = before
+        .iter()
+        .zip(&after)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    (before.len() - prefix - suffix).max(after.len() - prefix - suffix)
+}
+
+/// Read a --key-order file: one key per line, blank lines ignored.
+fn read_key_order(path: &PathBuf) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Open the given files, one after the other, or stdin if none are given.
+fn open_input(
+    files: &[PathBuf],
+    buffer_size: usize,
+) -> Result<Box<dyn BufRead>, Box<dyn std::error::Error>> {
+    if files.is_empty() {
+        return Ok(Box::new(BufReader::with_capacity(
+            buffer_size,
+            io::stdin().lock(),
+        )));
+    }
+
+    let mut combined: Box<dyn Read> = Box::new(io::empty());
+    for path in files {
+        let file =
+            File::open(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+        combined = Box::new(combined.chain(file));
+    }
+    Ok(Box::new(BufReader::with_capacity(buffer_size, combined)))
+}
+
+/// Read input from the given files, or from stdin if none are given.
+fn read_input(files: &[PathBuf], buffer_size: usize) -> Result<String, Box<dyn std::error::Error>> {
+    let mut bytes = Vec::new();
+    open_input(files, buffer_size)?.read_to_end(&mut bytes)?;
+    Ok(decode_utf8(&bytes)?.to_string())
+}
+
+fn decode_utf8(bytes: &[u8]) -> Result<&str, String> {
+    std::str::from_utf8(bytes)
+        .map_err(|e| format!("input is not valid UTF-8 (at byte {})", e.valid_up_to()))
+}
+
+/// Stream JSONL input into the file at `path`. Lines go to a temporary file
+/// beside it, which replaces `path` only once every line is written, so a
+/// failed run leaves `path` as it was, and `path` can also be an input.
+fn process_jsonl_to_file(
+    input: impl BufRead,
+    path: &Path,
+    formatter: &mut Formatter,
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.fjson-tmp", file_name));
+    let cannot_write = |e: io::Error| format!("cannot write '{}': {}", path.display(), e);
+
+    let file = File::create(&temp_path).map_err(cannot_write)?;
+    let mut writer = BufWriter::with_capacity(args.buffer_size, file);
+    let written = process_jsonl(input, &mut writer, formatter, args)
+        .and_then(|()| writer.flush().map_err(Into::into));
+    drop(writer);
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    fs::rename(&temp_path, path).map_err(cannot_write)?;
+    Ok(())
+}
+
+/// Process JSONL input (one JSON value per line), writing each line as soon
+/// as it is formatted.
 fn process_jsonl(
-    input: &str,
+    mut input: impl BufRead,
+    output: &mut impl Write,
     formatter: &mut Formatter,
-    compact: bool,
-    error_policy: JsonlErrorPolicy,
-) -> Result<String, Box<dyn std::error::Error>> {
-    let mut output_lines = Vec::new();
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut raw_line = Vec::new();
+    let mut line_num = 0;
+
+    loop {
+        raw_line.clear();
+        if input.read_until(b'\n', &mut raw_line)? == 0 {
+            break;
+        }
+        line_num += 1;
+
+        let mut raw = raw_line.as_slice();
+        raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+        raw = raw.strip_suffix(b"\r").unwrap_or(raw);
 
-    for (line_num, line) in input.lines().enumerate() {
         // Preserve empty lines
-        if line.trim().is_empty() {
-            output_lines.push(String::new());
+        if raw.iter().all(u8::is_ascii_whitespace) {
+            output.write_all(b"\n")?;
             continue;
         }
 
         // Try to format the line
-        let result = if compact {
-            formatter.minify(line)
-        } else {
-            formatter.reformat(line, 0)
+        let result = match std::str::from_utf8(raw) {
+            Ok(line) if args.compact => {
+                formatter.minify_jsonl_line(line).map_err(|e| e.to_string())
+            }
+            Ok(line) => formatter
+                .reformat_jsonl_line(line)
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(format!("not valid UTF-8 (at byte {})", e.valid_up_to())),
         };
 
         match result {
             Ok(formatted) => {
                 // Remove trailing newline from formatted output since we add our own
-                let formatted = formatted.trim_end().to_string();
-                output_lines.push(formatted);
+                output.write_all(formatted.trim_end().as_bytes())?;
+                output.write_all(b"\n")?;
             }
-            Err(e) => match error_policy {
+            Err(e) => match args.jsonl_errors {
                 JsonlErrorPolicy::Fail => {
-                    return Err(format!("line {}: {}", line_num + 1, e).into());
+                    return Err(format!("line {}: {}", line_num, e).into());
                 }
                 JsonlErrorPolicy::Skip => {
                     // Skip this line entirely
                     continue;
                 }
                 JsonlErrorPolicy::Passthrough => {
-                    // Output the original line unchanged
-                    output_lines.push(line.to_string());
+                    // Output the original line unchanged, even if it is not text
+                    output.write_all(raw)?;
+                    output.write_all(b"\n")?;
                 }
             },
         }
     }
-
-    // Join with newlines and add trailing newline
-    let mut result = output_lines.join("\n");
-    if !result.is_empty() {
-        result.push('\n');
-    }
-    Ok(result)
+    Ok(())
 }
 
 fn configure_options(opts: &mut FracturedJsonOptions, args: &Args) {
@@ -235,6 +678,13 @@ fn configure_options(opts: &mut FracturedJsonOptions, args: &Args) {
         CommentPolicyArg::Error => CommentPolicy::TreatAsError,
         CommentPolicyArg::Remove => CommentPolicy::Remove,
         CommentPolicyArg::Preserve => CommentPolicy::Preserve,
+        CommentPolicyArg::PreserveHeader => CommentPolicy::PreserveHeader,
+    };
+
+    opts.control_char_policy = match args.control_chars {
+        ControlCharPolicyArg::Error => ControlCharPolicy::TreatAsError,
+        ControlCharPolicyArg::Escape => ControlCharPolicy::Escape,
+        ControlCharPolicyArg::Preserve => ControlCharPolicy::Preserve,
     };
 
     opts.number_list_alignment = match args.number_align {
@@ -245,6 +695,7 @@ fn configure_options(opts: &mut FracturedJsonOptions, args: &Args) {
     };
 
     opts.allow_trailing_commas = args.trailing_commas;
+    opts.strict_rfc8259 = args.strict;
     opts.preserve_blank_lines = args.preserve_blanks;
     opts.max_inline_complexity = args.max_inline_complexity;
     opts.max_table_row_complexity = args.max_table_complexity;
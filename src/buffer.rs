@@ -5,6 +5,9 @@ use crate::options::{EolStyle, FracturedJsonOptions};
 pub struct StringJoinBuffer {
     line_buff: Vec<String>,
     doc_buff: Vec<String>,
+    /// Number of finished lines already taken off the front of `doc_buff`.
+    /// Line indices still count them.
+    taken: usize,
 }
 
 impl StringJoinBuffer {
@@ -32,10 +35,80 @@ impl StringJoinBuffer {
         self
     }
 
+    /// Removes spaces and tabs from the end of the line being built.
+    pub fn trim_end(&mut self) -> &mut Self {
+        while let Some(last) = self.line_buff.last_mut() {
+            let trimmed_len = last.trim_end_matches([' ', '\t']).len();
+            if trimmed_len > 0 {
+                last.truncate(trimmed_len);
+                break;
+            }
+            self.line_buff.pop();
+        }
+        self
+    }
+
+    /// Number of lines ended so far, which is also the index of the line
+    /// being built.
+    pub fn line_count(&self) -> usize {
+        self.taken + self.doc_buff.len()
+    }
+
     pub fn as_string(&self) -> String {
         self.doc_buff.join("")
     }
 
+    /// Takes the last finished line back off the document, without its line
+    /// ending, so more can be added to it.
+    pub fn reopen_last_line(&mut self, eol: &str) -> &mut Self {
+        if let Some(mut line) = self.doc_buff.pop() {
+            line.truncate(line.len() - eol.len());
+            self.line_buff.insert(0, line);
+        }
+        self
+    }
+
+    /// Finished line `index`, with its line ending. None once it's taken.
+    pub fn line(&self, index: usize) -> Option<&str> {
+        self.doc_buff
+            .get(index.checked_sub(self.taken)?)
+            .map(String::as_str)
+    }
+
+    /// Mutable access to finished line `index`, with its line ending.
+    pub fn line_mut(&mut self, index: usize) -> Option<&mut String> {
+        self.doc_buff.get_mut(index.checked_sub(self.taken)?)
+    }
+
+    /// Index of the last finished line that isn't empty and isn't taken.
+    pub fn last_non_empty_line(&self) -> Option<usize> {
+        self.doc_buff
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map(|index| self.taken + index)
+    }
+
+    /// Drops the line being built and every finished line from `index` on.
+    pub fn truncate_lines(&mut self, index: usize) {
+        self.doc_buff.truncate(index.saturating_sub(self.taken));
+        self.line_buff.clear();
+    }
+
+    /// Inserts `count` spaces into finished line `index` at byte offset `at`.
+    pub fn insert_spaces(&mut self, index: usize, at: usize, count: usize) {
+        if let Some(line) = self.line_mut(index) {
+            line.insert_str(at, &" ".repeat(count));
+        }
+    }
+
+    /// Removes and returns the finished lines before line `end` that aren't
+    /// taken yet, each with its line ending.
+    pub fn take_lines(&mut self, end: usize) -> Vec<String> {
+        let count = end.saturating_sub(self.taken).min(self.doc_buff.len());
+        self.taken += count;
+        self.doc_buff.drain(..count).collect()
+    }
+
     fn add_line_to_writer(&mut self, eol: &str) {
         if self.line_buff.is_empty() && eol.is_empty() {
             return;
@@ -56,15 +129,25 @@ pub struct PaddedFormattingTokens {
     comma: String,
     colon: String,
     comment: String,
+    postfix_comment: String,
+    postfix_comma: String,
+    postfix_dummy_comma: String,
     eol: String,
     dummy_comma: String,
+    table_comma: String,
+    table_dummy_comma: String,
     comma_len: usize,
+    table_comma_len: usize,
     colon_len: usize,
     comment_len: usize,
+    postfix_comment_len: usize,
     literal_null_len: usize,
     literal_true_len: usize,
     literal_false_len: usize,
     prefix_string_len: usize,
+    indent: String,
+    indent_len: usize,
+    indent_widths: Vec<usize>,
     arr_start: Vec<String>,
     arr_end: Vec<String>,
     obj_start: Vec<String>,
@@ -139,8 +222,14 @@ impl PaddedFormattingTokens {
         .to_string();
 
         let comma = if opts.comma_padding { ", " } else { "," }.to_string();
-        let colon = if opts.colon_padding { ": " } else { ":" }.to_string();
-        let comment = if opts.comment_padding { " " } else { "" }.to_string();
+        let colon = opts
+            .colon_token
+            .clone()
+            .unwrap_or_else(|| if opts.colon_padding { ": " } else { ":" }.to_string());
+        let comment = opts
+            .comment_separator
+            .clone()
+            .unwrap_or_else(|| if opts.comment_padding { " " } else { "" }.to_string());
         let eol = if opts.json_eol_style == EolStyle::Crlf {
             "\r\n"
         } else {
@@ -153,37 +242,88 @@ impl PaddedFormattingTokens {
         let obj_start_len = obj_start.iter().map(|s| str_len_func(s)).collect();
         let obj_end_len = obj_end.iter().map(|s| str_len_func(s)).collect();
 
-        let indent_strings = vec![
-            String::new(),
-            if opts.use_tab_to_indent {
-                "\t".to_string()
-            } else {
-                " ".repeat(opts.indent_spaces)
-            },
-        ];
+        let indent = if let Some(indent) = &opts.indent_string {
+            indent.clone()
+        } else if opts.use_tab_to_indent {
+            "\t".to_string()
+        } else {
+            " ".repeat(opts.indent_spaces)
+        };
+        // Tabs are budgeted as `indent_spaces` columns, as they always have been.
+        let indent_len = match &opts.indent_string {
+            Some(indent) => str_len_func(indent),
+            None => opts.indent_spaces,
+        };
+
+        // Depths listed in `indent_spaces_by_depth` are built up front; deeper
+        // levels are added on demand by `indent()`.
+        let mut indent_strings = vec![String::new()];
+        let mut indent_widths = vec![0];
+        for &spaces in &opts.indent_spaces_by_depth {
+            indent_strings.push(format!(
+                "{}{}",
+                indent_strings.last().unwrap(),
+                " ".repeat(spaces)
+            ));
+            indent_widths.push(indent_widths.last().unwrap() + spaces);
+        }
 
         let comma_len = str_len_func(&comma);
         let colon_len = str_len_func(&colon);
         let comment_len = str_len_func(&comment);
+        let postfix_comment = match opts.spaces_before_eol_comment {
+            Some(spaces) => " ".repeat(spaces),
+            None => comment.clone(),
+        };
+        let postfix_comment_len = str_len_func(&postfix_comment);
+        // With a fixed gutter, the gap is counted from the comma itself.
+        let postfix_comma = match opts.spaces_before_eol_comment {
+            Some(_) => comma.trim_end().to_string(),
+            None => comma.clone(),
+        };
+        let postfix_dummy_comma = " ".repeat(str_len_func(&postfix_comma));
         let literal_null_len = str_len_func("null");
         let literal_true_len = str_len_func("true");
         let literal_false_len = str_len_func("false");
         let prefix_string_len = str_len_func(&opts.prefix_string);
-        let dummy_comma = " ".repeat(comma_len);
+        let dummy_comma = opts
+            .dummy_comma
+            .clone()
+            .unwrap_or_else(|| " ".repeat(comma_len));
+        let table_comma = match opts.table_column_spacing {
+            Some(spacing) => format!(",{}", " ".repeat(spacing)),
+            None => comma.clone(),
+        };
+        let table_comma_len = str_len_func(&table_comma);
+        let table_dummy_comma = format!(
+            "{}{}",
+            dummy_comma,
+            " ".repeat(table_comma_len.saturating_sub(comma_len))
+        );
 
         Self {
             comma,
             colon,
             comment,
+            postfix_comment,
+            postfix_comma,
+            postfix_dummy_comma,
             eol,
             dummy_comma,
+            table_comma,
+            table_dummy_comma,
             comma_len,
+            table_comma_len,
             colon_len,
             comment_len,
+            postfix_comment_len,
             literal_null_len,
             literal_true_len,
             literal_false_len,
             prefix_string_len,
+            indent,
+            indent_len,
+            indent_widths,
             arr_start,
             arr_end,
             obj_start,
@@ -217,6 +357,13 @@ impl PaddedFormattingTokens {
     pub fn comment_len(&self) -> usize {
         self.comment_len
     }
+    /// The gutter between a value and the comment after it.
+    pub fn postfix_comment(&self) -> &str {
+        &self.postfix_comment
+    }
+    pub fn postfix_comment_len(&self) -> usize {
+        self.postfix_comment_len
+    }
     pub fn literal_null_len(&self) -> usize {
         self.literal_null_len
     }
@@ -229,9 +376,36 @@ impl PaddedFormattingTokens {
     pub fn prefix_string_len(&self) -> usize {
         self.prefix_string_len
     }
+    /// Width of the indentation at `depth`, for line length budgeting.
+    pub fn indent_width(&self, depth: usize) -> usize {
+        let listed = self.indent_widths.len() - 1;
+        if depth <= listed {
+            return self.indent_widths[depth];
+        }
+        self.indent_widths[listed] + (depth - listed).saturating_mul(self.indent_len)
+    }
     pub fn dummy_comma(&self) -> &str {
         &self.dummy_comma
     }
+    /// The comma after a value that has a line comment after it.
+    pub fn postfix_comma(&self) -> &str {
+        &self.postfix_comma
+    }
+    /// Filler for `postfix_comma` after the last item of a table.
+    pub fn postfix_dummy_comma(&self) -> &str {
+        &self.postfix_dummy_comma
+    }
+    /// The comma between table columns.
+    pub fn table_comma(&self) -> &str {
+        &self.table_comma
+    }
+    pub fn table_comma_len(&self) -> usize {
+        self.table_comma_len
+    }
+    /// Filler in place of the comma after a table row's last column.
+    pub fn table_dummy_comma(&self) -> &str {
+        &self.table_dummy_comma
+    }
 
     pub fn arr_start(&self, kind: BracketPaddingType) -> &str {
         &self.arr_start[kind as usize]
@@ -292,12 +466,9 @@ impl PaddedFormattingTokens {
     }
 
     pub fn indent(&mut self, level: usize) -> String {
-        if level >= self.indent_strings.len() {
-            let base = self.indent_strings[1].clone();
-            for i in self.indent_strings.len()..=level {
-                let next = format!("{}{}", self.indent_strings[i - 1], base);
-                self.indent_strings.push(next);
-            }
+        for i in self.indent_strings.len()..=level {
+            let next = format!("{}{}", self.indent_strings[i - 1], self.indent);
+            self.indent_strings.push(next);
         }
         self.indent_strings[level].clone()
     }
@@ -0,0 +1,332 @@
+use crate::model::{JsonItem, JsonItemType};
+use crate::options::{CommentSpacing, CommentStyle};
+
+/// Rewrites every comment in `doc_model` to follow `spacing`.
+pub fn apply_comment_spacing(doc_model: &mut [JsonItem], spacing: CommentSpacing) {
+    if spacing == CommentSpacing::Preserve {
+        return;
+    }
+    for item in doc_model.iter_mut() {
+        respace_item(item, spacing);
+    }
+}
+
+fn respace_item(item: &mut JsonItem, spacing: CommentSpacing) {
+    if matches!(
+        item.item_type,
+        JsonItemType::BlockComment | JsonItemType::LineComment
+    ) {
+        item.value = respace_comments(&item.value, spacing);
+    }
+    for comment in [
+        &mut item.prefix_comment,
+        &mut item.middle_comment,
+        &mut item.postfix_comment,
+    ] {
+        if !comment.is_empty() {
+            *comment = respace_comments(comment, spacing);
+        }
+    }
+    for child in item.children.iter_mut() {
+        respace_item(child, spacing);
+    }
+}
+
+/// Rewrites the comments in `doc_model` in the syntax `style` prefers, where
+/// they can be.
+pub fn apply_comment_style(doc_model: &mut [JsonItem], style: CommentStyle) {
+    if style == CommentStyle::Preserve {
+        return;
+    }
+    for item in doc_model.iter_mut() {
+        restyle_item(item, style);
+    }
+}
+
+fn restyle_item(item: &mut JsonItem, style: CommentStyle) {
+    let is_line_comment = item.item_type == JsonItemType::LineComment;
+    let is_block_comment = item.item_type == JsonItemType::BlockComment;
+    match style {
+        CommentStyle::PreferBlock => {
+            if let (true, Some(block)) = (is_line_comment, line_to_block(&item.value)) {
+                item.value = block;
+                item.item_type = JsonItemType::BlockComment;
+            }
+            if item.is_post_comment_line_style {
+                if let Some(block) = line_to_block(&item.postfix_comment) {
+                    item.postfix_comment = block;
+                    item.is_post_comment_line_style = false;
+                }
+            }
+            if item.middle_comment.contains("//") {
+                if let Some(blocks) = as_block_comments(&item.middle_comment) {
+                    item.middle_comment_has_new_line = blocks.contains('\n');
+                    item.middle_comment = blocks;
+                }
+            }
+        }
+        CommentStyle::PreferLine => {
+            if let (true, Some(line)) = (is_block_comment, block_to_line(&item.value)) {
+                item.value = line;
+                item.item_type = JsonItemType::LineComment;
+            }
+            if !item.is_post_comment_line_style {
+                if let Some(line) = block_to_line(&item.postfix_comment) {
+                    item.postfix_comment = line;
+                    item.is_post_comment_line_style = true;
+                }
+            }
+        }
+        CommentStyle::Preserve => {}
+    }
+    for child in item.children.iter_mut() {
+        restyle_item(child, style);
+    }
+}
+
+/// The line comment `comment` as a block comment, or None if it isn't a
+/// line comment or holds `*/`.
+fn line_to_block(comment: &str) -> Option<String> {
+    let body = comment
+        .strip_prefix("//")?
+        .trim_start_matches('/')
+        .trim_end();
+    if body.contains("*/") {
+        return None;
+    }
+    let padding = if body.starts_with([' ', '\t']) {
+        " "
+    } else {
+        ""
+    };
+    Some(format!("/*{}{}*/", body, padding))
+}
+
+/// The single-line block comment `comment` as a line comment, or None if it
+/// isn't one.
+fn block_to_line(comment: &str) -> Option<String> {
+    let body = comment.strip_prefix("/*")?.strip_suffix("*/")?;
+    if body.contains(['\n', '\r']) || body.contains("*/") {
+        return None;
+    }
+    let body = body.trim_start_matches('*').trim_end();
+    Some(format!("//{}", body))
+}
+
+/// Rewrites the `//` comments between object keys and their values in
+/// `doc_model` as `/* */` comments, for the members that can then be minified
+/// onto one line. Formatted output decides this per member, from its length.
+pub fn apply_block_middle_comments(doc_model: &mut [JsonItem]) {
+    for item in doc_model.iter_mut() {
+        block_middle_comments(item);
+    }
+}
+
+fn block_middle_comments(item: &mut JsonItem) {
+    for child in item.children.iter_mut() {
+        block_middle_comments(child);
+    }
+    if !item.middle_comment_has_new_line || !fits_one_line(item) {
+        return;
+    }
+    if let Some(converted) = as_block_comments(&item.middle_comment) {
+        item.middle_comment = converted;
+        item.middle_comment_has_new_line = false;
+    }
+}
+
+/// Whether `item` could be written on one line, apart from its middle
+/// comment.
+fn fits_one_line(item: &JsonItem) -> bool {
+    !item.value.contains('\n')
+        && !item.prefix_comment.contains('\n')
+        && !item.postfix_comment.contains('\n')
+        && item.children.iter().all(|child| {
+            !matches!(
+                child.item_type,
+                JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+            ) && !child.is_post_comment_line_style
+                && !child.middle_comment_has_new_line
+                && fits_one_line(child)
+        })
+}
+
+/// The comments in `text` as single-line block comments on one line, or
+/// None if one of them spans lines or can't be made a block comment.
+pub fn as_block_comments(text: &str) -> Option<String> {
+    let mut comments = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let end = if rest.starts_with("//") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            comments.push(line_to_block(&rest[..end])?);
+            end
+        } else if let Some(after_open) = rest.strip_prefix("/*") {
+            let end = after_open.find("*/")? + 4;
+            if rest[..end].contains('\n') {
+                return None;
+            }
+            comments.push(rest[..end].to_string());
+            end
+        } else {
+            return None;
+        };
+        rest = rest[end..].trim_start();
+    }
+    Some(comments.join(" "))
+}
+
+/// Respaces each comment in `text`, which may hold several comments
+/// separated by whitespace (as middle comments do).
+fn respace_comments(text: &str, spacing: CommentSpacing) -> String {
+    let mut output = String::with_capacity(text.len() + 2);
+    let mut rest = text;
+    while let Some(start) = rest.find('/') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = if rest.starts_with("//") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            output.push_str(&respace_line_comment(&rest[..end], spacing));
+            end
+        } else if let Some(after_open) = rest.strip_prefix("/*") {
+            let end = after_open.find("*/").map_or(rest.len(), |i| i + 4);
+            output.push_str(&respace_block_comment(&rest[..end], spacing));
+            end
+        } else {
+            output.push('/');
+            1
+        };
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn respace_line_comment(comment: &str, spacing: CommentSpacing) -> String {
+    // `///` and longer runs of slashes count as the marker.
+    let body = comment.trim_start_matches('/');
+    let marker = &comment[..comment.len() - body.len()];
+    if body.trim().is_empty() {
+        return comment.to_string();
+    }
+
+    match spacing {
+        CommentSpacing::Spaced if !body.starts_with([' ', '\t']) => {
+            format!("{} {}", marker, body)
+        }
+        CommentSpacing::Unspaced => format!("{}{}", marker, body.trim_start()),
+        _ => comment.to_string(),
+    }
+}
+
+fn respace_block_comment(comment: &str, spacing: CommentSpacing) -> String {
+    if comment.len() < 4 || !comment.ends_with("*/") {
+        return comment.to_string();
+    }
+    // `/**` counts as the opening marker.
+    let body = comment[1..comment.len() - 2].trim_start_matches('*');
+    let open = &comment[..comment.len() - 2 - body.len()];
+    if body.trim().is_empty() {
+        return comment.to_string();
+    }
+
+    let mut respaced = String::with_capacity(comment.len() + 2);
+    respaced.push_str(open);
+    match spacing {
+        CommentSpacing::Spaced => {
+            if !body.starts_with(char::is_whitespace) {
+                respaced.push(' ');
+            }
+            respaced.push_str(body);
+            if !body.ends_with(char::is_whitespace) {
+                respaced.push(' ');
+            }
+        }
+        _ => {
+            // Line breaks next to the markers are layout, not padding, and stay.
+            let leading = body.len() - body.trim_start_matches([' ', '\t']).len();
+            let body = if body[leading..].starts_with(['\n', '\r']) {
+                body
+            } else {
+                &body[leading..]
+            };
+            let trimmed = body.trim_end_matches([' ', '\t']);
+            let body = if trimmed.ends_with('\n') {
+                body
+            } else {
+                trimmed
+            };
+            respaced.push_str(body);
+        }
+    }
+    respaced.push_str("*/");
+    respaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spaced(text: &str) -> String {
+        respace_comments(text, CommentSpacing::Spaced)
+    }
+
+    fn unspaced(text: &str) -> String {
+        respace_comments(text, CommentSpacing::Unspaced)
+    }
+
+    #[test]
+    fn line_comments_are_respaced() {
+        assert_eq!(spaced("//comment"), "// comment");
+        assert_eq!(spaced("//   indented"), "//   indented");
+        assert_eq!(spaced("///doc"), "/// doc");
+        assert_eq!(unspaced("//   comment"), "//comment");
+        assert_eq!(spaced("//"), "//");
+        assert_eq!(spaced("////////"), "////////");
+    }
+
+    #[test]
+    fn block_comments_are_respaced() {
+        assert_eq!(spaced("/*x*/"), "/* x */");
+        assert_eq!(spaced("/**doc*/"), "/** doc */");
+        assert_eq!(spaced("/* x */"), "/* x */");
+        assert_eq!(unspaced("/*  x \t*/"), "/*x*/");
+        assert_eq!(spaced("/**/"), "/**/");
+        assert_eq!(spaced("/*****/"), "/*****/");
+    }
+
+    #[test]
+    fn multi_line_block_comments_keep_their_layout() {
+        assert_eq!(spaced("/*first\n   last*/"), "/* first\n   last */");
+        assert_eq!(unspaced("/*\n   text\n*/"), "/*\n   text\n*/");
+        assert_eq!(unspaced("/* first\n   last */"), "/*first\n   last*/");
+    }
+
+    #[test]
+    fn comments_change_style() {
+        assert_eq!(line_to_block("// note").as_deref(), Some("/* note */"));
+        assert_eq!(line_to_block("///doc").as_deref(), Some("/*doc*/"));
+        assert_eq!(line_to_block("// a */ b"), None);
+        assert_eq!(block_to_line("/* note */").as_deref(), Some("// note"));
+        assert_eq!(block_to_line("/**doc*/").as_deref(), Some("//doc"));
+        assert_eq!(block_to_line("/*a\nb*/"), None);
+    }
+
+    #[test]
+    fn middle_comments_become_block_comments() {
+        assert_eq!(as_block_comments("//b\n").as_deref(), Some("/*b*/"));
+        assert_eq!(
+            as_block_comments("/*a*/\n// b\n").as_deref(),
+            Some("/*a*/ /* b */")
+        );
+        assert_eq!(as_block_comments("// a */ b\n"), None);
+        assert_eq!(as_block_comments("/*a\nb*/"), None);
+    }
+
+    #[test]
+    fn every_comment_in_the_text_is_respaced() {
+        assert_eq!(spaced("/*a*/\n//b\n"), "/* a */\n// b\n");
+        assert_eq!(spaced("/*a*/ /*b*/"), "/* a */ /* b */");
+    }
+}
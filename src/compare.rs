@@ -0,0 +1,35 @@
+#[cfg(feature = "diff")]
+use similar::TextDiff;
+
+/// The same document formatted with two option sets, as returned by
+/// [`Formatter::compare_options`](crate::Formatter::compare_options).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionsComparison {
+    /// Output produced with the formatter's own options.
+    pub output_a: String,
+    /// Output produced with the other options.
+    pub output_b: String,
+}
+
+impl OptionsComparison {
+    /// Whether the two option sets produce different output.
+    pub fn has_differences(&self) -> bool {
+        self.output_a != self.output_b
+    }
+
+    /// The change from `output_a` to `output_b` as a unified diff with three
+    /// lines of context, using `label_a` and `label_b` in the `---`/`+++`
+    /// header. Empty if the outputs are the same. Requires the `diff`
+    /// feature.
+    #[cfg(feature = "diff")]
+    pub fn unified_diff(&self, label_a: &str, label_b: &str) -> String {
+        if !self.has_differences() {
+            return String::new();
+        }
+        TextDiff::from_lines(&self.output_a, &self.output_b)
+            .unified_diff()
+            .context_radius(3)
+            .header(label_a, label_b)
+            .to_string()
+    }
+}
@@ -1,10 +1,12 @@
 use crate::error::FracturedJsonError;
-use crate::model::{JsonItem, JsonItemType};
+use crate::model::{CommentMap, ItemComments, JsonItem, JsonItemType, Trivia};
+use crate::options::FracturedJsonOptions;
 
 pub fn convert_value_to_dom(
     element: &serde_json::Value,
     prop_name: Option<&str>,
     recursion_limit: usize,
+    options: &FracturedJsonOptions,
 ) -> Result<Option<JsonItem>, FracturedJsonError> {
     if recursion_limit == 0 {
         return Err(FracturedJsonError::simple(
@@ -46,12 +48,16 @@ pub fn convert_value_to_dom(
             item.item_type = JsonItemType::Array;
             let mut children = Vec::with_capacity(arr.len());
             for child in arr {
-                let converted = convert_value_to_dom(child, None, recursion_limit - 1)?;
+                let converted = convert_value_to_dom(child, None, recursion_limit - 1, options)?;
                 if let Some(child_item) = converted {
                     children.push(child_item);
                 } else {
-                    let null_item =
-                        convert_value_to_dom(&serde_json::Value::Null, None, recursion_limit - 1)?;
+                    let null_item = convert_value_to_dom(
+                        &serde_json::Value::Null,
+                        None,
+                        recursion_limit - 1,
+                        options,
+                    )?;
                     if let Some(null_item) = null_item {
                         children.push(null_item);
                     }
@@ -62,9 +68,11 @@ pub fn convert_value_to_dom(
         serde_json::Value::Object(map) => {
             item.item_type = JsonItemType::Object;
             for (key, value) in map.iter() {
-                let child = convert_value_to_dom(value, Some(key), recursion_limit - 1)?;
+                let child = convert_value_to_dom(value, Some(key), recursion_limit - 1, options)?;
                 if let Some(child_item) = child {
-                    item.children.push(child_item);
+                    if !is_omitted(&child_item, options) {
+                        item.children.push(child_item);
+                    }
                 }
             }
         }
@@ -82,3 +90,151 @@ pub fn convert_value_to_dom(
 
     Ok(Some(item))
 }
+
+/// Whether a converted property is left out under `omit_null_values` and
+/// `omit_empty_containers`.
+fn is_omitted(item: &JsonItem, options: &FracturedJsonOptions) -> bool {
+    match item.item_type {
+        JsonItemType::Null => options.omit_null_values,
+        JsonItemType::Array | JsonItemType::Object => {
+            options.omit_empty_containers && item.children.is_empty()
+        }
+        _ => false,
+    }
+}
+
+pub fn convert_dom_to_value(
+    doc_model: &[JsonItem],
+) -> Result<(serde_json::Value, CommentMap), FracturedJsonError> {
+    let mut comments = CommentMap::new();
+    let mut pending: Vec<Trivia> = Vec::new();
+    let mut root: Option<serde_json::Value> = None;
+
+    for item in doc_model {
+        if let Some(trivia) = trivia_from_item(item) {
+            pending.push(trivia);
+            continue;
+        }
+        let leading = std::mem::take(&mut pending);
+        root = Some(convert_item_to_value(
+            item,
+            String::new(),
+            leading,
+            &mut comments,
+        )?);
+    }
+
+    let root = root.ok_or_else(|| FracturedJsonError::simple("No top-level value found"))?;
+    if !pending.is_empty() {
+        comments
+            .entry(String::new())
+            .or_default()
+            .trailing
+            .append(&mut pending);
+    }
+    Ok((root, comments))
+}
+
+fn convert_item_to_value(
+    item: &JsonItem,
+    pointer: String,
+    leading: Vec<Trivia>,
+    comments: &mut CommentMap,
+) -> Result<serde_json::Value, FracturedJsonError> {
+    let mut item_comments = ItemComments {
+        leading,
+        prefix_comment: non_empty(&item.prefix_comment),
+        middle_comment: non_empty(&item.middle_comment),
+        postfix_comment: non_empty(&item.postfix_comment),
+        trailing: Vec::new(),
+    };
+
+    let value = match item.item_type {
+        JsonItemType::Null => serde_json::Value::Null,
+        JsonItemType::True => serde_json::Value::Bool(true),
+        JsonItemType::False => serde_json::Value::Bool(false),
+        JsonItemType::Number | JsonItemType::String => {
+            serde_json::from_str(&item.value).map_err(|err| {
+                FracturedJsonError::new(
+                    format!("Cannot convert value: {}", err),
+                    Some(item.input_position),
+                )
+            })?
+        }
+        JsonItemType::Array => {
+            let mut elements = Vec::new();
+            let mut pending: Vec<Trivia> = Vec::new();
+            for child in &item.children {
+                if let Some(trivia) = trivia_from_item(child) {
+                    pending.push(trivia);
+                    continue;
+                }
+                let child_pointer = format!("{}/{}", pointer, elements.len());
+                let leading = std::mem::take(&mut pending);
+                elements.push(convert_item_to_value(
+                    child,
+                    child_pointer,
+                    leading,
+                    comments,
+                )?);
+            }
+            item_comments.trailing = pending;
+            serde_json::Value::Array(elements)
+        }
+        JsonItemType::Object => {
+            let mut map = serde_json::Map::new();
+            let mut pending: Vec<Trivia> = Vec::new();
+            for child in &item.children {
+                if let Some(trivia) = trivia_from_item(child) {
+                    pending.push(trivia);
+                    continue;
+                }
+                let key: String = serde_json::from_str(&child.name).map_err(|err| {
+                    FracturedJsonError::new(
+                        format!("Cannot convert property name: {}", err),
+                        Some(child.input_position),
+                    )
+                })?;
+                let child_pointer = format!("{}/{}", pointer, escape_pointer_token(&key));
+                let leading = std::mem::take(&mut pending);
+                let child_value = convert_item_to_value(child, child_pointer, leading, comments)?;
+                map.insert(key, child_value);
+            }
+            item_comments.trailing = pending;
+            serde_json::Value::Object(map)
+        }
+        JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment => {
+            return Err(FracturedJsonError::new(
+                "Unexpected comment or blank line",
+                Some(item.input_position),
+            ));
+        }
+    };
+
+    if !item_comments.is_empty() {
+        comments.insert(pointer, item_comments);
+    }
+    Ok(value)
+}
+
+fn trivia_from_item(item: &JsonItem) -> Option<Trivia> {
+    match item.item_type {
+        JsonItemType::BlankLine => Some(Trivia::BlankLine),
+        JsonItemType::BlockComment | JsonItemType::LineComment => {
+            Some(Trivia::Comment(item.value.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn non_empty(comment: &str) -> Option<String> {
+    if comment.is_empty() {
+        None
+    } else {
+        Some(comment.to_string())
+    }
+}
+
+pub fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
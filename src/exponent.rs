@@ -0,0 +1,56 @@
+use crate::model::{JsonItem, JsonItemType};
+use crate::options::ExponentStyle;
+
+/// Rewrites the exponent of every number in `doc_model` to follow `style`.
+pub fn apply_exponent_style(doc_model: &mut [JsonItem], style: ExponentStyle) {
+    if style == ExponentStyle::Preserve {
+        return;
+    }
+    for item in doc_model.iter_mut() {
+        if item.item_type == JsonItemType::Number {
+            if let Some(restyled) = restyle_exponent(&item.value, style) {
+                item.value = restyled;
+            }
+        }
+        apply_exponent_style(&mut item.children, style);
+    }
+}
+
+/// Returns `number` with its exponent marker and sign following `style`, or
+/// None if it has no exponent.
+fn restyle_exponent(number: &str, style: ExponentStyle) -> Option<String> {
+    let marker = number.find(['e', 'E'])?;
+    let mantissa = &number[..marker];
+    let exponent = &number[marker + 1..];
+    let (negative, digits) = match exponent.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, exponent.strip_prefix('+').unwrap_or(exponent)),
+    };
+
+    let (marker, plus) = match style {
+        ExponentStyle::Lowercase => ("e", ""),
+        ExponentStyle::LowercaseSigned => ("e", "+"),
+        ExponentStyle::Uppercase => ("E", ""),
+        ExponentStyle::UppercaseSigned => ("E", "+"),
+        ExponentStyle::Preserve => return None,
+    };
+    let sign = if negative { "-" } else { plus };
+    Some(format!("{}{}{}{}", mantissa, marker, sign, digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponents_are_restyled() {
+        let lower = |n| restyle_exponent(n, ExponentStyle::Lowercase);
+        assert_eq!(lower("1.5E+10").as_deref(), Some("1.5e10"));
+        assert_eq!(lower("2E-3").as_deref(), Some("2e-3"));
+        assert_eq!(lower("12.5"), None);
+
+        let upper_signed = |n| restyle_exponent(n, ExponentStyle::UppercaseSigned);
+        assert_eq!(upper_signed("1.5e10").as_deref(), Some("1.5E+10"));
+        assert_eq!(upper_signed("-4e-07").as_deref(), Some("-4E-07"));
+    }
+}
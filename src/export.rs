@@ -0,0 +1,247 @@
+use crate::convert::convert_dom_to_value;
+use crate::error::FracturedJsonError;
+use crate::model::{JsonItem, JsonItemType, TableColumnType};
+use crate::table_template::TableTemplate;
+
+/// The item at JSON Pointer `pointer` in `doc_model`, if there is one.
+pub fn find_by_pointer<'a>(doc_model: &'a [JsonItem], pointer: &str) -> Option<&'a JsonItem> {
+    let mut item = doc_model.iter().find(|item| !is_trivia(item))?;
+    if pointer.is_empty() {
+        return Some(item);
+    }
+
+    for token in pointer.strip_prefix('/')?.split('/') {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        let mut elements = item.children.iter().filter(|child| !is_trivia(child));
+        item = match item.item_type {
+            JsonItemType::Array => elements.nth(token.parse().ok()?)?,
+            JsonItemType::Object => elements.find(|child| {
+                serde_json::from_str::<String>(&child.name).is_ok_and(|key| key == token)
+            })?,
+            _ => return None,
+        };
+    }
+    Some(item)
+}
+
+/// Writes the rows of `table_root` as delimiter-separated values, with a
+/// header row naming the columns `template` found: keys for rows that are
+/// objects, positions for rows that are arrays.
+pub fn write_delimited(
+    table_root: &JsonItem,
+    template: &TableTemplate,
+    delimiter: char,
+    eol: &str,
+) -> Result<String, FracturedJsonError> {
+    let columns = table_columns(table_root, template)?;
+    let mut output = String::new();
+    for record in table_records(table_root, &columns)? {
+        write_record(&mut output, &record, delimiter, eol);
+    }
+    Ok(output)
+}
+
+/// Writes the rows of `table_root` as a GitHub-flavored Markdown table with
+/// the same columns as [`write_delimited`], padded so the pipes line up.
+/// Columns of numbers are right-aligned.
+pub fn write_markdown(
+    table_root: &JsonItem,
+    template: &TableTemplate,
+    string_length_func: &dyn Fn(&str) -> usize,
+    eol: &str,
+) -> Result<String, FracturedJsonError> {
+    let columns = table_columns(table_root, template)?;
+    let records: Vec<Vec<String>> = table_records(table_root, &columns)?
+        .into_iter()
+        .map(|record| record.iter().map(|cell| markdown_cell(cell)).collect())
+        .collect();
+    let right_aligned: Vec<bool> = columns
+        .iter()
+        .map(|(_, _, column_type)| *column_type == TableColumnType::Number)
+        .collect();
+
+    // Markdown needs at least three dashes in each separator cell.
+    let mut widths = vec![3; columns.len()];
+    for record in &records {
+        for (width, cell) in widths.iter_mut().zip(record) {
+            *width = (*width).max(string_length_func(cell));
+        }
+    }
+
+    let mut output = String::new();
+    for (i, record) in records.iter().enumerate() {
+        output.push('|');
+        for (j, cell) in record.iter().enumerate() {
+            let padding = " ".repeat(widths[j] - string_length_func(cell));
+            if right_aligned[j] {
+                output.push_str(&format!(" {}{} |", padding, cell));
+            } else {
+                output.push_str(&format!(" {}{} |", cell, padding));
+            }
+        }
+        output.push_str(eol);
+
+        if i == 0 {
+            output.push('|');
+            for (width, right) in widths.iter().zip(&right_aligned) {
+                if *right {
+                    output.push_str(&format!(" {}: |", "-".repeat(width - 1)));
+                } else {
+                    output.push_str(&format!(" {} |", "-".repeat(*width)));
+                }
+            }
+            output.push_str(eol);
+        }
+    }
+    Ok(output)
+}
+
+/// Where a column's values are found in each row.
+enum ColumnKey<'a> {
+    Name(&'a str),
+    Index(usize),
+}
+
+impl ColumnKey<'_> {
+    fn cell<'r>(&self, row: &'r JsonItem) -> Option<&'r JsonItem> {
+        let mut elements = row.children.iter().filter(|child| !is_trivia(child));
+        match self {
+            ColumnKey::Name(name) => elements.find(|child| child.name == *name),
+            ColumnKey::Index(index) => elements.nth(*index),
+        }
+    }
+}
+
+/// The header, key and type of each column of `template`, or an error if
+/// the rows of `table_root` aren't all objects or all arrays.
+fn table_columns<'t>(
+    table_root: &JsonItem,
+    template: &'t TableTemplate,
+) -> Result<Vec<(String, ColumnKey<'t>, TableColumnType)>, FracturedJsonError> {
+    let is_container = matches!(
+        table_root.item_type,
+        JsonItemType::Array | JsonItemType::Object
+    );
+    let columns = match template.column_type {
+        TableColumnType::Object if is_container => template
+            .children
+            .iter()
+            .map(|column| {
+                let name = column.location_in_parent.as_deref().unwrap_or_default();
+                let header =
+                    serde_json::from_str::<String>(name).unwrap_or_else(|_| name.to_string());
+                (header, ColumnKey::Name(name), column.column_type)
+            })
+            .collect(),
+        TableColumnType::Array if is_container => template
+            .children
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                (
+                    index.to_string(),
+                    ColumnKey::Index(index),
+                    column.column_type,
+                )
+            })
+            .collect(),
+        _ => {
+            return Err(FracturedJsonError::new(
+                "Value isn't tabular: its elements must be all objects or all arrays",
+                Some(table_root.input_position),
+            ))
+        }
+    };
+    Ok(columns)
+}
+
+/// The header record followed by a record for each row of `table_root`.
+fn table_records(
+    table_root: &JsonItem,
+    columns: &[(String, ColumnKey, TableColumnType)],
+) -> Result<Vec<Vec<String>>, FracturedJsonError> {
+    let mut records = vec![columns
+        .iter()
+        .map(|(header, _, _)| header.clone())
+        .collect()];
+    for row in table_root.children.iter().filter(|row| !is_trivia(row)) {
+        let mut record = Vec::with_capacity(columns.len());
+        for (_, key, _) in columns {
+            let cell = match key.cell(row) {
+                Some(cell) => cell_text(cell)?,
+                None => String::new(),
+            };
+            record.push(cell);
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// `text` made safe for a Markdown table cell, which can't hold pipes or
+/// line breaks.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\r', '\n'], "<br>")
+}
+
+/// The text of a cell: strings unescaped, nulls empty, and arrays and
+/// objects as minified JSON.
+fn cell_text(item: &JsonItem) -> Result<String, FracturedJsonError> {
+    let text = match item.item_type {
+        JsonItemType::Null => String::new(),
+        JsonItemType::String => {
+            serde_json::from_str::<String>(&item.value).unwrap_or_else(|_| item.value.clone())
+        }
+        JsonItemType::Array | JsonItemType::Object => {
+            let (value, _) = convert_dom_to_value(std::slice::from_ref(item))?;
+            value.to_string()
+        }
+        _ => item.value.clone(),
+    };
+    Ok(text)
+}
+
+fn write_record(output: &mut String, fields: &[String], delimiter: char, eol: &str) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            output.push(delimiter);
+        }
+        let needs_quotes = field.contains([delimiter, '"', '\n', '\r']);
+        if needs_quotes {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+    output.push_str(eol);
+}
+
+fn is_trivia(item: &JsonItem) -> bool {
+    matches!(
+        item.item_type,
+        JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_quoted_when_needed() {
+        let mut output = String::new();
+        let fields = ["plain", "a,b", "say \"hi\"", "two\nlines"].map(String::from);
+        write_record(&mut output, &fields, ',', "\n");
+        assert_eq!(output, "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\"\n");
+    }
+
+    #[test]
+    fn markdown_cells_are_escaped() {
+        assert_eq!(markdown_cell("a|b"), "a\\|b");
+        assert_eq!(markdown_cell("one\r\ntwo\nthree"), "one<br>two<br>three");
+    }
+}
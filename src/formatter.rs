@@ -1,12 +1,37 @@
+use std::io::Write;
 use std::sync::Arc;
 
+use unicode_width::UnicodeWidthStr;
+
 use crate::buffer::{PaddedFormattingTokens, StringJoinBuffer};
-use crate::convert::convert_value_to_dom;
+use crate::comment_spacing::{
+    apply_block_middle_comments, apply_comment_spacing, apply_comment_style, as_block_comments,
+};
+use crate::compare::OptionsComparison;
+use crate::convert::{convert_dom_to_value, convert_value_to_dom};
 use crate::error::FracturedJsonError;
-use crate::model::{BracketPaddingType, JsonItem, JsonItemType, TableColumnType};
-use crate::options::{FracturedJsonOptions, TableCommaPlacement};
+use crate::exponent::apply_exponent_style;
+use crate::export::{find_by_pointer, write_delimited, write_markdown};
+use crate::hex::apply_hex_integers;
+use crate::key_case::apply_key_case;
+use crate::key_order::apply_key_order;
+use crate::model::{BracketPaddingType, CommentMap, JsonItem, JsonItemType, TableColumnType};
+use crate::notation::{apply_number_format, apply_number_notation, NumberFormatFn};
+use crate::options::{
+    CellAlignment, FracturedJsonOptions, LayoutHint, StringColumnAlignment, TableCommaPlacement,
+};
 use crate::parser::Parser;
-use crate::table_template::TableTemplate;
+use crate::round_trip::find_round_trip_difference;
+use crate::table_info::TableInfo;
+use crate::table_template::{
+    has_value_wider_than, matching_key_set_percent, sorted_table_rows, ClassifyFn, NumberWidth,
+    NumberWidthFn, StringClassifier, TableTemplate,
+};
+use crate::trace::{FormatDecision, FormatTrace, TraceRecorder};
+use crate::trailing_zeros::apply_trim_trailing_zeros;
+use crate::truncate::apply_string_truncation;
+use crate::type_skeleton::{render_type_skeleton, SkeletonLanguage};
+use crate::unicode_escape::{apply_ascii_only, apply_normalize_escapes, apply_unescape_unicode};
 
 /// The main JSON formatter.
 ///
@@ -34,6 +59,8 @@ use crate::table_template::TableTemplate;
 /// A single `Formatter` instance can be reused for multiple formatting operations.
 /// The configuration in `options` persists across calls, but internal buffers are
 /// reset for each operation.
+/// State derived from the options is kept between calls and rebuilt only when
+/// the options change; [`Formatter::with_options`] builds it up front.
 pub struct Formatter {
     /// Configuration options that control formatting behavior.
     /// Modify these before calling formatting methods.
@@ -57,8 +84,127 @@ pub struct Formatter {
     /// formatter.string_length_func = Arc::new(|s: &str| s.chars().count());
     /// ```
     pub string_length_func: Arc<dyn Fn(&str) -> usize + Send + Sync>,
+
+    /// Function that supplies part of each output line's prefix, written
+    /// after `prefix_string` when formatting.
+    ///
+    /// It's called with the line's number (counting from 0) and the depth of
+    /// what's on the line, so it can write line-number gutters or markers that
+    /// change with nesting. Its output doesn't count towards
+    /// `max_total_line_length`, and it can't be combined with
+    /// `verify_round_trip`. Minified output has no prefix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    /// use std::sync::Arc;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.options.max_total_line_length = 10;
+    /// formatter.line_prefix_func = Some(Arc::new(|line, _depth| format!("{:>2} | ", line + 1)));
+    /// let output = formatter.reformat(r#"{"a":1,"b":2}"#, 0).unwrap();
+    ///
+    /// assert_eq!(output, " 1 | {\n 2 |     \"a\": 1,\n 3 |     \"b\": 2\n 4 | }\n");
+    /// ```
+    pub line_prefix_func: Option<Arc<dyn Fn(usize, usize) -> String + Send + Sync>>,
+
+    /// Function that picks how strings in table columns are aligned, for
+    /// values such as `"12.5 kg"`, UUIDs or hex colors that deserve more than
+    /// `string_column_alignment`.
+    ///
+    /// It's called with each unescaped string in a table column. A column
+    /// whose strings all get the same [`CellAlignment`] is aligned that way;
+    /// strings classed as [`CellAlignment::Number`] are aligned with the
+    /// column's numbers. When it returns `None`, or the strings of a column
+    /// disagree, the column is aligned as usual.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{CellAlignment, Formatter};
+    /// use std::sync::Arc;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.options.max_total_line_length = 30;
+    /// formatter.string_cell_classifier = Some(Arc::new(|value: &str| {
+    ///     value.ends_with(" kg").then_some(CellAlignment::Right)
+    /// }));
+    /// let output = formatter
+    ///     .reformat(r#"[{"w": "2 kg", "n": 1}, {"w": "12.5 kg", "n": 2}]"#, 0)
+    ///     .unwrap();
+    ///
+    /// assert!(output.contains("{\"w\":    \"2 kg\", \"n\": 1}"));
+    /// ```
+    pub string_cell_classifier: Option<ClassifyFn>,
+
+    /// Function that rewrites the text of each number before layout, for
+    /// house rules the options don't cover. It's called after
+    /// `number_notation`, `exponent_style` and `trim_trailing_zeros` have
+    /// been applied, and must return a valid JSON number.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    /// use std::sync::Arc;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.number_format_func = Some(Arc::new(|number: &str| number.replace("e+", "E")));
+    /// let output = formatter.reformat(r#"[1.5e+3, 2]"#, 0).unwrap();
+    ///
+    /// assert_eq!(output, "[1.5E3, 2]\n");
+    /// ```
+    pub number_format_func: Option<NumberFormatFn>,
+
+    /// Function giving the width numbers will have once the output is
+    /// post-processed, for example by inserting digit grouping for display.
+    /// Table and compact array columns are padded for those widths, so they
+    /// stay aligned once the separators are in. It's called with whole
+    /// numbers and with their integer parts; the separators must only go in
+    /// the integer part. When `None`, numbers are measured as written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    /// use std::sync::Arc;
+    ///
+    /// // Room for a comma between each group of three digits.
+    /// fn grouped_width(number: &str) -> usize {
+    ///     let digits = number
+    ///         .trim_start_matches('-')
+    ///         .split(['.', 'e', 'E'])
+    ///         .next()
+    ///         .unwrap_or_default()
+    ///         .len();
+    ///     number.len() + digits.saturating_sub(1) / 3
+    /// }
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.options.max_total_line_length = 20;
+    /// formatter.number_width_func = Some(Arc::new(grouped_width));
+    /// let output = formatter.reformat("[[1234567, 1], [8, 2]]", 0).unwrap();
+    ///
+    /// // Once grouped, 1,234,567 lines up with the padded 8.
+    /// assert!(output.contains("[1234567, 1],\n    [        8, 2]"));
+    /// ```
+    pub number_width_func: Option<NumberWidthFn>,
     buffer: StringJoinBuffer,
     pads: PaddedFormattingTokens,
+    /// The options and length function `pads` was built from.
+    pads_options: FracturedJsonOptions,
+    pads_length_func: Arc<dyn Fn(&str) -> usize + Send + Sync>,
+    /// Collects formatting events during `reformat_with_trace`.
+    trace: Option<TraceRecorder>,
+    /// Line length the layout being tried has to fit in: the preferred length
+    /// on a first attempt, the hard maximum otherwise.
+    line_length_limit: usize,
+    /// Number of expanded containers being written, each of which needs lines
+    /// for its closing bracket and possibly an elision marker.
+    open_containers: usize,
+    /// Whether elements have been left out under `max_output_lines`.
+    output_elided: bool,
 }
 
 impl Default for Formatter {
@@ -78,18 +224,61 @@ impl Formatter {
     /// let formatter = Formatter::new();
     /// ```
     pub fn new() -> Self {
-        let options = FracturedJsonOptions::default();
+        Self::with_options(FracturedJsonOptions::default())
+    }
+
+    /// Creates a new `Formatter` with the given options.
+    ///
+    /// Everything derived from the options is built here rather than on the
+    /// first formatting call, so latency-sensitive callers can pay that cost
+    /// at startup. It is rebuilt only when the options or
+    /// `string_length_func` change.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{Formatter, FracturedJsonOptions};
+    ///
+    /// let formatter = Formatter::with_options(FracturedJsonOptions {
+    ///     indent_spaces: 2,
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(formatter.options().indent_spaces, 2);
+    /// ```
+    pub fn with_options(options: FracturedJsonOptions) -> Self {
         let string_length_func: Arc<dyn Fn(&str) -> usize + Send + Sync> =
             Arc::new(Self::string_length_by_char_count);
         let pads = PaddedFormattingTokens::new(&options, string_length_func.as_ref());
         Self {
+            line_length_limit: options.max_total_line_length,
+            pads_options: options.clone(),
+            pads_length_func: string_length_func.clone(),
             options,
             string_length_func,
+            line_prefix_func: None,
+            string_cell_classifier: None,
+            number_format_func: None,
+            number_width_func: None,
             buffer: StringJoinBuffer::default(),
             pads,
+            trace: None,
+            open_containers: 0,
+            output_elided: false,
         }
     }
 
+    /// Returns the current options.
+    pub fn options(&self) -> &FracturedJsonOptions {
+        &self.options
+    }
+
+    /// Replaces the options, rebuilding everything derived from them right
+    /// away instead of on the next formatting call.
+    pub fn set_options(&mut self, options: FracturedJsonOptions) {
+        self.options = options;
+        self.refresh_pads();
+    }
+
     /// Default string length function that counts Unicode characters.
     ///
     /// This is the default implementation used for calculating display widths.
@@ -99,6 +288,31 @@ impl Formatter {
         value.chars().count()
     }
 
+    /// String length function that measures display width in terminal
+    /// columns, counting East Asian wide characters (such as CJK ideographs)
+    /// as two and combining marks as zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    /// use std::sync::Arc;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.string_length_func = Arc::new(Formatter::string_length_by_display_width);
+    ///
+    /// assert_eq!(Formatter::string_length_by_display_width("孫子"), 4);
+    /// ```
+    pub fn string_length_by_display_width(value: &str) -> usize {
+        UnicodeWidthStr::width(value)
+    }
+
+    /// String length function that counts UTF-8 bytes. Useful when line
+    /// lengths are limited in bytes rather than in visible characters.
+    pub fn string_length_by_byte_count(value: &str) -> usize {
+        value.len()
+    }
+
     /// Reformats JSON text according to the current options.
     ///
     /// Parses the input JSON and produces formatted output with proper indentation,
@@ -130,24 +344,38 @@ impl Formatter {
         starting_depth: usize,
     ) -> Result<String, FracturedJsonError> {
         let parser = Parser::new(self.options.clone());
-        let mut doc_model = parser.parse_top_level(json_text, true)?;
-        self.format_top_level(&mut doc_model, starting_depth);
+        let mut doc_model = match parser.parse_top_level(json_text, true) {
+            Ok(doc_model) => doc_model,
+            Err(_) if self.options.auto_detect_jsonl && Self::is_jsonl(&parser, json_text) => {
+                return self.reformat_jsonl(json_text);
+            }
+            Err(err) => return Err(err),
+        };
+        self.format_top_level(&mut doc_model, starting_depth)?;
         self.buffer.flush();
-        Ok(self.buffer.as_string())
+        self.finish_output(&doc_model, true, true)
     }
 
-    /// Minifies JSON text by removing all unnecessary whitespace.
+    /// Reformats JSON text like [`reformat`](Self::reformat), writing the
+    /// output to `writer`.
     ///
-    /// Produces the most compact valid JSON representation of the input.
-    /// Comments are handled according to `options.comment_policy`.
+    /// Each top-level element is written as soon as it's formatted, and JSON
+    /// Lines input one line at a time, so a large JSONL file never has to be
+    /// held in memory as output. A single JSON value is still formatted in
+    /// full before it's written, since its lines can change until it's
+    /// finished. With `verify_round_trip` set, nothing is written until the
+    /// whole output has been checked. If a JSONL line fails to parse, the
+    /// lines before it have already been written.
     ///
     /// # Arguments
     ///
-    /// * `json_text` - The JSON string to minify
+    /// * `json_text` - The JSON string to format
+    /// * `starting_depth` - Initial indentation depth (usually 0)
+    /// * `writer` - Where to write the output
     ///
     /// # Returns
     ///
-    /// The minified JSON string, or an error if parsing fails.
+    /// An error if parsing fails or the output can't be written.
     ///
     /// # Example
     ///
@@ -155,203 +383,763 @@ impl Formatter {
     /// use fracturedjson::Formatter;
     ///
     /// let mut formatter = Formatter::new();
-    /// let input = r#"{
-    ///     "name": "Alice",
-    ///     "age": 30
-    /// }"#;
+    /// let mut output = Vec::new();
+    /// formatter
+    ///     .reformat_to_writer(r#"{"name":"Alice","age":30}"#, 0, &mut output)
+    ///     .unwrap();
     ///
-    /// let output = formatter.minify(input).unwrap();
-    /// assert_eq!(output, r#"{"name":"Alice","age":30}"#);
+    /// assert_eq!(output, b"{\"name\": \"Alice\", \"age\": 30}\n");
     /// ```
-    pub fn minify(&mut self, json_text: &str) -> Result<String, FracturedJsonError> {
+    pub fn reformat_to_writer<W: Write>(
+        &mut self,
+        json_text: &str,
+        starting_depth: usize,
+        mut writer: W,
+    ) -> Result<(), FracturedJsonError> {
+        let parser = Parser::new(self.options.clone());
+        let mut doc_model = match parser.parse_top_level(json_text, true) {
+            Ok(doc_model) => doc_model,
+            Err(_) if self.options.auto_detect_jsonl && Self::is_jsonl(&parser, json_text) => {
+                self.reformat_jsonl_with(json_text, |text| Self::write_text(&mut writer, text))?;
+                return writer.flush().map_err(Self::write_error);
+            }
+            Err(err) => return Err(err),
+        };
+        self.format_top_level_with(&mut doc_model, starting_depth, |formatter| {
+            formatter.write_finished_lines(&mut writer)
+        })?;
+        self.buffer.flush();
+        self.write_output(&doc_model, true, true, &mut writer)
+    }
+
+    /// Reformats JSON text like [`reformat`](Self::reformat), and also reports
+    /// which output lines each item was written to and how it was laid out.
+    ///
+    /// Useful for tools that map the output back to the data, for example to
+    /// make lines clickable or containers foldable. Numbers whose value was
+    /// changed on the way, for example rounded by
+    /// `normalize_max_decimal_places`, are listed in `number_changes`. JSON
+    /// Lines input is not detected, even with `auto_detect_jsonl` set.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_text` - The JSON text to reformat
+    /// * `starting_depth` - Initial indentation depth (usually 0)
+    ///
+    /// # Returns
+    ///
+    /// The output with its events, or an error if parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{FormatDecision, Formatter};
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.options.max_inline_complexity = 1;
+    /// let trace = formatter
+    ///     .reformat_with_trace(r#"{"a": [1, 2], "b": true}"#, 0)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(trace.output, "{\n    \"a\": [1, 2],\n    \"b\": true\n}\n");
+    /// assert_eq!(trace.line_paths[1], vec!["/a"]);
+    /// assert_eq!(trace.events[0].path, "");
+    /// assert_eq!(trace.events[0].decision, FormatDecision::Expanded);
+    /// ```
+    pub fn reformat_with_trace(
+        &mut self,
+        json_text: &str,
+        starting_depth: usize,
+    ) -> Result<FormatTrace, FracturedJsonError> {
         let parser = Parser::new(self.options.clone());
         let mut doc_model = parser.parse_top_level(json_text, true)?;
-        self.minify_top_level(&mut doc_model);
+
+        self.trace = Some(TraceRecorder::default());
+        let formatted = self.format_top_level(&mut doc_model, starting_depth);
+        let recorder = self.trace.take().unwrap_or_default();
+        formatted?;
         self.buffer.flush();
-        Ok(self.buffer.as_string())
+        let output = self.finish_output(&doc_model, true, true)?;
+        Ok(recorder.finish(output))
     }
 
-    /// Reformats JSONL (JSON Lines) input where each line is a separate JSON value.
+    /// Exports an array or object of uniform rows as CSV.
     ///
-    /// Each line is independently parsed and formatted. Empty lines are preserved.
-    /// The output maintains the line structure: one formatted JSON per line.
+    /// `pointer` is the JSON Pointer of the container (`""` for the whole
+    /// document), whose elements must be all objects or all arrays. The first
+    /// line names the columns the table formatter finds: the keys of object
+    /// rows, in `table_column_order`, or the positions of array rows. Strings
+    /// are unescaped, nulls and missing values are empty, and nested arrays
+    /// and objects are written as minified JSON. Values are exported as they
+    /// are in the input, whatever options such as `max_string_display_length`
+    /// or `number_notation` say; `key_case` and `key_order` do apply. Lines end
+    /// with the `json_eol_style` line ending.
     ///
     /// # Arguments
     ///
-    /// * `jsonl_text` - The JSONL string to format (one JSON value per line)
+    /// * `json_text` - The JSON text holding the rows
+    /// * `pointer` - The JSON Pointer of the rows' container
     ///
     /// # Returns
     ///
-    /// The formatted JSONL string, or an error if any line fails to parse.
-    /// The error will indicate which line failed.
+    /// The CSV text, or an error if parsing fails or there are no such rows.
     ///
     /// # Example
     ///
     /// ```rust
     /// use fracturedjson::Formatter;
     ///
-    /// let input = r#"{"a":1}
-    /// {"b":2}
-    /// {"c":3}"#;
-    ///
     /// let mut formatter = Formatter::new();
-    /// let output = formatter.reformat_jsonl(input).unwrap();
+    /// let csv = formatter
+    ///     .to_csv(r#"{"users": [{"id": 1, "name": "Ann"}, {"id": 2}]}"#, "/users")
+    ///     .unwrap();
     ///
-    /// // Each line is formatted independently
-    /// assert!(output.contains("\"a\": 1"));
+    /// assert_eq!(csv, "id,name\n1,Ann\n2,\n");
     /// ```
-    pub fn reformat_jsonl(&mut self, jsonl_text: &str) -> Result<String, FracturedJsonError> {
-        let mut output_lines = Vec::new();
+    pub fn to_csv(&mut self, json_text: &str, pointer: &str) -> Result<String, FracturedJsonError> {
+        self.export_delimited(json_text, pointer, ',')
+    }
 
-        for (line_num, line) in jsonl_text.lines().enumerate() {
-            // Preserve empty lines
-            if line.trim().is_empty() {
-                output_lines.push(String::new());
-                continue;
-            }
+    /// Exports an array or object of uniform rows as tab-separated values.
+    /// See [`to_csv`](Self::to_csv), which this matches apart from the
+    /// delimiter.
+    pub fn to_tsv(&mut self, json_text: &str, pointer: &str) -> Result<String, FracturedJsonError> {
+        self.export_delimited(json_text, pointer, '\t')
+    }
 
-            // Format the line
-            let formatted = self.reformat(line, 0).map_err(|e| {
-                FracturedJsonError::simple(format!("line {}: {}", line_num + 1, e))
-            })?;
+    /// Exports an array or object of uniform rows as a GitHub-flavored
+    /// Markdown table, with the pipes lined up and columns of numbers
+    /// right-aligned. Columns and cells are as in [`to_csv`](Self::to_csv),
+    /// so display options don't change the values; pipes in cells are escaped
+    /// and line breaks become `<br>`. Widths are measured with
+    /// `string_length_func`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let table = formatter
+    ///     .to_markdown_table(r#"[{"id": 1, "name": "Ann"}, {"id": 22, "name": "Bo"}]"#, "")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     table,
+    ///     "|  id | name |\n| --: | ---- |\n|   1 | Ann  |\n|  22 | Bo   |\n"
+    /// );
+    /// ```
+    pub fn to_markdown_table(
+        &mut self,
+        json_text: &str,
+        pointer: &str,
+    ) -> Result<String, FracturedJsonError> {
+        self.export_table(json_text, pointer, |table_root, template, formatter| {
+            write_markdown(
+                table_root,
+                template,
+                formatter.string_length_func.as_ref(),
+                formatter.pads.eol(),
+            )
+        })
+    }
 
-            // Remove trailing newline since we add our own
-            output_lines.push(formatted.trim_end().to_string());
-        }
+    fn export_delimited(
+        &mut self,
+        json_text: &str,
+        pointer: &str,
+        delimiter: char,
+    ) -> Result<String, FracturedJsonError> {
+        self.export_table(json_text, pointer, |table_root, template, formatter| {
+            write_delimited(table_root, template, delimiter, formatter.pads.eol())
+        })
+    }
 
-        // Join with newlines and add trailing newline
-        let mut result = output_lines.join("\n");
-        if !result.is_empty() {
-            result.push('\n');
-        }
-        Ok(result)
+    /// Finds the container at `pointer` in `json_text`, measures its columns
+    /// and passes both to `write`.
+    fn export_table(
+        &mut self,
+        json_text: &str,
+        pointer: &str,
+        write: impl FnOnce(&JsonItem, &TableTemplate, &Self) -> Result<String, FracturedJsonError>,
+    ) -> Result<String, FracturedJsonError> {
+        let parser = Parser::new(self.options.clone());
+        let mut doc_model = parser.parse_top_level(json_text, true)?;
+        self.refresh_pads();
+        // Only the keys are rewritten: options that change values for display
+        // would change the exported data.
+        self.rewrite_keys(&mut doc_model)?;
+
+        let table_root = find_by_pointer(&doc_model, pointer).ok_or_else(|| {
+            FracturedJsonError::simple(format!("No value found at '{}'", pointer))
+        })?;
+        let mut template =
+            TableTemplate::new(self.pads.clone(), self.options.number_list_alignment);
+        template.measure_table_columns(table_root);
+        template.order_columns(
+            self.options.table_column_order,
+            &self.options.table_column_keys,
+        );
+        write(table_root, &template, self)
     }
 
-    /// Minifies JSONL (JSON Lines) input where each line is a separate JSON value.
+    /// Reports the tables the formatter lays out in JSON text: where each
+    /// one is, its rows, and the names, kinds and widths of its columns.
     ///
-    /// Each line is independently parsed and minified. Empty lines are preserved.
+    /// The tables are those [`reformat`](Self::reformat) would write with the
+    /// current options, so tools such as CSV exporters or UI grids can reuse
+    /// the formatter's column structure.
     ///
     /// # Arguments
     ///
-    /// * `jsonl_text` - The JSONL string to minify (one JSON value per line)
+    /// * `json_text` - The JSON text to analyze
     ///
     /// # Returns
     ///
-    /// The minified JSONL string, or an error if any line fails to parse.
+    /// The tables in document order, or an error if parsing fails.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use fracturedjson::Formatter;
-    ///
-    /// let input = r#"{ "a": 1 }
-    /// { "b": 2 }"#;
+    /// use fracturedjson::{Formatter, TableColumnKind};
     ///
     /// let mut formatter = Formatter::new();
-    /// let output = formatter.minify_jsonl(input).unwrap();
-    ///
-    /// assert!(output.contains(r#"{"a":1}"#));
+    /// formatter.options.max_total_line_length = 40;
+    /// let tables = formatter
+    ///     .analyze(r#"{"users": [{"id": 1, "name": "Ann"}, {"id": 22, "name": "Bob"}]}"#)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(tables[0].path, "/users");
+    /// assert_eq!(tables[0].row_count, 2);
+    /// assert_eq!(tables[0].columns[0].name.as_deref(), Some("id"));
+    /// assert_eq!(tables[0].columns[0].kind, TableColumnKind::Number);
     /// ```
-    pub fn minify_jsonl(&mut self, jsonl_text: &str) -> Result<String, FracturedJsonError> {
-        let mut output_lines = Vec::new();
-
-        for (line_num, line) in jsonl_text.lines().enumerate() {
-            // Preserve empty lines
-            if line.trim().is_empty() {
-                output_lines.push(String::new());
-                continue;
-            }
-
-            // Minify the line
-            let minified = self.minify(line).map_err(|e| {
-                FracturedJsonError::simple(format!("line {}: {}", line_num + 1, e))
-            })?;
-
-            // Remove trailing newline since we add our own
-            output_lines.push(minified.trim_end().to_string());
-        }
+    pub fn analyze(&mut self, json_text: &str) -> Result<Vec<TableInfo>, FracturedJsonError> {
+        let parser = Parser::new(self.options.clone());
+        let mut doc_model = parser.parse_top_level(json_text, true)?;
 
-        // Join with newlines and add trailing newline
-        let mut result = output_lines.join("\n");
-        if !result.is_empty() {
-            result.push('\n');
-        }
-        Ok(result)
+        self.trace = Some(TraceRecorder::default());
+        let formatted = self.format_top_level(&mut doc_model, 0);
+        let recorder = self.trace.take().unwrap_or_default();
+        self.buffer = StringJoinBuffer::default();
+        formatted?;
+        Ok(recorder.into_tables())
     }
 
-    /// Formats a [`serde_json::Value`] according to the current options.
+    /// Reformats a stream of concatenated top-level JSON documents.
     ///
-    /// This is useful when you already have parsed JSON data and want to
-    /// format it without going through text parsing again.
+    /// Unlike [`reformat`](Self::reformat), which rejects input containing more
+    /// than one top-level value, this accepts any number of documents separated
+    /// by whitespace or newlines (e.g. `{"a":1} {"b":2}`). Each document is
+    /// formatted independently and emitted back-to-back, each starting on its
+    /// own line.
     ///
     /// # Arguments
     ///
-    /// * `value` - The JSON value to format
+    /// * `json_text` - The concatenated JSON documents to format
     /// * `starting_depth` - Initial indentation depth (usually 0)
-    /// * `recursion_limit` - Maximum nesting depth to prevent stack overflow
     ///
     /// # Returns
     ///
-    /// The formatted JSON string, or an error if the recursion limit is exceeded.
+    /// The formatted documents, or an error if any of them fails to parse.
     ///
     /// # Example
     ///
     /// ```rust
     /// use fracturedjson::Formatter;
-    /// use serde_json::json;
     ///
     /// let mut formatter = Formatter::new();
-    /// let value = json!({"name": "Alice", "scores": [95, 87, 92]});
+    /// let output = formatter.reformat_stream(r#"{"a":1} [1,2]"#, 0).unwrap();
     ///
-    /// let output = formatter.serialize_value(&value, 0, 100).unwrap();
+    /// assert_eq!(output, "{\"a\": 1}\n[1, 2]\n");
     /// ```
-    pub fn serialize_value(
+    pub fn reformat_stream(
         &mut self,
-        value: &serde_json::Value,
+        json_text: &str,
         starting_depth: usize,
-        recursion_limit: usize,
     ) -> Result<String, FracturedJsonError> {
-        let doc_model = convert_value_to_dom(value, None, recursion_limit)?;
-        let mut doc_list = Vec::new();
-        if let Some(item) = doc_model {
-            doc_list.push(item);
-        }
-        self.format_top_level(&mut doc_list, starting_depth);
+        let parser = Parser::new(self.options.clone());
+        let mut doc_model = parser.parse_top_level(json_text, false)?;
+        self.format_top_level(&mut doc_model, starting_depth)?;
         self.buffer.flush();
-        Ok(self.buffer.as_string())
+        self.finish_output(&doc_model, false, true)
     }
 
-    /// Serializes any [`serde::Serialize`] type to formatted JSON.
+    /// Reformats JSON text for insertion into an already-indented context.
     ///
-    /// This is the most convenient method for formatting Rust data structures.
-    /// The value is first converted to a `serde_json::Value`, then formatted.
+    /// Every output line, including the closing brackets, starts with
+    /// `indent_prefix` (ahead of any configured `prefix_string`), and its width
+    /// is subtracted from `max_total_line_length`. This is the usual case when
+    /// embedding JSON in generated source code.
     ///
     /// # Arguments
     ///
-    /// * `value` - Any value implementing `Serialize`
-    /// * `starting_depth` - Initial indentation depth (usually 0)
-    /// * `recursion_limit` - Maximum nesting depth to prevent stack overflow
+    /// * `json_text` - The JSON string to format
+    /// * `indent_prefix` - The indentation of the surrounding context
     ///
     /// # Returns
     ///
-    /// The formatted JSON string, or an error if serialization fails.
+    /// The formatted JSON string, or an error if parsing fails or
+    /// `indent_prefix` contains a line break.
     ///
     /// # Example
     ///
     /// ```rust
     /// use fracturedjson::Formatter;
-    /// use serde::Serialize;
-    ///
-    /// #[derive(Serialize)]
-    /// struct Person {
-    ///     name: String,
-    ///     age: u32,
-    /// }
-    ///
-    /// let person = Person {
-    ///     name: "Alice".into(),
-    ///     age: 30,
-    /// };
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.options.max_total_line_length = 20;
+    /// let output = formatter
+    ///     .reformat_fragment(r#"{"name":"Alice","age":30}"#, "    ")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     output,
+    ///     "    {\n        \"name\": \"Alice\",\n        \"age\": 30\n    }\n"
+    /// );
+    /// ```
+    pub fn reformat_fragment(
+        &mut self,
+        json_text: &str,
+        indent_prefix: &str,
+    ) -> Result<String, FracturedJsonError> {
+        let original_prefix = self.options.prefix_string.clone();
+        self.options.prefix_string = format!("{}{}", indent_prefix, original_prefix);
+        let result = self.reformat(json_text, 0);
+        self.options.prefix_string = original_prefix;
+        result
+    }
+
+    /// Minifies JSON text by removing all unnecessary whitespace.
+    ///
+    /// Produces the most compact valid JSON representation of the input.
+    /// Comments are handled according to `options.comment_policy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_text` - The JSON string to minify
+    ///
+    /// # Returns
+    ///
+    /// The minified JSON string, or an error if parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let input = r#"{
+    ///     "name": "Alice",
+    ///     "age": 30
+    /// }"#;
+    ///
+    /// let output = formatter.minify(input).unwrap();
+    /// assert_eq!(output, r#"{"name":"Alice","age":30}"#);
+    /// ```
+    pub fn minify(&mut self, json_text: &str) -> Result<String, FracturedJsonError> {
+        let parser = Parser::new(self.options.clone());
+        let mut doc_model = parser.parse_top_level(json_text, true)?;
+        self.minify_top_level(&mut doc_model)?;
+        self.buffer.flush();
+        self.finish_output(&doc_model, true, false)
+    }
+
+    /// Minifies JSON text like [`minify`](Self::minify), writing the output
+    /// to `writer` without first building it as one string.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_text` - The JSON string to minify
+    /// * `writer` - Where to write the output
+    ///
+    /// # Returns
+    ///
+    /// An error if parsing fails or the output can't be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let mut output = Vec::new();
+    /// formatter.minify_to_writer("[1, 2, 3]", &mut output).unwrap();
+    ///
+    /// assert_eq!(output, b"[1,2,3]");
+    /// ```
+    pub fn minify_to_writer<W: Write>(
+        &mut self,
+        json_text: &str,
+        mut writer: W,
+    ) -> Result<(), FracturedJsonError> {
+        let parser = Parser::new(self.options.clone());
+        let mut doc_model = parser.parse_top_level(json_text, true)?;
+        self.minify_top_level(&mut doc_model)?;
+        self.buffer.flush();
+        self.write_output(&doc_model, true, false, &mut writer)
+    }
+
+    /// Reformats JSONL (JSON Lines) input where each line is a separate JSON value.
+    ///
+    /// Each line is independently parsed and formatted. Empty lines are preserved.
+    /// The output maintains the line structure: one formatted JSON per line.
+    ///
+    /// # Arguments
+    ///
+    /// * `jsonl_text` - The JSONL string to format (one JSON value per line)
+    ///
+    /// # Returns
+    ///
+    /// The formatted JSONL string, or an error if any line fails to parse.
+    /// The error will indicate which line failed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    ///
+    /// let input = r#"{"a":1}
+    /// {"b":2}
+    /// {"c":3}"#;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let output = formatter.reformat_jsonl(input).unwrap();
+    ///
+    /// // Each line is formatted independently
+    /// assert!(output.contains("\"a\": 1"));
+    /// ```
+    pub fn reformat_jsonl(&mut self, jsonl_text: &str) -> Result<String, FracturedJsonError> {
+        let mut output = String::new();
+        self.reformat_jsonl_with(jsonl_text, |text| {
+            output.push_str(text);
+            Ok(())
+        })?;
+        Ok(output)
+    }
+
+    /// Reformats JSONL like [`reformat_jsonl`](Self::reformat_jsonl), handing
+    /// the output to `write` piece by piece as each line is formatted.
+    fn reformat_jsonl_with(
+        &mut self,
+        jsonl_text: &str,
+        mut write: impl FnMut(&str) -> Result<(), FracturedJsonError>,
+    ) -> Result<(), FracturedJsonError> {
+        if self.options.jsonl_shared_column_widths {
+            if let Some(output) = self.reformat_jsonl_as_table(jsonl_text)? {
+                return write(&output);
+            }
+        }
+
+        let mut is_empty = true;
+        for (line_num, line) in jsonl_text.lines().enumerate() {
+            if line_num > 0 {
+                write("\n")?;
+                is_empty = false;
+            }
+
+            // Preserve empty lines
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // Format the line
+            let formatted = self.reformat_jsonl_line(line).map_err(|e| {
+                FracturedJsonError::simple(format!("line {}: {}", line_num + 1, e))
+            })?;
+
+            // Remove trailing newline since we add our own
+            let formatted = formatted.trim_end();
+            is_empty &= formatted.is_empty();
+            write(formatted)?;
+        }
+
+        // Add trailing newline
+        if !is_empty && self.options.ensure_final_newline != Some(false) {
+            write("\n")?;
+        }
+        Ok(())
+    }
+
+    /// Formats the lines of `jsonl_text` as the rows of a single table, so
+    /// their columns line up from line to line. Returns None if the lines
+    /// don't make a table that fits within `max_total_line_length`.
+    fn reformat_jsonl_as_table(
+        &mut self,
+        jsonl_text: &str,
+    ) -> Result<Option<String>, FracturedJsonError> {
+        self.validate_options()?;
+        self.refresh_pads();
+        self.line_length_limit = self.options.max_total_line_length;
+
+        let mut table_root = JsonItem {
+            item_type: JsonItemType::Array,
+            ..Default::default()
+        };
+        let mut blank_lines = Vec::new();
+        for (line_num, line) in jsonl_text.lines().enumerate() {
+            blank_lines.push(line.trim().is_empty());
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut doc_model = self
+                .parse_jsonl_line(line)
+                .map_err(|e| FracturedJsonError::simple(format!("line {}: {}", line_num + 1, e)))?;
+            self.rewrite_document(&mut doc_model)?;
+            match doc_model.pop() {
+                Some(mut row) if !Self::is_comment_or_blank_line(row.item_type) => {
+                    self.compute_item_lengths(&mut row, 0);
+                    table_root.complexity = table_root.complexity.max(row.complexity + 1);
+                    table_root.children.push(row);
+                }
+                // A line of nothing but comments can't be a row.
+                _ => return Ok(None),
+            }
+        }
+
+        if (table_root.complexity as isize) > self.options.max_table_row_complexity + 1 {
+            return Ok(None);
+        }
+        let mut template = self.new_table_template(self.pads.clone());
+        template.measure_table_root(&table_root, true);
+        template.order_columns(
+            self.options.table_column_order,
+            &self.options.table_column_keys,
+        );
+        let is_table = matches!(
+            template.column_type,
+            TableColumnType::Array | TableColumnType::Object
+        ) && !template.requires_multiple_lines;
+        if !is_table || !template.try_to_fit(self.available_line_space(0)) {
+            return Ok(None);
+        }
+        if let Some(max_padding) = self.options.max_column_padding {
+            template.limit_column_padding(max_padding);
+        }
+
+        let mut output_lines = Vec::new();
+        let mut rows = table_root.children.iter();
+        for is_blank in blank_lines {
+            if is_blank {
+                output_lines.push(String::new());
+                continue;
+            }
+            let Some(row) = rows.next() else {
+                break;
+            };
+            self.buffer = StringJoinBuffer::default();
+            self.add_line_prefix(0);
+            self.inline_table_row_segment(&template, row, false, false);
+            self.buffer.end_line(self.pads.eol());
+            let formatted = self.finish_output(std::slice::from_ref(row), true, true)?;
+            output_lines.push(formatted.trim_end().to_string());
+        }
+
+        let mut result = output_lines.join("\n");
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        Ok(Some(self.apply_final_newline(result, "\n")))
+    }
+
+    /// Reformats a single line of JSONL input.
+    ///
+    /// This is what [`reformat_jsonl`](Self::reformat_jsonl) does for each
+    /// line. Comments on the same line as the value (when allowed by
+    /// `comment_policy`) are kept with it instead of being moved to lines of
+    /// their own.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{CommentPolicy, Formatter};
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.options.comment_policy = CommentPolicy::Preserve;
+    /// let output = formatter.reformat_jsonl_line(r#"{"a":1} // request 7"#).unwrap();
+    ///
+    /// assert_eq!(output, "{\"a\": 1} // request 7\n");
+    /// ```
+    pub fn reformat_jsonl_line(&mut self, line: &str) -> Result<String, FracturedJsonError> {
+        let mut doc_model = self.parse_jsonl_line(line)?;
+        self.format_top_level(&mut doc_model, 0)?;
+        self.buffer.flush();
+        self.finish_output(&doc_model, true, true)
+    }
+
+    /// Minifies JSONL (JSON Lines) input where each line is a separate JSON value.
+    ///
+    /// Each line is independently parsed and minified. Empty lines are preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `jsonl_text` - The JSONL string to minify (one JSON value per line)
+    ///
+    /// # Returns
+    ///
+    /// The minified JSONL string, or an error if any line fails to parse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    ///
+    /// let input = r#"{ "a": 1 }
+    /// { "b": 2 }"#;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let output = formatter.minify_jsonl(input).unwrap();
+    ///
+    /// assert!(output.contains(r#"{"a":1}"#));
+    /// ```
+    pub fn minify_jsonl(&mut self, jsonl_text: &str) -> Result<String, FracturedJsonError> {
+        let mut output_lines = Vec::new();
+
+        for (line_num, line) in jsonl_text.lines().enumerate() {
+            // Preserve empty lines
+            if line.trim().is_empty() {
+                output_lines.push(String::new());
+                continue;
+            }
+
+            // Minify the line
+            let minified = self.minify_jsonl_line(line).map_err(|e| {
+                FracturedJsonError::simple(format!("line {}: {}", line_num + 1, e))
+            })?;
+
+            // Remove trailing newline since we add our own
+            output_lines.push(minified.trim_end().to_string());
+        }
+
+        // Join with newlines and add trailing newline
+        let mut result = output_lines.join("\n");
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        Ok(self.apply_final_newline(result, "\n"))
+    }
+
+    /// Minifies a single line of JSONL input, keeping comments on the same
+    /// line as the value with it. See [`reformat_jsonl_line`](Self::reformat_jsonl_line).
+    pub fn minify_jsonl_line(&mut self, line: &str) -> Result<String, FracturedJsonError> {
+        let mut doc_model = self.parse_jsonl_line(line)?;
+        self.minify_top_level(&mut doc_model)?;
+        self.buffer.flush();
+        self.finish_output(&doc_model, true, false)
+    }
+
+    /// Converts a document whose top-level value is an array to JSONL, with
+    /// each element of the array formatted on a line of its own.
+    ///
+    /// This is the reverse of [`reformat_jsonl`](Self::reformat_jsonl).
+    /// Elements are written the way inline arrays and objects are, however
+    /// long or complex they are. Comments between elements are written on
+    /// lines of their own; blank lines and comments outside the array are
+    /// dropped. `verify_round_trip` does not apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_text` - The JSON document holding the array
+    ///
+    /// # Returns
+    ///
+    /// The JSONL string, or an error if parsing fails, the top-level value is
+    /// not an array, or an element contains comments on lines of their own.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let output = formatter.reformat_as_jsonl(r#"[{"a":1}, {"b":[1,2]}]"#).unwrap();
+    ///
+    /// assert_eq!(output, "{\"a\": 1}\n{ \"b\": [1, 2] }\n");
+    /// ```
+    pub fn reformat_as_jsonl(&mut self, json_text: &str) -> Result<String, FracturedJsonError> {
+        self.write_as_jsonl(json_text, false)
+    }
+
+    /// Converts a document whose top-level value is an array to JSONL like
+    /// [`reformat_as_jsonl`](Self::reformat_as_jsonl), with each element
+    /// minified.
+    pub fn minify_as_jsonl(&mut self, json_text: &str) -> Result<String, FracturedJsonError> {
+        self.write_as_jsonl(json_text, true)
+    }
+
+    /// Formats a [`serde_json::Value`] according to the current options.
+    ///
+    /// This is useful when you already have parsed JSON data and want to
+    /// format it without going through text parsing again.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON value to format
+    /// * `starting_depth` - Initial indentation depth (usually 0)
+    /// * `recursion_limit` - Maximum nesting depth to prevent stack overflow
+    ///
+    /// # Returns
+    ///
+    /// The formatted JSON string, or an error if the recursion limit is exceeded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    /// use serde_json::json;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let value = json!({"name": "Alice", "scores": [95, 87, 92]});
+    ///
+    /// let output = formatter.serialize_value(&value, 0, 100).unwrap();
+    /// ```
+    pub fn serialize_value(
+        &mut self,
+        value: &serde_json::Value,
+        starting_depth: usize,
+        recursion_limit: usize,
+    ) -> Result<String, FracturedJsonError> {
+        let doc_model = convert_value_to_dom(value, None, recursion_limit, &self.options)?;
+        let mut doc_list = Vec::new();
+        if let Some(item) = doc_model {
+            doc_list.push(item);
+        }
+        self.format_top_level(&mut doc_list, starting_depth)?;
+        self.buffer.flush();
+        self.finish_output(&doc_list, true, true)
+    }
+
+    /// Serializes any [`serde::Serialize`] type to formatted JSON.
+    ///
+    /// This is the most convenient method for formatting Rust data structures.
+    /// The value is first converted to a `serde_json::Value`, then formatted.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Any value implementing `Serialize`
+    /// * `starting_depth` - Initial indentation depth (usually 0)
+    /// * `recursion_limit` - Maximum nesting depth to prevent stack overflow
+    ///
+    /// # Returns
+    ///
+    /// The formatted JSON string, or an error if serialization fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Person {
+    ///     name: String,
+    ///     age: u32,
+    /// }
+    ///
+    /// let person = Person {
+    ///     name: "Alice".into(),
+    ///     age: 30,
+    /// };
     ///
     /// let mut formatter = Formatter::new();
     /// let output = formatter.serialize(&person, 0, 100).unwrap();
@@ -360,45 +1148,700 @@ impl Formatter {
     /// ```
     pub fn serialize<T: serde::Serialize>(
         &mut self,
-        value: &T,
-        starting_depth: usize,
-        recursion_limit: usize,
+        value: &T,
+        starting_depth: usize,
+        recursion_limit: usize,
+    ) -> Result<String, FracturedJsonError> {
+        let json_value = serde_json::to_value(value).map_err(|err| {
+            FracturedJsonError::simple(format!("Failed to serialize value: {}", err))
+        })?;
+        self.serialize_value(&json_value, starting_depth, recursion_limit)
+    }
+
+    /// Serializes any [`serde::Serialize`] type like
+    /// [`serialize`](Self::serialize), writing the output to `writer` without
+    /// first building it as one string.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Any value implementing `Serialize`
+    /// * `starting_depth` - Initial indentation depth (usually 0)
+    /// * `recursion_limit` - Maximum nesting depth to prevent stack overflow
+    /// * `writer` - Where to write the output
+    ///
+    /// # Returns
+    ///
+    /// An error if serialization fails or the output can't be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::Formatter;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let scores = BTreeMap::from([("alice", 95), ("bob", 87)]);
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let mut output = Vec::new();
+    /// formatter.serialize_to_writer(&scores, 0, 100, &mut output).unwrap();
+    ///
+    /// assert_eq!(output, b"{\"alice\": 95, \"bob\": 87}\n");
+    /// ```
+    pub fn serialize_to_writer<T: serde::Serialize, W: Write>(
+        &mut self,
+        value: &T,
+        starting_depth: usize,
+        recursion_limit: usize,
+        mut writer: W,
+    ) -> Result<(), FracturedJsonError> {
+        let json_value = serde_json::to_value(value).map_err(|err| {
+            FracturedJsonError::simple(format!("Failed to serialize value: {}", err))
+        })?;
+        let mut doc_list: Vec<JsonItem> =
+            convert_value_to_dom(&json_value, None, recursion_limit, &self.options)?
+                .into_iter()
+                .collect();
+        drop(json_value);
+        self.format_top_level_with(&mut doc_list, starting_depth, |formatter| {
+            formatter.write_finished_lines(&mut writer)
+        })?;
+        self.buffer.flush();
+        self.write_output(&doc_list, true, true, &mut writer)
+    }
+
+    /// Parses JSON text into a [`serde_json::Value`], keeping its comments aside.
+    ///
+    /// This is the way to get typed data out of commented JSON (JSONC) without
+    /// losing the comments. They are returned in a [`CommentMap`] keyed by the
+    /// JSON Pointer of the element each one belongs to. Comments, blank lines
+    /// and trailing commas are accepted according to `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_text` - The JSON document to parse
+    ///
+    /// # Returns
+    ///
+    /// The parsed value and its comments, or an error if parsing fails or the
+    /// input has no top-level value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{CommentPolicy, Formatter, Trivia};
+    ///
+    /// let input = r#"{
+    ///     // Server settings
+    ///     "port": 8080, // default
+    ///     "hosts": ["a", "b"]
+    /// }"#;
+    ///
+    /// let mut formatter = Formatter::new();
+    /// formatter.options.comment_policy = CommentPolicy::Preserve;
+    /// let (value, comments) = formatter.parse_to_value(input).unwrap();
+    ///
+    /// assert_eq!(value["port"], 8080);
+    /// let port = &comments["/port"];
+    /// assert_eq!(port.leading, vec![Trivia::Comment("// Server settings".into())]);
+    /// assert_eq!(port.postfix_comment.as_deref(), Some("// default"));
+    /// ```
+    pub fn parse_to_value(
+        &self,
+        json_text: &str,
+    ) -> Result<(serde_json::Value, CommentMap), FracturedJsonError> {
+        let parser = Parser::new(self.options.clone());
+        let doc_model = parser.parse_top_level(json_text, true)?;
+        convert_dom_to_value(&doc_model)
+    }
+
+    /// Infers a struct/interface skeleton describing the shape of JSON text.
+    ///
+    /// Field names and types are inferred from the parsed document. Fields that
+    /// are missing from some objects in an array, or that hold `null`, are
+    /// marked optional (`Option<T>` in Rust, `?`/`| null` in TypeScript).
+    /// Values whose types disagree fall back to `serde_json::Value`/`unknown`.
+    /// Comments and trailing commas are accepted according to `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_text` - The JSON document to inspect
+    /// * `language` - The language to emit definitions for
+    ///
+    /// # Returns
+    ///
+    /// The generated type definitions, or an error if parsing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{Formatter, SkeletonLanguage};
+    ///
+    /// let formatter = Formatter::new();
+    /// let output = formatter
+    ///     .type_skeleton(r#"[{"id":1,"tag":"a"},{"id":2}]"#, SkeletonLanguage::TypeScript)
+    ///     .unwrap();
+    ///
+    /// assert!(output.contains("export interface RootItem {"));
+    /// assert!(output.contains("    tag?: string;"));
+    /// ```
+    pub fn type_skeleton(
+        &self,
+        json_text: &str,
+        language: SkeletonLanguage,
+    ) -> Result<String, FracturedJsonError> {
+        let parser = Parser::new(self.options.clone());
+        let doc_model = parser.parse_top_level(json_text, true)?;
+        Ok(render_type_skeleton(&doc_model, language))
+    }
+
+    /// Formats a document with both the current options and `other_options`,
+    /// to preview the effect of a change of style.
+    ///
+    /// Both outputs are produced with this formatter's `string_length_func`.
+    /// The current options are restored afterwards, even on error.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_text` - The JSON document to format
+    /// * `other_options` - The options to compare the current ones with
+    ///
+    /// # Returns
+    ///
+    /// Both outputs, or an error if formatting fails with either option set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{Formatter, FracturedJsonOptions};
+    ///
+    /// let mut formatter = Formatter::new();
+    /// let other = FracturedJsonOptions {
+    ///     max_inline_complexity: -1,
+    ///     ..Default::default()
+    /// };
+    /// let comparison = formatter.compare_options(r#"{"a":[1,2]}"#, &other).unwrap();
+    ///
+    /// assert!(comparison.has_differences());
+    /// # #[cfg(feature = "diff")]
+    /// assert!(comparison
+    ///     .unified_diff("a.json", "b.json")
+    ///     .starts_with("--- a.json\n+++ b.json\n"));
+    /// ```
+    pub fn compare_options(
+        &mut self,
+        json_text: &str,
+        other_options: &FracturedJsonOptions,
+    ) -> Result<OptionsComparison, FracturedJsonError> {
+        let output_a = self.reformat(json_text, 0)?;
+        let own_options = std::mem::replace(&mut self.options, other_options.clone());
+        let output_b = self.reformat(json_text, 0);
+        self.options = own_options;
+        Ok(OptionsComparison {
+            output_a,
+            output_b: output_b?,
+        })
+    }
+
+    fn write_as_jsonl(
+        &mut self,
+        json_text: &str,
+        minify: bool,
     ) -> Result<String, FracturedJsonError> {
-        let json_value = serde_json::to_value(value).map_err(|err| {
-            FracturedJsonError::simple(format!("Failed to serialize value: {}", err))
-        })?;
-        self.serialize_value(&json_value, starting_depth, recursion_limit)
+        let parser = Parser::new(self.options.clone());
+        let mut doc_model = parser.parse_top_level(json_text, true)?;
+        self.validate_options()?;
+        self.buffer = StringJoinBuffer::default();
+        self.refresh_pads();
+        self.rewrite_document(&mut doc_model)?;
+
+        let root = doc_model
+            .iter_mut()
+            .find(|item| !Self::is_comment_or_blank_line(item.item_type))
+            .ok_or_else(|| FracturedJsonError::simple("No top-level value found"))?;
+        if root.item_type != JsonItemType::Array {
+            return Err(FracturedJsonError::new(
+                "JSONL output needs a top-level array",
+                Some(root.input_position),
+            ));
+        }
+
+        for element in root.children.iter_mut() {
+            match element.item_type {
+                JsonItemType::BlankLine => continue,
+                JsonItemType::BlockComment | JsonItemType::LineComment => {
+                    let comment_rows = Self::normalize_multiline_comment(
+                        &element.value,
+                        element.input_position.column,
+                    );
+                    for line in comment_rows {
+                        self.buffer.add(&line).end_line(self.pads.eol());
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            self.compute_item_lengths(element, 0);
+            if element.requires_multiple_lines {
+                return Err(FracturedJsonError::new(
+                    "Element can't be written on a single line",
+                    Some(element.input_position),
+                ));
+            }
+            if minify {
+                self.minify_item(element, true);
+            } else {
+                self.inline_element(element, false, None);
+            }
+            self.buffer.end_line(self.pads.eol());
+        }
+
+        self.buffer.flush();
+        let output = self.buffer.as_string();
+        Ok(self.apply_final_newline(output, self.pads.eol()))
+    }
+
+    /// Whether the text holds two or more top-level values, each of which
+    /// starts on a new line and fits on that line.
+    fn is_jsonl(parser: &Parser, json_text: &str) -> bool {
+        let Ok(doc_model) = parser.parse_top_level(json_text, false) else {
+            return false;
+        };
+
+        let mut value_count = 0;
+        let mut last_row = None;
+        for item in doc_model.iter().filter(|item| {
+            !matches!(
+                item.item_type,
+                JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+            )
+        }) {
+            let row = item.input_position.row;
+            if last_row.is_some_and(|last| last >= row)
+                || json_text[item.span.start..item.span.end].contains('\n')
+            {
+                return false;
+            }
+            last_row = Some(row);
+            value_count += 1;
+        }
+        value_count >= 2
+    }
+
+    /// Parses a single JSONL line. Comments sharing the line with the value
+    /// become its prefix/postfix comments, so they stay on the same output line.
+    fn parse_jsonl_line(&self, line: &str) -> Result<Vec<JsonItem>, FracturedJsonError> {
+        let parser = Parser::new(self.options.clone());
+        let mut doc_model = parser.parse_top_level(line, true)?;
+        let Some(value_idx) = doc_model
+            .iter()
+            .position(|item| !Self::is_comment_or_blank_line(item.item_type))
+        else {
+            return Ok(doc_model);
+        };
+
+        let after: Vec<JsonItem> = doc_model.drain(value_idx + 1..).collect();
+        let mut value = doc_model.pop().unwrap();
+        let join_comments = |comments: &[JsonItem]| {
+            comments
+                .iter()
+                .map(|comment| comment.value.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        value.prefix_comment = join_comments(&doc_model);
+        value.postfix_comment = join_comments(&after);
+        value.is_post_comment_line_style = after
+            .last()
+            .is_some_and(|comment| comment.item_type == JsonItemType::LineComment);
+        Ok(vec![value])
+    }
+
+    /// Returns the buffered output, first checking it against `doc_model` if
+    /// `verify_round_trip` is set. `uses_prefix` says whether `prefix_string`
+    /// was written at the start of each line.
+    fn finish_output(
+        &self,
+        doc_model: &[JsonItem],
+        stop_after_first_elem: bool,
+        uses_prefix: bool,
+    ) -> Result<String, FracturedJsonError> {
+        let output = self.apply_final_newline(self.buffer.as_string(), self.pads.eol());
+        if !self.options.verify_round_trip {
+            return Ok(output);
+        }
+
+        let prefix = &self.options.prefix_string;
+        let unprefixed = if uses_prefix && !prefix.is_empty() {
+            output
+                .split_inclusive('\n')
+                .map(|line| {
+                    // Blank lines carry the prefix with its trailing whitespace trimmed.
+                    line.strip_prefix(prefix.as_str())
+                        .or_else(|| line.strip_prefix(prefix.trim_end()))
+                        .unwrap_or(line)
+                })
+                .collect()
+        } else {
+            output.clone()
+        };
+
+        let mut options = self.options.clone();
+        options.max_document_size = usize::MAX;
+        let reparsed = Parser::new(options)
+            .parse_top_level(&unprefixed, stop_after_first_elem)
+            .map_err(|err| {
+                FracturedJsonError::simple(format!(
+                    "Round-trip verification failed: output does not parse: {}",
+                    err
+                ))
+            })?;
+        match find_round_trip_difference(doc_model, &reparsed) {
+            Some(difference) => Err(FracturedJsonError::simple(format!(
+                "Round-trip verification failed {}",
+                difference
+            ))),
+            None => Ok(output),
+        }
+    }
+
+    /// Writes the rest of the buffered output to `writer` one line at a time,
+    /// emptying the buffer. Falls back to [`finish_output`](Self::finish_output)
+    /// when the output has to be verified as a whole first.
+    fn write_output<W: Write>(
+        &mut self,
+        doc_model: &[JsonItem],
+        stop_after_first_elem: bool,
+        uses_prefix: bool,
+        writer: &mut W,
+    ) -> Result<(), FracturedJsonError> {
+        if self.options.verify_round_trip {
+            let output = self.finish_output(doc_model, stop_after_first_elem, uses_prefix)?;
+            return Self::write_text(writer, &output);
+        }
+
+        let lines = self.buffer.take_lines(self.buffer.line_count());
+        let last_line = lines.iter().rposition(|line| !line.is_empty());
+        for (i, line) in lines.into_iter().enumerate() {
+            let line = if Some(i) == last_line {
+                self.apply_final_newline(line, self.pads.eol())
+            } else {
+                line
+            };
+            Self::write_text(writer, &line)?;
+        }
+        writer.flush().map_err(Self::write_error)
+    }
+
+    /// Writes the finished lines that can no longer change to `writer`: all
+    /// of them but the last one with text, which may still gain or lose the
+    /// final newline. Writes nothing while the output has to be verified as
+    /// a whole.
+    fn write_finished_lines<W: Write>(&mut self, writer: &mut W) -> Result<(), FracturedJsonError> {
+        if self.options.verify_round_trip {
+            return Ok(());
+        }
+        let Some(last_line) = self.buffer.last_non_empty_line() else {
+            return Ok(());
+        };
+        for line in self.buffer.take_lines(last_line) {
+            Self::write_text(writer, &line)?;
+        }
+        Ok(())
+    }
+
+    fn write_text<W: Write>(writer: &mut W, text: &str) -> Result<(), FracturedJsonError> {
+        writer.write_all(text.as_bytes()).map_err(Self::write_error)
+    }
+
+    fn write_error(err: std::io::Error) -> FracturedJsonError {
+        FracturedJsonError::simple(format!("Failed to write output: {}", err))
+    }
+
+    fn apply_final_newline(&self, mut output: String, eol: &str) -> String {
+        match self.options.ensure_final_newline {
+            Some(true) if !output.is_empty() && !output.ends_with('\n') => output.push_str(eol),
+            Some(false) if output.ends_with('\n') => {
+                output.pop();
+                if output.ends_with('\r') {
+                    output.pop();
+                }
+            }
+            _ => {}
+        }
+        output
+    }
+
+    fn format_top_level(
+        &mut self,
+        doc_model: &mut [JsonItem],
+        starting_depth: usize,
+    ) -> Result<(), FracturedJsonError> {
+        self.format_top_level_with(doc_model, starting_depth, |_| Ok(()))
     }
 
-    fn format_top_level(&mut self, doc_model: &mut [JsonItem], starting_depth: usize) {
+    /// Formats `doc_model` like `format_top_level`, calling `after_item`
+    /// once each top-level item is written.
+    fn format_top_level_with(
+        &mut self,
+        doc_model: &mut [JsonItem],
+        starting_depth: usize,
+        mut after_item: impl FnMut(&mut Self) -> Result<(), FracturedJsonError>,
+    ) -> Result<(), FracturedJsonError> {
+        self.validate_options()?;
         self.buffer = StringJoinBuffer::default();
-        self.pads = PaddedFormattingTokens::new(&self.options, self.string_length_func.as_ref());
+        self.open_containers = 0;
+        self.output_elided = false;
+        self.refresh_pads();
+        self.rewrite_document(doc_model)?;
+        if let Some(trace) = &mut self.trace {
+            trace.index(doc_model);
+        }
 
         for item in doc_model.iter_mut() {
-            self.compute_item_lengths(item);
+            self.compute_item_lengths(item, starting_depth);
             self.format_item(item, starting_depth, false, None);
+            after_item(self)?;
+        }
+        Ok(())
+    }
+
+    fn validate_options(&self) -> Result<(), FracturedJsonError> {
+        if self.options.prefix_string.contains(['\n', '\r']) {
+            return Err(FracturedJsonError::simple(
+                "prefix_string may not contain line breaks",
+            ));
+        }
+        if self.line_prefix_func.is_some() && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with line_prefix_func",
+            ));
+        }
+        if self.options.max_output_lines.is_some() && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with max_output_lines",
+            ));
+        }
+        if self.options.table_header_comments && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with table_header_comments",
+            ));
+        }
+        if self.options.blank_table_nulls && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with blank_table_nulls",
+            ));
+        }
+        if self.options.table_sort_column.is_some() && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with table_sort_column",
+            ));
+        }
+        if self.options.fill_missing_table_keys && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with fill_missing_table_keys",
+            ));
+        }
+        if self.options.normalize_max_decimal_places.is_some() && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with normalize_max_decimal_places",
+            ));
+        }
+        if self.options.normalize_negative_zero && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with normalize_negative_zero",
+            ));
+        }
+        if self.options.zero_pad_integers && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with zero_pad_integers",
+            ));
         }
+        if !self.options.hex_integer_keys.is_empty() && self.options.verify_round_trip {
+            return Err(FracturedJsonError::simple(
+                "verify_round_trip can't be used with hex_integer_keys",
+            ));
+        }
+        if self.options.fill_missing_table_keys && self.options.blank_table_nulls {
+            return Err(FracturedJsonError::simple(
+                "blank_table_nulls can't be used with fill_missing_table_keys",
+            ));
+        }
+
+        let is_blank = |token: &str| token.chars().all(|ch| ch == ' ' || ch == '\t');
+        if let Some(colon) = &self.options.colon_token {
+            if colon.matches(':').count() != 1 || !is_blank(&colon.replace(':', "")) {
+                return Err(FracturedJsonError::simple(
+                    "colon_token must be a colon surrounded only by spaces or tabs",
+                ));
+            }
+        }
+        if let Some(separator) = &self.options.comment_separator {
+            if !is_blank(separator) {
+                return Err(FracturedJsonError::simple(
+                    "comment_separator may only contain spaces or tabs",
+                ));
+            }
+        }
+        if let Some(indent) = &self.options.indent_string {
+            if !is_blank(indent) {
+                return Err(FracturedJsonError::simple(
+                    "indent_string may only contain spaces or tabs",
+                ));
+            }
+        }
+        if let Some(dummy_comma) = &self.options.dummy_comma {
+            // Widths are worked out with the comma's, so a filler of any other
+            // width, or a tab, would push the rest of its row out of line.
+            let comma = if self.options.comma_padding {
+                ", "
+            } else {
+                ","
+            };
+            let str_len = &self.string_length_func;
+            if dummy_comma.chars().any(|ch| ch != ' ') || str_len(dummy_comma) != str_len(comma) {
+                return Err(FracturedJsonError::simple(
+                    "dummy_comma must be spaces as wide as the comma",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the options that change the document's text rather than its
+    /// layout: key renaming and reordering, comment spacing, unicode escaping,
+    /// string truncation, hex integers and exponent style.
+    fn rewrite_document(&mut self, doc_model: &mut [JsonItem]) -> Result<(), FracturedJsonError> {
+        self.rewrite_keys(doc_model)?;
+        apply_comment_style(doc_model, self.options.comment_style);
+        apply_comment_spacing(doc_model, self.options.comment_spacing);
+        if self.options.normalize_string_escapes {
+            apply_normalize_escapes(doc_model);
+        }
+        if self.options.unescape_unicode {
+            apply_unescape_unicode(doc_model);
+        }
+        if let Some(max_length) = self.options.max_string_display_length {
+            apply_string_truncation(doc_model, max_length);
+        }
+        if self.options.ascii_only {
+            apply_ascii_only(doc_model);
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.snapshot_numbers(doc_model);
+        }
+        if !self.options.hex_integer_keys.is_empty() {
+            apply_hex_integers(doc_model, &self.options.hex_integer_keys);
+        }
+        apply_number_notation(
+            doc_model,
+            self.options.number_notation,
+            self.options.number_notation_threshold,
+        );
+        apply_exponent_style(doc_model, self.options.exponent_style);
+        if self.options.trim_trailing_zeros {
+            apply_trim_trailing_zeros(doc_model);
+        }
+        if let Some(format) = &self.number_format_func {
+            apply_number_format(doc_model, format.as_ref());
+        }
+        Ok(())
     }
 
-    fn minify_top_level(&mut self, doc_model: &mut [JsonItem]) {
+    /// Renames and reorders the properties of `doc_model` as the options say.
+    fn rewrite_keys(&self, doc_model: &mut [JsonItem]) -> Result<(), FracturedJsonError> {
+        if let Some(case) = self.options.key_case {
+            apply_key_case(doc_model, case, &self.options.key_case_exclusions)?;
+        }
+        if !self.options.key_order.is_empty() || self.options.sort_keys {
+            apply_key_order(doc_model, &self.options.key_order, self.options.sort_keys);
+        }
+        Ok(())
+    }
+
+    fn minify_top_level(&mut self, doc_model: &mut [JsonItem]) -> Result<(), FracturedJsonError> {
         self.buffer = StringJoinBuffer::default();
-        self.pads = PaddedFormattingTokens::new(&self.options, self.string_length_func.as_ref());
+        self.refresh_pads();
+        self.rewrite_document(doc_model)?;
+        if self.options.block_middle_comments {
+            apply_block_middle_comments(doc_model);
+        }
 
         let mut at_start_of_new_line = true;
         for item in doc_model.iter() {
             at_start_of_new_line = self.minify_item(item, at_start_of_new_line);
         }
+        Ok(())
+    }
+
+    /// Rebuilds `pads` if the options or length function changed since it
+    /// was last built.
+    fn refresh_pads(&mut self) {
+        if self.pads_options == self.options
+            && Arc::ptr_eq(&self.pads_length_func, &self.string_length_func)
+        {
+            return;
+        }
+        self.pads = PaddedFormattingTokens::new(&self.options, self.string_length_func.as_ref());
+        self.pads_options = self.options.clone();
+        self.pads_length_func = self.string_length_func.clone();
     }
 
-    fn compute_item_lengths(&mut self, item: &mut JsonItem) {
+    fn compute_item_lengths(&mut self, item: &mut JsonItem, depth: usize) {
         for child in item.children.iter_mut() {
-            self.compute_item_lengths(child);
+            self.compute_item_lengths(child, depth + 1);
+        }
+
+        self.compute_own_lengths(item);
+        if self.options.block_middle_comments && item.middle_comment_has_new_line {
+            self.block_middle_comment(item, depth);
+        }
+    }
+
+    /// Rewrites the line comments between `item`'s key and value as block
+    /// comments if that lets `item` be written on one line at `depth`, inline
+    /// or as a table row. Otherwise `item` is left as it was.
+    fn block_middle_comment(&mut self, item: &mut JsonItem, depth: usize) {
+        let Some(converted) = as_block_comments(&item.middle_comment) else {
+            return;
+        };
+        let original = std::mem::replace(&mut item.middle_comment, converted);
+        self.compute_own_lengths(item);
+
+        let max_complexity = self
+            .options
+            .max_inline_complexity
+            .max(self.options.max_table_row_complexity);
+        let fits_length = match self.options.max_inline_value_length {
+            Some(max) => item.value_length <= max,
+            None => {
+                item.minimum_total_length + self.pads.comma_len()
+                    <= self.available_line_space(depth)
+            }
+        };
+        if !item.requires_multiple_lines
+            && item.complexity as isize <= max_complexity
+            && fits_length
+        {
+            item.middle_comment_has_new_line = false;
+            return;
         }
 
+        item.middle_comment = original;
+        self.compute_own_lengths(item);
+    }
+
+    /// Computes `item`'s lengths from its own text and its children's
+    /// lengths, which must already be computed.
+    fn compute_own_lengths(&mut self, item: &mut JsonItem) {
         item.value_length = match item.item_type {
             JsonItemType::Null => self.pads.literal_null_len(),
             JsonItemType::True => self.pads.literal_true_len(),
             JsonItemType::False => self.pads.literal_false_len(),
+            JsonItemType::Number => match &self.number_width_func {
+                Some(width) => width(&item.value),
+                None => (self.string_length_func)(&item.value),
+            },
             _ => (self.string_length_func)(&item.value),
         };
 
@@ -447,7 +1890,7 @@ impl Formatter {
             0
         } + item.value_length
             + if item.postfix_comment_length > 0 {
-                item.postfix_comment_length + self.pads.comment_len()
+                item.postfix_comment_length + self.pads.postfix_comment_len()
             } else {
                 0
             };
@@ -460,13 +1903,18 @@ impl Formatter {
         include_trailing_comma: bool,
         parent_template: Option<&TableTemplate>,
     ) {
-        match item.item_type {
+        let first_line = self.buffer.line_count();
+        let decision = match item.item_type {
             JsonItemType::Array | JsonItemType::Object => {
                 self.format_container(item, depth, include_trailing_comma, parent_template)
             }
-            JsonItemType::BlankLine => self.format_blank_line(),
+            JsonItemType::BlankLine => {
+                self.format_blank_line(depth);
+                return;
+            }
             JsonItemType::BlockComment | JsonItemType::LineComment => {
-                self.format_standalone_comment(item, depth)
+                self.format_standalone_comment(item, depth);
+                FormatDecision::Comment
             }
             _ => {
                 if item.requires_multiple_lines {
@@ -484,7 +1932,22 @@ impl Formatter {
                         parent_template,
                     );
                 }
+                FormatDecision::Value
             }
+        };
+        let last_line = self.buffer.line_count().saturating_sub(1).max(first_line);
+        self.record_trace(item, decision, first_line, last_line);
+    }
+
+    fn record_trace(
+        &mut self,
+        item: &JsonItem,
+        decision: FormatDecision,
+        first_line: usize,
+        last_line: usize,
+    ) {
+        if let Some(trace) = &mut self.trace {
+            trace.record(item, decision, first_line, last_line);
         }
     }
 
@@ -494,33 +1957,178 @@ impl Formatter {
         depth: usize,
         include_trailing_comma: bool,
         parent_template: Option<&TableTemplate>,
-    ) {
-        if (depth as isize) > self.options.always_expand_depth
-            && self.format_container_inline(item, depth, include_trailing_comma, parent_template)
+    ) -> FormatDecision {
+        let compact_at_depth = self.options.compact_at_depth;
+        if compact_at_depth >= 0
+            && depth as isize > compact_at_depth
+            && self.format_container_minified(item, depth, include_trailing_comma, parent_template)
         {
-            return;
+            return FormatDecision::Inline;
         }
 
         let item_complexity = item.complexity as isize;
-        let recursive_template = item_complexity <= self.options.max_compact_array_complexity
-            || item_complexity <= self.options.max_table_row_complexity + 1;
-        let mut template =
-            TableTemplate::new(self.pads.clone(), self.options.number_list_alignment);
+        let recursive_template = (item_complexity <= self.options.max_compact_array_complexity
+            || item_complexity <= self.options.max_table_row_complexity + 1)
+            && !self.has_too_many_table_rows(item);
+        let mut template = self.new_table_template(self.pads.clone());
         template.measure_table_root(item, recursive_template);
+        template.order_columns(
+            self.options.table_column_order,
+            &self.options.table_column_keys,
+        );
 
-        if (depth as isize) > self.options.always_expand_depth
-            && self.format_container_compact_multiline(
+        let max_length = self.options.max_total_line_length;
+        self.line_length_limit = max_length;
+        let hint = self.layout_hint(item);
+        if let Some(decision) = self.format_container_hinted(
+            item,
+            depth,
+            include_trailing_comma,
+            &template,
+            parent_template,
+        ) {
+            return decision;
+        }
+
+        // Lines longer than the preferred length are only written when the
+        // alternative is expanding the container.
+        let limits = match self.options.preferred_line_length {
+            _ if hint == Some(LayoutHint::Expand) => vec![],
+            Some(preferred) if preferred < max_length => vec![preferred, max_length],
+            _ => vec![max_length],
+        };
+        let first_line = self.buffer.line_count();
+        for limit in limits {
+            self.line_length_limit = limit;
+            if let Some(decision) = self.format_container_single_lines(
                 item,
                 depth,
                 include_trailing_comma,
                 &template,
                 parent_template,
+            ) {
+                // Only an expanded container can be cut short.
+                if !self.exceeds_line_budget() {
+                    return decision;
+                }
+                self.discard_lines_from(first_line);
+                break;
+            }
+        }
+
+        self.line_length_limit = max_length;
+        self.format_container_expanded(
+            item,
+            depth,
+            include_trailing_comma,
+            &template,
+            parent_template,
+        );
+        FormatDecision::Expanded
+    }
+
+    /// Tries the layout `key_layout_hints` asks for, if any. Returns None if
+    /// there's no hint or the hinted layout isn't possible.
+    fn format_container_hinted(
+        &mut self,
+        item: &JsonItem,
+        depth: usize,
+        include_trailing_comma: bool,
+        template: &TableTemplate,
+        parent_template: Option<&TableTemplate>,
+    ) -> Option<FormatDecision> {
+        let hint = self.layout_hint(item)?;
+        let children_may_inline = item
+            .children
+            .iter()
+            .all(|child| !Self::is_container(child) || self.may_inline(child, depth + 1));
+        if !children_may_inline {
+            return None;
+        }
+
+        let formatted = match hint {
+            LayoutHint::Inline => self.format_container_inline(
+                item,
+                depth,
+                include_trailing_comma,
+                template,
+                parent_template,
+            ),
+            LayoutHint::Compact => self.format_container_compact_multiline(
+                item,
+                depth,
+                include_trailing_comma,
+                template,
+                parent_template,
+            ),
+            LayoutHint::Table => self.format_container_table(
+                item,
+                depth,
+                include_trailing_comma,
+                &mut template.clone(),
+                parent_template,
+            ),
+            LayoutHint::Expand => false,
+        };
+        if !formatted {
+            return None;
+        }
+        Some(match hint {
+            LayoutHint::Inline => FormatDecision::Inline,
+            LayoutHint::Compact => FormatDecision::CompactMultiline,
+            _ => FormatDecision::Table,
+        })
+    }
+
+    /// The entry in `key_layout_hints` for the property `item` is the value
+    /// of, if any.
+    fn layout_hint(&self, item: &JsonItem) -> Option<LayoutHint> {
+        if self.options.key_layout_hints.is_empty() || item.name.is_empty() {
+            return None;
+        }
+        let key = serde_json::from_str::<String>(&item.name).ok()?;
+        self.options.key_layout_hints.get(&key).copied()
+    }
+
+    /// Tries the layouts that keep whole elements on single lines: inline,
+    /// compact multiline and table, in that order. Returns None if none fits.
+    fn format_container_single_lines(
+        &mut self,
+        item: &JsonItem,
+        depth: usize,
+        include_trailing_comma: bool,
+        template: &TableTemplate,
+        parent_template: Option<&TableTemplate>,
+    ) -> Option<FormatDecision> {
+        if self.may_inline(item, depth)
+            && self.format_container_inline(
+                item,
+                depth,
+                include_trailing_comma,
+                template,
+                parent_template,
             )
         {
-            return;
+            return Some(FormatDecision::Inline);
+        }
+
+        if self.may_inline(item, depth)
+            && self.format_container_compact_multiline(
+                item,
+                depth,
+                include_trailing_comma,
+                template,
+                parent_template,
+            )
+        {
+            return Some(FormatDecision::CompactMultiline);
         }
 
-        if (depth as isize) >= self.options.always_expand_depth {
+        let rows_may_inline = item
+            .children
+            .iter()
+            .all(|child| !Self::is_container(child) || self.may_inline(child, depth + 1));
+        if (depth as isize) >= self.options.always_expand_depth && rows_may_inline {
             let mut table_template = template.clone();
             if self.format_container_table(
                 item,
@@ -529,17 +2137,65 @@ impl Formatter {
                 &mut table_template,
                 parent_template,
             ) {
-                return;
+                return Some(FormatDecision::Table);
+            }
+        }
+
+        None
+    }
+
+    /// Whether `item` at `depth`, and every container within it, may be
+    /// written on a single line under the always-expand options,
+    /// `max_inline_children` and `key_layout_hints`.
+    fn may_inline(&self, item: &JsonItem, depth: usize) -> bool {
+        let opts = &self.options;
+        match self.layout_hint(item) {
+            Some(LayoutHint::Inline) => {
+                return item
+                    .children
+                    .iter()
+                    .all(|child| !Self::is_container(child) || self.may_inline(child, depth + 1));
+            }
+            Some(_) => return false,
+            None => {}
+        }
+        if let Some(max_children) = opts.max_inline_children {
+            let element_count = item
+                .children
+                .iter()
+                .filter(|child| !Self::is_comment_or_blank_line(child.item_type))
+                .count();
+            if element_count > max_children {
+                return false;
             }
         }
 
-        self.format_container_expanded(
-            item,
-            depth,
-            include_trailing_comma,
-            &template,
-            parent_template,
-        );
+        let deepest = opts
+            .always_expand_depth
+            .max(opts.always_expand_array_depth)
+            .max(opts.always_expand_object_depth);
+        if depth as isize > deepest
+            && opts.max_inline_children.is_none()
+            && opts.key_layout_hints.is_empty()
+        {
+            return true;
+        }
+
+        let expand_depth = match item.item_type {
+            JsonItemType::Array => opts.always_expand_array_depth,
+            JsonItemType::Object => opts.always_expand_object_depth,
+            _ => -1,
+        }
+        .max(opts.always_expand_depth);
+        depth as isize > expand_depth
+            && item
+                .children
+                .iter()
+                .all(|child| !Self::is_container(child) || self.may_inline(child, depth + 1))
+    }
+
+    fn is_container(item: &JsonItem) -> bool {
+        matches!(item.item_type, JsonItemType::Array | JsonItemType::Object)
     }
 
     fn format_container_inline(
@@ -547,6 +2203,7 @@ impl Formatter {
         item: &JsonItem,
         depth: usize,
         include_trailing_comma: bool,
+        template: &TableTemplate,
         parent_template: Option<&TableTemplate>,
     ) -> bool {
         if item.requires_multiple_lines {
@@ -579,16 +2236,15 @@ impl Formatter {
             (prefix, name)
         };
 
-        let length_to_consider = prefix_length
+        let length_besides_value = prefix_length
             + name_length
             + if item.middle_comment_length > 0 {
                 item.middle_comment_length + self.pads.comment_len()
             } else {
                 0
             }
-            + item.value_length
             + if item.postfix_comment_length > 0 {
-                item.postfix_comment_length + self.pads.comment_len()
+                item.postfix_comment_length + self.pads.postfix_comment_len()
             } else {
                 0
             }
@@ -598,15 +2254,97 @@ impl Formatter {
                 0
             };
 
-        if (item.complexity as isize) > self.options.max_inline_complexity
-            || length_to_consider > self.available_line_space(depth)
+        let forced = self.layout_hint(item) == Some(LayoutHint::Inline);
+        let ignore_length =
+            forced || (self.options.never_wrap_primitive_arrays && Self::is_primitive_array(item));
+        // `max_inline_value_length` stands in for the line length, which
+        // shrinks with depth.
+        let fits = |value_length: usize| match self.options.max_inline_value_length {
+            Some(max) => value_length <= max,
+            None => length_besides_value + value_length <= self.available_line_space(depth),
+        };
+        if (!forced && (item.complexity as isize) > self.options.max_inline_complexity)
+            || (!ignore_length && !fits(item.value_length))
         {
             return false;
         }
 
+        let aligned_length = self.aligned_inline_length(item, template);
+        let aligned = aligned_length.is_some_and(|length| ignore_length || fits(length));
+
         let indent = self.pads.indent(depth);
-        self.buffer.add(&self.options.prefix_string).add(&indent);
-        self.inline_element(item, include_trailing_comma, parent_template);
+        self.add_line_prefix(depth);
+        self.buffer.add(&indent);
+        if aligned {
+            self.inline_element_start(item, parent_template);
+            self.inline_aligned_numbers(item, template);
+            self.inline_element_end(item, include_trailing_comma);
+        } else {
+            self.inline_element(item, include_trailing_comma, parent_template);
+        }
+        self.buffer.end_line(self.pads.eol());
+        true
+    }
+
+    /// Length of the array `item` written inline with its numbers aligned as
+    /// `template` says, or None if `align_inline_numbers` isn't set or
+    /// `item` isn't an array of numbers and nulls without comments.
+    fn aligned_inline_length(&self, item: &JsonItem, template: &TableTemplate) -> Option<usize> {
+        let is_number_list = item.item_type == JsonItemType::Array
+            && template.column_type == TableColumnType::Number
+            && item.children.iter().all(|child| {
+                matches!(child.item_type, JsonItemType::Number | JsonItemType::Null)
+                    && child.prefix_comment_length == 0
+                    && child.middle_comment_length == 0
+                    && child.postfix_comment_length == 0
+            });
+        if !self.options.align_inline_numbers || !is_number_list {
+            return None;
+        }
+        let pad_type = Self::get_padding_type(item);
+        let count = item.children.len();
+        Some(
+            self.pads.arr_start_len(pad_type)
+                + count * template.composite_value_length
+                + count.saturating_sub(1) * self.pads.comma_len()
+                + self.pads.arr_end_len(pad_type),
+        )
+    }
+
+    /// Writes the array `item` of numbers on one line, each padded to the
+    /// width of `template`.
+    fn inline_aligned_numbers(&mut self, item: &JsonItem, template: &TableTemplate) {
+        let pad_type = Self::get_padding_type(item);
+        self.buffer.add(self.pads.arr_start(pad_type));
+        let last_index = item.children.len().saturating_sub(1);
+        for (i, child) in item.children.iter().enumerate() {
+            // Written as a whole row for the plain comma, except the last,
+            // which needs no filler in place of one.
+            let needs_comma = i < last_index;
+            self.inline_table_row_segment(template, child, needs_comma, needs_comma);
+        }
+        self.buffer.add(self.pads.arr_end(pad_type));
+    }
+
+    /// Writes the container on one line with no padding inside it, for
+    /// `compact_at_depth`.
+    fn format_container_minified(
+        &mut self,
+        item: &JsonItem,
+        depth: usize,
+        include_trailing_comma: bool,
+        parent_template: Option<&TableTemplate>,
+    ) -> bool {
+        if item.requires_multiple_lines {
+            return false;
+        }
+
+        let indent = self.pads.indent(depth);
+        self.add_line_prefix(depth);
+        self.buffer.add(&indent);
+        self.inline_element_start(item, parent_template);
+        self.minify_container(item);
+        self.inline_element_end(item, include_trailing_comma);
         self.buffer.end_line(self.pads.eol());
         true
     }
@@ -640,9 +2378,18 @@ impl Formatter {
         );
         let likely_available_line_space = self.available_line_space(depth + 1);
 
+        // Items that aren't table-formatted can still be padded into columns.
+        let column_width = if !use_table_formatting && self.options.align_compact_array_items {
+            item.children.iter().map(|ch| ch.minimum_total_length).max()
+        } else {
+            None
+        };
+
         let mut avg_item_width = self.pads.comma_len();
         if use_table_formatting {
             avg_item_width += template.total_length;
+        } else if let Some(column_width) = column_width {
+            avg_item_width += column_width;
         } else {
             let sum: usize = item.children.iter().map(|ch| ch.minimum_total_length).sum();
             avg_item_width += sum / item.children.len().max(1);
@@ -656,50 +2403,114 @@ impl Formatter {
             .add(self.pads.start(item.item_type, BracketPaddingType::Empty));
 
         let available_line_space = self.available_line_space(depth_after_colon + 1);
-        let mut remaining_line_space: isize = -1;
-        for (i, child) in item.children.iter().enumerate() {
-            let needs_comma = i < item.children.len() - 1;
-            let space_needed = if use_table_formatting {
-                (if needs_comma {
-                    self.pads.comma_len()
-                } else {
-                    0
-                }) + template.total_length
-            } else {
-                (if needs_comma {
+        let last_index = item.children.len() - 1;
+        let space_needed: Vec<usize> = item
+            .children
+            .iter()
+            .enumerate()
+            .map(|(i, child)| {
+                let comma_len = if i < last_index {
                     self.pads.comma_len()
                 } else {
                     0
-                }) + child.minimum_total_length
-            };
+                };
+                comma_len
+                    + if use_table_formatting {
+                        template.total_length
+                    } else {
+                        column_width.unwrap_or(child.minimum_total_length)
+                    }
+            })
+            .collect();
+        let mut row_sizes = Self::fill_compact_rows(&space_needed, available_line_space);
+        if self.options.balance_compact_array_rows {
+            row_sizes = Self::balance_compact_rows(&space_needed, available_line_space, row_sizes);
+        }
 
-            if remaining_line_space < space_needed as isize {
-                let indent = self.pads.indent(depth_after_colon + 1);
-                self.buffer
-                    .end_line(self.pads.eol())
-                    .add(&self.options.prefix_string)
-                    .add(&indent);
-                remaining_line_space = available_line_space as isize;
-            }
+        let mut children = item.children.iter().enumerate();
+        for row_size in row_sizes {
+            let indent = self.pads.indent(depth_after_colon + 1);
+            self.buffer.end_line(self.pads.eol());
+            self.add_line_prefix(depth_after_colon + 1);
+            self.buffer.add(&indent);
+
+            for (i, child) in children.by_ref().take(row_size) {
+                let needs_comma = i < last_index;
+                let line = self.buffer.line_count();
+                let decision = if Self::is_container(child) {
+                    FormatDecision::Inline
+                } else {
+                    FormatDecision::Value
+                };
+                self.record_trace(child, decision, line, line);
 
-            if use_table_formatting {
-                self.inline_table_row_segment(template, child, needs_comma, false);
-            } else {
-                self.inline_element(child, needs_comma, None);
+                if use_table_formatting {
+                    self.inline_table_row_segment(template, child, needs_comma, true);
+                } else {
+                    self.inline_element(child, needs_comma, None);
+                    if let Some(column_width) = column_width {
+                        self.buffer
+                            .spaces(column_width.saturating_sub(child.minimum_total_length));
+                    }
+                }
             }
-            remaining_line_space -= space_needed as isize;
         }
 
         let indent = self.pads.indent(depth_after_colon);
+        self.buffer.end_line(self.pads.eol());
+        self.add_line_prefix(depth_after_colon);
         self.buffer
-            .end_line(self.pads.eol())
-            .add(&self.options.prefix_string)
             .add(&indent)
             .add(self.pads.end(item.item_type, BracketPaddingType::Empty));
         self.standard_format_end(item, include_trailing_comma);
         true
     }
 
+    /// Splits items needing `space_needed` into rows of at most
+    /// `available_line_space`, filling each row before starting the next.
+    /// Returns the number of items in each row. An item too wide for any row
+    /// gets one of its own.
+    fn fill_compact_rows(space_needed: &[usize], available_line_space: usize) -> Vec<usize> {
+        let mut row_sizes: Vec<usize> = Vec::new();
+        let mut remaining_line_space = 0;
+        for &space in space_needed {
+            match row_sizes.last_mut() {
+                Some(row_size) if space <= remaining_line_space => {
+                    *row_size += 1;
+                    remaining_line_space -= space;
+                }
+                _ => {
+                    row_sizes.push(1);
+                    remaining_line_space = available_line_space.saturating_sub(space);
+                }
+            }
+        }
+        row_sizes
+    }
+
+    /// Spreads the items of `filled_rows` evenly over the same number of rows,
+    /// keeping every row but the last the same length. Returns `filled_rows`
+    /// unchanged if the even split doesn't fit.
+    fn balance_compact_rows(
+        space_needed: &[usize],
+        available_line_space: usize,
+        filled_rows: Vec<usize>,
+    ) -> Vec<usize> {
+        let row_count = filled_rows.len();
+        if row_count < 2 {
+            return filled_rows;
+        }
+
+        let per_row = space_needed.len().div_ceil(row_count);
+        let fits = space_needed
+            .chunks(per_row)
+            .all(|row| row.len() == 1 || row.iter().sum::<usize>() <= available_line_space);
+        if !fits {
+            return filled_rows;
+        }
+        space_needed.chunks(per_row).map(|row| row.len()).collect()
+    }
+
     fn format_container_table(
         &mut self,
         item: &JsonItem,
@@ -714,6 +2525,14 @@ impl Formatter {
         if template.requires_multiple_lines {
             return false;
         }
+        let row_count = item
+            .children
+            .iter()
+            .filter(|ch| !Self::is_comment_or_blank_line(ch.item_type))
+            .count();
+        if row_count < self.options.min_table_rows || self.has_too_many_table_rows(item) {
+            return false;
+        }
 
         let available_space_depth = if item.middle_comment_has_new_line {
             depth + 2
@@ -724,29 +2543,112 @@ impl Formatter {
             .available_line_space(available_space_depth)
             .saturating_sub(self.pads.comma_len());
 
-        let is_child_too_long = item
+        // Rows holding a value wider than `max_table_column_width` are written
+        // unaligned anyway, so they don't count against
+        // `max_table_outlier_rows` when they're also too long.
+        let max_column_width = self.options.max_table_column_width.unwrap_or(usize::MAX);
+        let force = self.options.force_table_formatting;
+        let too_long_rows: Vec<&JsonItem> = item
             .children
             .iter()
             .filter(|ch| !Self::is_comment_or_blank_line(ch.item_type))
-            .any(|ch| ch.minimum_total_length > available_space);
-        if is_child_too_long {
+            .filter(|ch| ch.minimum_total_length > available_space)
+            .collect();
+        let too_long_count = too_long_rows
+            .iter()
+            .filter(|row| !has_value_wider_than(row, max_column_width))
+            .count();
+        if !too_long_rows.is_empty()
+            && !force
+            && (too_long_count > self.options.max_table_outlier_rows
+                || too_long_rows.len() == row_count)
+        {
             return false;
         }
-
-        if template.column_type == TableColumnType::Mixed || !template.try_to_fit(available_space) {
+        let is_too_long = |row: &JsonItem| !force && row.minimum_total_length > available_space;
+
+        // Rows that are too long or hold an outlier value are written
+        // unaligned, with columns measured from the other rows.
+        let is_outlier =
+            |row: &JsonItem| is_too_long(row) || has_value_wider_than(row, max_column_width);
+        let mut narrowed;
+        let template = if item.children.iter().any(is_outlier) {
+            narrowed = self.new_table_template(self.pads.clone());
+            narrowed.measure_table_root_except(item, true, is_outlier);
+            narrowed.order_columns(
+                self.options.table_column_order,
+                &self.options.table_column_keys,
+            );
+            &mut narrowed
+        } else {
+            template
+        };
+        if template.column_type == TableColumnType::Mixed {
+            return false;
+        }
+        if let Some(min_percent) = self.options.min_table_key_match_percent {
+            if template.column_type == TableColumnType::Object
+                && matching_key_set_percent(item) < min_percent
+            {
+                return false;
+            }
+        }
+        // Set when the rows only fit with their padding squeezed out. Swapped
+        // into `self.pads` while the aligned parts of the table are written.
+        let mut tight_pads = None;
+        if self.options.tight_table_fallback && template.total_length > available_space {
+            let pads = self.tight_table_pads();
+            let mut tight = self.new_table_template(pads.clone());
+            tight.measure_table_root_except(item, true, is_outlier);
+            tight.order_columns(
+                self.options.table_column_order,
+                &self.options.table_column_keys,
+            );
+            if tight.total_length <= available_space {
+                *template = tight;
+                tight_pads = Some(pads);
+            }
+        }
+        if force {
+            // A forced table that doesn't fit keeps all of its columns.
+            let mut fitted = template.clone();
+            if fitted.try_to_fit(available_space) {
+                *template = fitted;
+            }
+        } else if !template.try_to_fit(available_space) {
             return false;
         }
+        if let Some(max_padding) = self.options.max_column_padding {
+            template.limit_column_padding(max_padding);
+        }
+        if let Some(max_padding) = self.options.max_table_name_padding {
+            template.unpadded_names =
+                template.name_length.saturating_sub(template.name_minimum) > max_padding;
+        }
+
+        if let Some(trace) = &mut self.trace {
+            trace.record_table(item, row_count, template, self.buffer.line_count());
+        }
 
         let depth_after_colon = self.standard_format_start(item, depth, parent_template);
         self.buffer
             .add(self.pads.start(item.item_type, BracketPaddingType::Empty))
             .end_line(self.pads.eol());
+        if self.options.table_header_comments {
+            self.swap_tight_pads(&mut tight_pads);
+            self.format_table_header(template, depth_after_colon + 1);
+            self.swap_tight_pads(&mut tight_pads);
+        }
 
+        let rows = match &self.options.table_sort_column {
+            Some(column) => sorted_table_rows(item, column, self.options.table_sort_descending),
+            None => item.children.iter().collect(),
+        };
         let last_element_index = Self::index_of_last_element(&item.children);
-        for (i, row_item) in item.children.iter().enumerate() {
+        for (i, row_item) in rows.into_iter().enumerate() {
             match row_item.item_type {
                 JsonItemType::BlankLine => {
-                    self.format_blank_line();
+                    self.format_blank_line(depth_after_colon + 1);
                     continue;
                 }
                 JsonItemType::LineComment | JsonItemType::BlockComment => {
@@ -756,26 +2658,146 @@ impl Formatter {
                 _ => {}
             }
 
+            if is_too_long(row_item) {
+                self.format_item(
+                    row_item,
+                    depth_after_colon + 1,
+                    (i as isize) < last_element_index,
+                    None,
+                );
+                continue;
+            }
+
             let indent = self.pads.indent(depth_after_colon + 1);
-            self.buffer.add(&self.options.prefix_string).add(&indent);
-            self.inline_table_row_segment(
-                template,
-                row_item,
-                (i as isize) < last_element_index,
-                true,
-            );
+            self.add_line_prefix(depth_after_colon + 1);
+            self.buffer.add(&indent);
+            let decision = if has_value_wider_than(row_item, max_column_width) {
+                self.inline_element(row_item, (i as isize) < last_element_index, None);
+                FormatDecision::Inline
+            } else {
+                self.swap_tight_pads(&mut tight_pads);
+                self.inline_table_row_segment(
+                    template,
+                    row_item,
+                    (i as isize) < last_element_index,
+                    true,
+                );
+                self.swap_tight_pads(&mut tight_pads);
+                FormatDecision::TableRow
+            };
+            let line = self.buffer.line_count();
+            self.record_trace(row_item, decision, line, line);
             self.buffer.end_line(self.pads.eol());
         }
 
         let indent = self.pads.indent(depth_after_colon);
+        self.add_line_prefix(depth_after_colon);
         self.buffer
-            .add(&self.options.prefix_string)
             .add(&indent)
             .add(self.pads.end(item.item_type, BracketPaddingType::Empty));
         self.standard_format_end(item, include_trailing_comma);
         true
     }
 
+    /// Whether `item` has more elements than `max_table_rows` allows.
+    fn has_too_many_table_rows(&self, item: &JsonItem) -> bool {
+        self.options.max_table_rows.is_some_and(|max_rows| {
+            item.children
+                .iter()
+                .filter(|ch| !Self::is_comment_or_blank_line(ch.item_type))
+                .nth(max_rows)
+                .is_some()
+        })
+    }
+
+    /// A template for measuring tables with `pads`, set up as the options say.
+    fn new_table_template(&self, pads: PaddedFormattingTokens) -> TableTemplate {
+        let mut template = TableTemplate::new(pads, self.options.number_list_alignment);
+        template.blank_null_members = self.options.blank_table_nulls;
+        template.numeric_strings = self.options.align_numeric_strings;
+        template.unit_strings = self.options.align_unit_strings;
+        template.fill_missing_members = self.options.fill_missing_table_keys;
+        template.equal_array_lengths_only = self.options.require_equal_array_lengths;
+        template.datetime_strings = self.options.detect_datetime_columns;
+        template.zero_pad_integers = self.options.zero_pad_integers;
+        template.max_decimal_places = self.options.normalize_max_decimal_places;
+        template.decimal_rounding = self.options.normalize_rounding;
+        template.unsigned_zero = self.options.normalize_negative_zero;
+        template.normalize_fallback = self.options.normalize_fallback;
+        template.min_integer_digits = self.options.number_min_integer_digits;
+        template.string_classifier = self.string_cell_classifier.clone().map(StringClassifier);
+        template.number_width = self.number_width_func.clone().map(NumberWidth);
+        template
+    }
+
+    /// Formatting tokens without padding inside brackets or after colons, for
+    /// tables that only fit without it.
+    fn tight_table_pads(&self) -> PaddedFormattingTokens {
+        let mut options = self.options.clone();
+        options.colon_padding = false;
+        options.colon_token = None;
+        options.simple_bracket_padding = false;
+        options.nested_bracket_padding = false;
+        PaddedFormattingTokens::new(&options, self.string_length_func.as_ref())
+    }
+
+    /// Exchanges `self.pads` with `tight_pads`, if there are any.
+    fn swap_tight_pads(&mut self, tight_pads: &mut Option<PaddedFormattingTokens>) {
+        if let Some(pads) = tight_pads {
+            std::mem::swap(&mut self.pads, pads);
+        }
+    }
+
+    /// Writes a comment naming the columns of a table of objects, each name
+    /// starting above the first letter of its key in the rows.
+    fn format_table_header(&mut self, template: &TableTemplate, depth: usize) {
+        // Rows whose names aren't padded to one width have no common columns.
+        if template.column_type != TableColumnType::Object
+            || template.children.is_empty()
+            || template.unpadded_names
+        {
+            return;
+        }
+
+        // Rows that are properties start with their names, and any comments,
+        // before the brace.
+        let row_prefix_length = if template.prefix_comment_length > 0 {
+            template.prefix_comment_length + self.pads.comment_len()
+        } else {
+            0
+        } + if template.name_length > 0 {
+            template.name_length + self.pads.colon_len()
+        } else {
+            0
+        } + if template.middle_comment_length > 0 {
+            template.middle_comment_length + self.pads.comment_len()
+        } else {
+            0
+        };
+
+        let mut header = String::from("//");
+        let mut header_length = 2;
+        let mut column_start = row_prefix_length + self.pads.obj_start_len(template.pad_type);
+        let aligned_count = template.unaligned_from.unwrap_or(template.children.len());
+        for column in &template.children[..aligned_count] {
+            let name = column.location_in_parent.as_deref().unwrap_or_default();
+            let label = serde_json::from_str::<String>(name).unwrap_or_else(|_| name.to_string());
+            // Skip the key's opening quote.
+            let gap = (column_start + 1).saturating_sub(header_length).max(1);
+            header.push_str(&" ".repeat(gap));
+            header.push_str(&label);
+            header_length += gap + (self.string_length_func)(&label);
+            column_start += column.total_length + self.pads.table_comma_len();
+        }
+
+        let indent = self.pads.indent(depth);
+        self.add_line_prefix(depth);
+        self.buffer
+            .add(&indent)
+            .add(&header)
+            .end_line(self.pads.eol());
+    }
+
     fn format_container_expanded(
         &mut self,
         item: &JsonItem,
@@ -797,40 +2819,180 @@ impl Formatter {
         let template_to_pass = if align_props { Some(template) } else { None };
 
         let last_element_index = Self::index_of_last_element(&item.children);
+        let mut ends_with_bracket_line = false;
+        let mut commented_lines = Vec::new();
+        let mut last_written = None;
+        self.open_containers += 1;
         for (i, child) in item.children.iter().enumerate() {
+            let first_line = self.buffer.line_count();
             self.format_item(
                 child,
                 depth_after_colon + 1,
                 (i as isize) < last_element_index,
                 template_to_pass,
             );
+
+            if self.exceeds_line_budget() {
+                self.discard_lines_from(first_line);
+                commented_lines.retain(|&(line, _)| line < first_line);
+                if self.elide_elements(&item.children[i..], last_written, depth_after_colon + 1) {
+                    ends_with_bracket_line = false;
+                }
+                break;
+            }
+            if !Self::is_comment_or_blank_line(child.item_type) {
+                last_written = Some((self.buffer.line_count() - 1, child));
+            }
+            if self.output_elided {
+                // Something inside `child` was left out, so nothing more fits.
+                if self.elide_elements(&item.children[i + 1..], last_written, depth_after_colon + 1)
+                {
+                    ends_with_bracket_line = false;
+                    break;
+                }
+            }
+
+            if child.postfix_comment_length > 0 && self.buffer.line_count() == first_line + 1 {
+                commented_lines.push((first_line, child.postfix_comment.as_str()));
+            }
+            // A container written on several lines ends with a line holding
+            // just its closing bracket.
+            ends_with_bracket_line = Self::is_container(child)
+                && child.postfix_comment_length == 0
+                && self.buffer.line_count() > first_line + 1;
+            if self.output_elided {
+                break;
+            }
         }
+        self.open_containers -= 1;
 
-        let indent = self.pads.indent(depth_after_colon);
+        if self.options.align_postfix_comments {
+            self.align_postfix_comments(&commented_lines);
+        }
+
+        if self.options.collapse_closing_brackets && ends_with_bracket_line {
+            self.buffer.reopen_last_line(self.pads.eol());
+        } else {
+            let indent = self.pads.indent(depth_after_colon);
+            self.add_line_prefix(depth_after_colon);
+            self.buffer.add(&indent);
+        }
         self.buffer
-            .add(&self.options.prefix_string)
-            .add(&indent)
             .add(self.pads.end(item.item_type, BracketPaddingType::Empty));
         self.standard_format_end(item, include_trailing_comma);
     }
 
+    /// Whether the output has grown too long to still close every open
+    /// container, with a marker line for each, within `max_output_lines`.
+    fn exceeds_line_budget(&self) -> bool {
+        self.options
+            .max_output_lines
+            .is_some_and(|max| self.buffer.line_count() + 2 * self.open_containers > max)
+    }
+
+    fn discard_lines_from(&mut self, line: usize) {
+        self.buffer.truncate_lines(line);
+        if let Some(trace) = &mut self.trace {
+            trace.discard_from(line);
+        }
+    }
+
+    /// Leaves out the elements in `rest` under `max_output_lines`, writing a
+    /// marker comment in their place. `last_written` is the last line and
+    /// the item of the element before them, whose comma is removed. Returns
+    /// whether a marker was written: there's none if `rest` holds only
+    /// comments and blank lines.
+    fn elide_elements(
+        &mut self,
+        rest: &[JsonItem],
+        last_written: Option<(usize, &JsonItem)>,
+        depth: usize,
+    ) -> bool {
+        self.output_elided = true;
+        let count = rest
+            .iter()
+            .filter(|child| !Self::is_comment_or_blank_line(child.item_type))
+            .count();
+        if count == 0 {
+            return false;
+        }
+
+        if let Some((line, element)) = last_written {
+            self.remove_trailing_comma(line, element);
+        }
+        let noun = if count == 1 { "item" } else { "items" };
+        let indent = self.pads.indent(depth);
+        self.add_line_prefix(depth);
+        self.buffer
+            .add(&indent)
+            .add(&format!("// \u{2026} {} more {} \u{2026}", count, noun))
+            .end_line(self.pads.eol());
+        true
+    }
+
+    /// Removes the comma after `item`, which ends on finished line `line`.
+    fn remove_trailing_comma(&mut self, line: usize, item: &JsonItem) {
+        let eol_len = self.pads.eol().len();
+        let Some(text) = self.buffer.line_mut(line) else {
+            return;
+        };
+        let mut end = text.len() - eol_len;
+        // The comma goes before a line comment and after anything else.
+        if item.postfix_comment_length > 0 && item.is_post_comment_line_style {
+            end = text[..end].rfind(&item.postfix_comment).unwrap_or(end);
+        }
+        let before = text[..end].trim_end();
+        if before.ends_with(',') {
+            text.remove(before.len() - 1);
+        }
+    }
+
+    /// Pads finished lines so the postfix comments on them start in the same
+    /// column. Each entry is a line number and the comment that ends the line,
+    /// save for a comma.
+    fn align_postfix_comments(&mut self, commented_lines: &[(usize, &str)]) {
+        let starts: Vec<(usize, usize, usize)> = commented_lines
+            .iter()
+            .filter_map(|&(index, comment)| {
+                let start = self.buffer.line(index)?.rfind(comment)?;
+                let column = (self.string_length_func)(&self.buffer.line(index)?[..start]);
+                Some((index, start, column))
+            })
+            .collect();
+        let Some(max_column) = starts.iter().map(|&(_, _, column)| column).max() else {
+            return;
+        };
+        for (index, start, column) in starts {
+            self.buffer.insert_spaces(index, start, max_column - column);
+        }
+    }
+
     fn format_standalone_comment(&mut self, item: &JsonItem, depth: usize) {
         let comment_rows =
             Self::normalize_multiline_comment(&item.value, item.input_position.column);
         let indent = self.pads.indent(depth);
         for line in comment_rows {
+            self.add_line_prefix(depth);
             self.buffer
-                .add(&self.options.prefix_string)
                 .add(&indent)
                 .add(&line)
                 .end_line(self.pads.eol());
         }
     }
 
-    fn format_blank_line(&mut self) {
-        self.buffer
-            .add(&self.options.prefix_string)
-            .end_line(self.pads.eol());
+    fn format_blank_line(&mut self, depth: usize) {
+        self.add_line_prefix(depth);
+        self.buffer.end_line(self.pads.eol());
+    }
+
+    /// Writes what goes at the start of every line: `prefix_string`, then the
+    /// output of `line_prefix_func` if there is one.
+    fn add_line_prefix(&mut self, depth: usize) {
+        self.buffer.add(&self.options.prefix_string);
+        if let Some(prefix_func) = &self.line_prefix_func {
+            let prefix = prefix_func(self.buffer.line_count(), depth);
+            self.buffer.add(&prefix);
+        }
     }
 
     fn format_inline_element(
@@ -841,7 +3003,8 @@ impl Formatter {
         parent_template: Option<&TableTemplate>,
     ) {
         let indent = self.pads.indent(depth);
-        self.buffer.add(&self.options.prefix_string).add(&indent);
+        self.add_line_prefix(depth);
+        self.buffer.add(&indent);
         self.inline_element(item, include_trailing_comma, parent_template);
         self.buffer.end_line(self.pads.eol());
     }
@@ -865,7 +3028,8 @@ impl Formatter {
         parent_template: Option<&TableTemplate>,
     ) -> usize {
         let indent = self.pads.indent(depth);
-        self.buffer.add(&self.options.prefix_string).add(&indent);
+        self.add_line_prefix(depth);
+        self.buffer.add(&indent);
 
         let comment_sep = self.pads.comment().to_string();
         let colon_sep = self.pads.colon().to_string();
@@ -917,24 +3081,22 @@ impl Formatter {
         self.buffer.end_line(self.pads.eol());
         let indent = self.pads.indent(depth + 1);
         for row in comment_rows {
-            self.buffer
-                .add(&self.options.prefix_string)
-                .add(&indent)
-                .add(&row)
-                .end_line(self.pads.eol());
+            self.add_line_prefix(depth + 1);
+            self.buffer.add(&indent).add(&row).end_line(self.pads.eol());
         }
         let indent = self.pads.indent(depth + 1);
-        self.buffer.add(&self.options.prefix_string).add(&indent);
+        self.add_line_prefix(depth + 1);
+        self.buffer.add(&indent);
         depth + 1
     }
 
     fn standard_format_end(&mut self, item: &JsonItem, include_trailing_comma: bool) {
         if include_trailing_comma && item.is_post_comment_line_style {
-            self.buffer.add(self.pads.comma());
+            self.buffer.add(self.pads.postfix_comma());
         }
         if item.postfix_comment_length > 0 {
             self.buffer
-                .add(self.pads.comment())
+                .add(self.pads.postfix_comment())
                 .add(&item.postfix_comment);
         }
         if include_trailing_comma && !item.is_post_comment_line_style {
@@ -953,6 +3115,14 @@ impl Formatter {
             return;
         }
 
+        self.inline_element_start(item, parent_template);
+        self.inline_element_raw(item);
+        self.inline_element_end(item, include_trailing_comma);
+    }
+
+    /// Writes the prefix comment, name and middle comment of an item written
+    /// on a single line.
+    fn inline_element_start(&mut self, item: &JsonItem, parent_template: Option<&TableTemplate>) {
         let comment_sep = self.pads.comment().to_string();
         let colon_sep = self.pads.colon().to_string();
 
@@ -991,15 +3161,17 @@ impl Formatter {
                 &comment_sep,
             );
         }
+    }
 
-        self.inline_element_raw(item);
-
+    /// Writes the comma and postfix comment of an item written on a single
+    /// line.
+    fn inline_element_end(&mut self, item: &JsonItem, include_trailing_comma: bool) {
         if include_trailing_comma && item.is_post_comment_line_style {
-            self.buffer.add(self.pads.comma());
+            self.buffer.add(self.pads.postfix_comma());
         }
         if item.postfix_comment_length > 0 {
             self.buffer
-                .add(self.pads.comment())
+                .add(self.pads.postfix_comment())
                 .add(&item.postfix_comment);
         }
         if include_trailing_comma && !item.is_post_comment_line_style {
@@ -1048,10 +3220,15 @@ impl Formatter {
             &comment_sep,
             false,
         );
+        let name_width = if template.unpadded_names {
+            item.name_length
+        } else {
+            template.name_length
+        };
         self.add_to_buffer_fixed(
             &item.name,
             item.name_length,
-            template.name_length,
+            name_width,
             &colon_sep,
             self.options.colon_before_prop_name_padding,
         );
@@ -1067,7 +3244,10 @@ impl Formatter {
             == TableCommaPlacement::BeforePadding
             || (self.options.table_comma_placement
                 == TableCommaPlacement::BeforePaddingExceptNumbers
-                && template.column_type != TableColumnType::Number);
+                && !matches!(
+                    template.column_type,
+                    TableColumnType::Number | TableColumnType::Boolean
+                ));
 
         let comma_pos =
             if template.postfix_comment_length > 0 && !template.is_any_post_comment_line_style {
@@ -1088,12 +3268,15 @@ impl Formatter {
                 CommaPosition::AfterValuePadding
             };
 
-        let comma_type = if include_trailing_comma {
-            self.pads.comma().to_string()
-        } else if is_whole_row {
-            self.pads.dummy_comma().to_string()
-        } else {
-            String::new()
+        let before_line_comment =
+            template.postfix_comment_length > 0 && template.is_any_post_comment_line_style;
+        let comma_type = match (include_trailing_comma, is_whole_row) {
+            (true, true) if before_line_comment => self.pads.postfix_comma().to_string(),
+            (true, true) => self.pads.comma().to_string(),
+            (true, false) => self.pads.table_comma().to_string(),
+            (false, true) if before_line_comment => self.pads.postfix_dummy_comma().to_string(),
+            (false, true) => self.pads.dummy_comma().to_string(),
+            (false, false) => String::new(),
         };
 
         if !template.children.is_empty() && item.item_type != JsonItemType::Null {
@@ -1115,13 +3298,42 @@ impl Formatter {
                 ""
             };
             template.format_number(&mut self.buffer, item, number_comma_type);
+            if let Some(trace) = &mut self.trace {
+                if let Some(text) = template.normalized_text(item) {
+                    trace.record_number(item, text);
+                }
+            }
+        } else if template.column_type == TableColumnType::Boolean {
+            // Right-aligned like numbers, so `true` and `false` end together.
+            self.buffer
+                .spaces(template.composite_value_length - item.value_length);
+            self.inline_element_raw(item);
+            if matches!(comma_pos, CommaPosition::BeforeValuePadding) {
+                self.buffer.add(&comma_type);
+            }
         } else {
+            let padding = template.composite_value_length - item.value_length;
+            let alignment = match template.string_cell_alignment() {
+                Some(CellAlignment::Left) => StringColumnAlignment::Left,
+                Some(CellAlignment::Right) => StringColumnAlignment::Right,
+                Some(CellAlignment::Center) => StringColumnAlignment::Center,
+                _ if template.column_type == TableColumnType::DateTime => {
+                    StringColumnAlignment::Left
+                }
+                _ => self.options.string_column_alignment,
+            };
+            let left_padding = match (template.unit_padding(item), alignment) {
+                (Some(unit_padding), _) => unit_padding,
+                (None, StringColumnAlignment::Left) => 0,
+                (None, StringColumnAlignment::Right) => padding,
+                (None, StringColumnAlignment::Center) => padding / 2,
+            };
+            self.buffer.spaces(left_padding);
             self.inline_element_raw(item);
             if matches!(comma_pos, CommaPosition::BeforeValuePadding) {
                 self.buffer.add(&comma_type);
             }
-            self.buffer
-                .spaces(template.composite_value_length - item.value_length);
+            self.buffer.spaces(padding - left_padding);
         }
 
         if matches!(comma_pos, CommaPosition::AfterValuePadding) {
@@ -1130,7 +3342,7 @@ impl Formatter {
 
         if template.postfix_comment_length > 0 {
             self.buffer
-                .add(self.pads.comment())
+                .add(self.pads.postfix_comment())
                 .add(&item.postfix_comment);
         }
 
@@ -1156,10 +3368,14 @@ impl Formatter {
             let is_last_in_array = i == item.children.len().saturating_sub(1);
             let is_past_end = i >= item.children.len();
 
-            if is_past_end {
+            if template.unaligned_from.is_some_and(|from| i >= from) {
+                if !is_past_end {
+                    self.inline_element(&item.children[i], !is_last_in_array, None);
+                }
+            } else if is_past_end {
                 self.buffer.spaces(sub_template.total_length);
                 if !is_last_in_template {
-                    self.buffer.add(self.pads.dummy_comma());
+                    self.buffer.add(self.pads.table_dummy_comma());
                 }
             } else {
                 self.inline_table_row_segment(
@@ -1169,20 +3385,54 @@ impl Formatter {
                     false,
                 );
                 if is_last_in_array && !is_last_in_template {
-                    self.buffer.add(self.pads.dummy_comma());
+                    self.buffer.add(self.pads.table_dummy_comma());
                 }
             }
         }
+        if self.options.trim_table_row_padding {
+            self.buffer.trim_end();
+        }
         self.buffer.add(self.pads.arr_end(template.pad_type));
     }
 
     fn inline_table_raw_object(&mut self, template: &TableTemplate, item: &JsonItem) {
-        let mut matches: Vec<(&TableTemplate, Option<&JsonItem>)> = Vec::new();
-        for sub in &template.children {
-            let matched = item
+        let blank_nulls = self.options.blank_table_nulls;
+        let find_member = |sub: &TableTemplate| {
+            item.children
+                .iter()
+                .find(|ch| ch.name == sub.location_in_parent.clone().unwrap_or_default())
+        };
+
+        // Stand-ins for the members this row lacks, when they're written as
+        // nulls.
+        let mut fillers = Vec::new();
+        if self.options.fill_missing_table_keys {
+            for sub in template
                 .children
                 .iter()
-                .find(|ch| ch.name == sub.location_in_parent.clone().unwrap_or_default());
+                .filter(|sub| find_member(sub).is_none())
+            {
+                let name = sub.location_in_parent.clone().unwrap_or_default();
+                fillers.push(JsonItem {
+                    item_type: JsonItemType::Null,
+                    name_length: (self.string_length_func)(&name),
+                    name,
+                    value: "null".to_string(),
+                    value_length: self.pads.literal_null_len(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        let mut matches: Vec<(&TableTemplate, Option<&JsonItem>)> = Vec::new();
+        for sub in &template.children {
+            let matched = find_member(sub)
+                .or_else(|| {
+                    fillers
+                        .iter()
+                        .find(|filler| Some(&filler.name) == sub.location_in_parent.as_ref())
+                })
+                .filter(|ch| !(blank_nulls && ch.item_type == JsonItemType::Null));
             matches.push((sub, matched));
         }
 
@@ -1196,26 +3446,32 @@ impl Formatter {
             let is_last_in_object = i as isize == last_non_null_idx;
             let is_last_in_template = i == matches.len() - 1;
 
-            if let Some(item) = sub_item {
+            if template.unaligned_from.is_some_and(|from| i >= from) {
+                if let Some(item) = sub_item {
+                    self.inline_element(item, !is_last_in_object, None);
+                }
+            } else if let Some(item) = sub_item {
                 self.inline_table_row_segment(sub_template, item, !is_last_in_object, false);
                 if is_last_in_object && !is_last_in_template {
-                    self.buffer.add(self.pads.dummy_comma());
+                    self.buffer.add(self.pads.table_dummy_comma());
                 }
             } else {
                 self.buffer.spaces(sub_template.total_length);
                 if !is_last_in_template {
-                    self.buffer.add(self.pads.dummy_comma());
+                    self.buffer.add(self.pads.table_dummy_comma());
                 }
             }
         }
+        if self.options.trim_table_row_padding {
+            self.buffer.trim_end();
+        }
         self.buffer.add(self.pads.obj_end(template.pad_type));
     }
 
     fn available_line_space(&self, depth: usize) -> usize {
-        self.options
-            .max_total_line_length
+        self.line_length_limit
             .saturating_sub(self.pads.prefix_string_len())
-            .saturating_sub(self.options.indent_spaces.saturating_mul(depth))
+            .saturating_sub(self.pads.indent_width(depth))
     }
 
     fn minify_item(&mut self, item: &JsonItem, at_start_of_new_line: bool) -> bool {
@@ -1236,26 +3492,7 @@ impl Formatter {
 
         match item.item_type {
             JsonItemType::Array | JsonItemType::Object => {
-                let close_bracket = if item.item_type == JsonItemType::Array {
-                    self.buffer.add("[");
-                    "]"
-                } else {
-                    self.buffer.add("{");
-                    "}"
-                };
-
-                let mut needs_comma = false;
-                let mut at_start = false;
-                for child in &item.children {
-                    if !Self::is_comment_or_blank_line(child.item_type) {
-                        if needs_comma {
-                            self.buffer.add(",");
-                        }
-                        needs_comma = true;
-                    }
-                    at_start = self.minify_item(child, at_start);
-                }
-                self.buffer.add(close_bracket);
+                self.minify_container(item);
             }
             JsonItemType::BlankLine => {
                 if !at_start_of_new_line {
@@ -1302,6 +3539,30 @@ impl Formatter {
         false
     }
 
+    /// Writes the brackets and minified contents of an array or object.
+    fn minify_container(&mut self, item: &JsonItem) {
+        let close_bracket = if item.item_type == JsonItemType::Array {
+            self.buffer.add("[");
+            "]"
+        } else {
+            self.buffer.add("{");
+            "}"
+        };
+
+        let mut needs_comma = false;
+        let mut at_start = false;
+        for child in &item.children {
+            if !Self::is_comment_or_blank_line(child.item_type) {
+                if needs_comma {
+                    self.buffer.add(",");
+                }
+                needs_comma = true;
+            }
+            at_start = self.minify_item(child, at_start);
+        }
+        self.buffer.add(close_bracket);
+    }
+
     fn add_to_buffer(&mut self, value: &str, value_width: usize, separator: &str) {
         if value_width == 0 {
             return;
@@ -1364,6 +3625,21 @@ impl Formatter {
         comment_rows
     }
 
+    fn is_primitive_array(item: &JsonItem) -> bool {
+        item.item_type == JsonItemType::Array
+            && !item.children.is_empty()
+            && item.children.iter().all(|child| {
+                matches!(
+                    child.item_type,
+                    JsonItemType::Null
+                        | JsonItemType::False
+                        | JsonItemType::True
+                        | JsonItemType::String
+                        | JsonItemType::Number
+                )
+            })
+    }
+
     fn index_of_last_element(item_list: &[JsonItem]) -> isize {
         for (i, item) in item_list.iter().enumerate().rev() {
             if !Self::is_comment_or_blank_line(item.item_type) {
@@ -0,0 +1,284 @@
+//! Random JSON documents for benchmarks and fuzz tests.
+//!
+//! [`Generator`] produces reproducible documents (the same seed gives the
+//! same output) whose shape is controlled by [`GeneratorOptions`]: nesting
+//! depth, number of children per container, how often comments appear, and
+//! how numbers are written.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fracturedjson::gen::{Generator, GeneratorOptions};
+//! use fracturedjson::{CommentPolicy, Formatter};
+//!
+//! let mut generator = Generator::new(GeneratorOptions {
+//!     seed: 7,
+//!     comment_density: 0.2,
+//!     ..Default::default()
+//! });
+//! let document = generator.document();
+//!
+//! let mut formatter = Formatter::new();
+//! formatter.options.comment_policy = CommentPolicy::Preserve;
+//! assert!(formatter.reformat(&document, 0).is_ok());
+//! ```
+
+/// How generated numbers are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberStyle {
+    /// Whole numbers such as `-42`.
+    Integer,
+    /// Numbers with a fractional part such as `3.25`.
+    Decimal,
+    /// Numbers with an exponent such as `1.5e-3`.
+    Exponent,
+    /// Any of the above, picked at random for each number.
+    Mixed,
+}
+
+/// Settings for [`Generator`].
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    /// Seed for the random number generator. Default: 0.
+    pub seed: u64,
+
+    /// Maximum nesting depth of arrays and objects. The top-level value is
+    /// always a container unless this is 0.
+    /// Default: 4.
+    pub max_depth: usize,
+
+    /// Maximum number of children in each array or object.
+    /// Default: 8.
+    pub max_fan_out: usize,
+
+    /// Probability, from 0.0 to 1.0, that a comment is written before or
+    /// after each element. Documents with comments need
+    /// `CommentPolicy::Preserve` or `CommentPolicy::Remove` to be parsed.
+    /// Default: 0.0.
+    pub comment_density: f64,
+
+    /// How numbers are written. Default: [`NumberStyle::Mixed`].
+    pub number_style: NumberStyle,
+
+    /// Write each element on its own line (true) or the whole document on a
+    /// single line (false). Single-line documents only get block comments.
+    /// Default: true.
+    pub multiline: bool,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            max_depth: 4,
+            max_fan_out: 8,
+            comment_density: 0.0,
+            number_style: NumberStyle::Mixed,
+            multiline: true,
+        }
+    }
+}
+
+/// Produces random JSON documents according to [`GeneratorOptions`].
+#[derive(Debug, Clone)]
+pub struct Generator {
+    options: GeneratorOptions,
+    state: u64,
+}
+
+impl Generator {
+    /// Creates a generator. Generators created with the same options
+    /// produce the same sequence of documents.
+    pub fn new(options: GeneratorOptions) -> Self {
+        let state = options.seed;
+        Self { options, state }
+    }
+
+    /// Generates the next document.
+    pub fn document(&mut self) -> String {
+        let mut out = String::new();
+        self.write_value(&mut out, 0);
+        if self.options.multiline {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Generates `count` single-line documents, one per line (JSON Lines).
+    pub fn jsonl(&mut self, count: usize) -> String {
+        let multiline = std::mem::replace(&mut self.options.multiline, false);
+        let mut out = String::new();
+        for _ in 0..count {
+            self.write_value(&mut out, 0);
+            out.push('\n');
+        }
+        self.options.multiline = multiline;
+        out
+    }
+
+    fn write_value(&mut self, out: &mut String, depth: usize) {
+        let can_nest = depth < self.options.max_depth;
+        if can_nest && (depth == 0 || self.chance(0.4)) {
+            let is_object = self.chance(0.5);
+            self.write_container(out, depth, is_object);
+            return;
+        }
+
+        match self.below(10) {
+            0 => out.push_str("null"),
+            1 => out.push_str("true"),
+            2 => out.push_str("false"),
+            3..=5 => self.write_number(out),
+            _ => {
+                let word_count = 1 + self.below(4);
+                self.write_string(out, word_count);
+            }
+        }
+    }
+
+    fn write_container(&mut self, out: &mut String, depth: usize, is_object: bool) {
+        let (open, close) = if is_object { ('{', '}') } else { ('[', ']') };
+        let count = self.below(self.options.max_fan_out.saturating_add(1));
+        let mut keys: Vec<String> = Vec::new();
+        out.push(open);
+
+        for i in 0..count {
+            if self.options.multiline {
+                Self::new_line(out, depth + 1);
+                if self.chance(self.options.comment_density) {
+                    self.write_comment(out, true);
+                    Self::new_line(out, depth + 1);
+                }
+            } else if self.chance(self.options.comment_density) {
+                self.write_comment(out, false);
+            }
+
+            if is_object {
+                let key = self.unique_key(&keys);
+                out.push_str(&format!("\"{}\": ", key));
+                keys.push(key);
+            }
+            self.write_value(out, depth + 1);
+            if i + 1 < count {
+                out.push(',');
+            }
+
+            if self.chance(self.options.comment_density) {
+                out.push(' ');
+                self.write_comment(out, self.options.multiline);
+            }
+        }
+
+        if self.options.multiline && count > 0 {
+            Self::new_line(out, depth);
+        }
+        out.push(close);
+    }
+
+    fn new_line(out: &mut String, depth: usize) {
+        out.push('\n');
+        for _ in 0..depth {
+            out.push_str("  ");
+        }
+    }
+
+    /// Writes a comment. Line comments are only used where a line break
+    /// follows, which is always the case when `allow_line` is set.
+    fn write_comment(&mut self, out: &mut String, allow_line: bool) {
+        let word_count = 1 + self.below(5);
+        let text = self.words(word_count);
+        if allow_line && self.chance(0.5) {
+            out.push_str("// ");
+            out.push_str(&text);
+        } else {
+            out.push_str("/* ");
+            out.push_str(&text);
+            out.push_str(" */");
+        }
+    }
+
+    fn unique_key(&mut self, used: &[String]) -> String {
+        let mut key = WORDS[self.below(WORDS.len())].to_string();
+        if self.chance(0.3) {
+            key.push('_');
+            key.push_str(WORDS[self.below(WORDS.len())]);
+        }
+        if used.contains(&key) {
+            key.push_str(&used.len().to_string());
+        }
+        key
+    }
+
+    fn write_string(&mut self, out: &mut String, word_count: usize) {
+        out.push('"');
+        out.push_str(&self.words(word_count));
+        if self.chance(0.1) {
+            out.push_str(["\\n", "\\\"", "\\u00e9", "\\t"][self.below(4)]);
+        }
+        out.push('"');
+    }
+
+    fn write_number(&mut self, out: &mut String) {
+        let style = match self.options.number_style {
+            NumberStyle::Mixed => [
+                NumberStyle::Integer,
+                NumberStyle::Decimal,
+                NumberStyle::Exponent,
+            ][self.below(3)],
+            style => style,
+        };
+        if self.chance(0.2) {
+            out.push('-');
+        }
+        match style {
+            NumberStyle::Decimal => {
+                let whole = self.below(10_000);
+                let fraction = self.below(1_000);
+                out.push_str(&format!("{}.{}", whole, fraction));
+            }
+            NumberStyle::Exponent => {
+                let mantissa = 1 + self.below(9);
+                let fraction = self.below(100);
+                let exponent = self.below(20) as i64 - 10;
+                out.push_str(&format!("{}.{}e{}", mantissa, fraction, exponent));
+            }
+            _ => out.push_str(&self.below(1_000_000).to_string()),
+        }
+    }
+
+    fn words(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| WORDS[self.below(WORDS.len())])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// SplitMix64.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A random number in `0..bound` (0 if `bound` is 0).
+    fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+const WORDS: &[&str] = &[
+    "id", "name", "value", "items", "count", "status", "created", "updated", "user", "group",
+    "enabled", "tags", "path", "size", "type", "data", "config", "limit", "offset", "label",
+];
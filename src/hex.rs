@@ -0,0 +1,77 @@
+use crate::convert::escape_pointer_token;
+use crate::model::{JsonItem, JsonItemType};
+
+/// Rewrites the non-negative integers in `doc_model` that `rules` pick as
+/// strings of hexadecimal digits, like `"0xFF"`. Rules starting with `/` are
+/// JSON Pointers; the others are keys, matched in every object.
+pub fn apply_hex_integers(doc_model: &mut [JsonItem], rules: &[String]) {
+    for item in doc_model.iter_mut() {
+        rewrite_children(item, "", rules);
+    }
+}
+
+fn rewrite_children(item: &mut JsonItem, pointer: &str, rules: &[String]) {
+    let is_object = item.item_type == JsonItemType::Object;
+    let mut index = 0;
+    for child in item.children.iter_mut() {
+        if matches!(
+            child.item_type,
+            JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+        ) {
+            continue;
+        }
+
+        let key = if is_object {
+            match serde_json::from_str::<String>(&child.name) {
+                Ok(key) => Some(key),
+                Err(_) => continue,
+            }
+        } else {
+            None
+        };
+        let child_pointer = match &key {
+            Some(key) => format!("{}/{}", pointer, escape_pointer_token(key)),
+            None => {
+                index += 1;
+                format!("{}/{}", pointer, index - 1)
+            }
+        };
+
+        let is_picked = rules.iter().any(|rule| {
+            *rule == child_pointer || (!rule.starts_with('/') && key.as_ref() == Some(rule))
+        });
+        if is_picked && child.item_type == JsonItemType::Number {
+            if let Some(hex) = hex_text(&child.value) {
+                child.value = hex;
+                child.item_type = JsonItemType::String;
+            }
+        }
+
+        rewrite_children(child, &child_pointer, rules);
+    }
+}
+
+/// The JSON string holding `number` in hexadecimal, or None if it isn't a
+/// plain non-negative integer that fits in 64 bits.
+fn hex_text(number: &str) -> Option<String> {
+    if !number.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let value: u64 = number.parse().ok()?;
+    Some(format!("\"0x{:X}\"", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_integers_become_hex() {
+        assert_eq!(hex_text("255").as_deref(), Some("\"0xFF\""));
+        assert_eq!(hex_text("0").as_deref(), Some("\"0x0\""));
+        assert_eq!(hex_text("-1"), None);
+        assert_eq!(hex_text("1.5"), None);
+        assert_eq!(hex_text("1e3"), None);
+        assert_eq!(hex_text("99999999999999999999"), None);
+    }
+}
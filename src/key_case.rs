@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use crate::convert::escape_pointer_token;
+use crate::error::FracturedJsonError;
+use crate::model::{JsonItem, JsonItemType};
+use crate::options::KeyCase;
+
+/// Renames the object keys in `doc_model` to `case`, skipping the properties
+/// named by `exclusions` (JSON Pointers) and everything below them. Fails if
+/// two different keys of an object would get the same name.
+pub fn apply_key_case(
+    doc_model: &mut [JsonItem],
+    case: KeyCase,
+    exclusions: &[String],
+) -> Result<(), FracturedJsonError> {
+    for item in doc_model.iter_mut() {
+        convert_children(item, "", case, exclusions)?;
+    }
+    Ok(())
+}
+
+fn convert_children(
+    item: &mut JsonItem,
+    pointer: &str,
+    case: KeyCase,
+    exclusions: &[String],
+) -> Result<(), FracturedJsonError> {
+    let is_object = item.item_type == JsonItemType::Object;
+    // Each key as written, with the input key it came from.
+    let mut sources: HashMap<String, String> = HashMap::new();
+    let mut index = 0;
+    for child in item.children.iter_mut() {
+        if matches!(
+            child.item_type,
+            JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+        ) {
+            continue;
+        }
+
+        let child_pointer = if is_object {
+            match serde_json::from_str::<String>(&child.name) {
+                Ok(key) => {
+                    let child_pointer = format!("{}/{}", pointer, escape_pointer_token(&key));
+                    let is_excluded = exclusions.contains(&child_pointer);
+                    let converted = if is_excluded {
+                        key.clone()
+                    } else {
+                        convert_key(&key, case)
+                    };
+                    if let Some(source) = sources.insert(converted.clone(), key.clone()) {
+                        if source != key {
+                            return Err(FracturedJsonError::simple(format!(
+                                "key_case turns \"{}\" and \"{}\" into the same key \"{}\"",
+                                source, key, converted
+                            )));
+                        }
+                    }
+                    if is_excluded {
+                        continue;
+                    }
+                    if converted != key {
+                        child.name = serde_json::to_string(&converted).unwrap_or_default();
+                    }
+                    child_pointer
+                }
+                Err(_) => continue,
+            }
+        } else {
+            index += 1;
+            format!("{}/{}", pointer, index - 1)
+        };
+
+        convert_children(child, &child_pointer, case, exclusions)?;
+    }
+    Ok(())
+}
+
+/// Converts a single key to the given naming convention. Leading and
+/// trailing separators (as in `_id`) are kept.
+pub fn convert_key(key: &str, case: KeyCase) -> String {
+    let is_separator = |ch: char| matches!(ch, '_' | '-' | ' ');
+    let core = key.trim_matches(is_separator);
+    if core.is_empty() {
+        return key.to_string();
+    }
+    let leading = &key[..key.len() - key.trim_start_matches(is_separator).len()];
+    let trailing = &key[key.trim_end_matches(is_separator).len()..];
+
+    let words = split_words(core);
+    let joined = match case {
+        KeyCase::CamelCase => words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                if i == 0 {
+                    word.to_lowercase()
+                } else {
+                    capitalize(word)
+                }
+            })
+            .collect::<String>(),
+        KeyCase::SnakeCase => lowercase_words(&words).join("_"),
+        KeyCase::KebabCase => lowercase_words(&words).join("-"),
+    };
+    format!("{}{}{}", leading, joined, trailing)
+}
+
+fn split_words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if matches!(ch, '_' | '-' | ' ') {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if ch.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            // "userName" splits before "N"; "HTTPServer" splits before "S".
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn lowercase_words(words: &[String]) -> Vec<String> {
+    words.iter().map(|word| word.to_lowercase()).collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_cases() {
+        let cases = [
+            ("firstName", "firstName", "first_name", "first-name"),
+            ("first_name", "firstName", "first_name", "first-name"),
+            ("first-name", "firstName", "first_name", "first-name"),
+            ("FirstName", "firstName", "first_name", "first-name"),
+            ("HTTPServer", "httpServer", "http_server", "http-server"),
+            ("userID", "userId", "user_id", "user-id"),
+            (
+                "address2Line",
+                "address2Line",
+                "address2_line",
+                "address2-line",
+            ),
+            ("_id", "_id", "_id", "_id"),
+            ("__meta__key", "__metaKey", "__meta_key", "__meta-key"),
+            ("", "", "", ""),
+        ];
+        for (input, camel, snake, kebab) in cases {
+            assert_eq!(convert_key(input, KeyCase::CamelCase), camel, "{}", input);
+            assert_eq!(convert_key(input, KeyCase::SnakeCase), snake, "{}", input);
+            assert_eq!(convert_key(input, KeyCase::KebabCase), kebab, "{}", input);
+        }
+    }
+}
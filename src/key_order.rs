@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use crate::model::{JsonItem, JsonItemType};
+
+/// Reorders the properties of every object in `doc_model` so that keys found
+/// in `key_order` come first, in that order, followed by the rest: sorted by
+/// key if `sort_rest` is set, otherwise in their original order.
+///
+/// Standalone comments and blank lines move with the property after them;
+/// those after the last property stay at the end of the object.
+pub fn apply_key_order(doc_model: &mut [JsonItem], key_order: &[String], sort_rest: bool) {
+    let mut ranks = HashMap::new();
+    for (rank, key) in key_order.iter().enumerate() {
+        ranks.entry(key.as_str()).or_insert(rank);
+    }
+    for item in doc_model.iter_mut() {
+        reorder_item(item, &ranks, sort_rest);
+    }
+}
+
+fn reorder_item(item: &mut JsonItem, ranks: &HashMap<&str, usize>, sort_rest: bool) {
+    for child in item.children.iter_mut() {
+        reorder_item(child, ranks, sort_rest);
+    }
+    if item.item_type != JsonItemType::Object {
+        return;
+    }
+
+    // Each group is a property together with the trivia in front of it.
+    let mut groups: Vec<((usize, String), Vec<JsonItem>)> = Vec::new();
+    let mut pending = Vec::new();
+    for child in std::mem::take(&mut item.children) {
+        let is_trivia = matches!(
+            child.item_type,
+            JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+        );
+        if is_trivia {
+            pending.push(child);
+            continue;
+        }
+
+        let key = serde_json::from_str::<String>(&child.name).unwrap_or_default();
+        let rank = match ranks.get(key.as_str()) {
+            Some(&rank) => (rank, String::new()),
+            None if sort_rest => (usize::MAX, key),
+            None => (usize::MAX, String::new()),
+        };
+        pending.push(child);
+        groups.push((rank, std::mem::take(&mut pending)));
+    }
+
+    groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+    item.children = groups.into_iter().flat_map(|(_, group)| group).collect();
+    item.children.append(&mut pending);
+}
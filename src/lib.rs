@@ -119,20 +119,55 @@
 //! - Similar objects are aligned in a table format
 //! - Numbers are right-aligned within their columns
 //! - The structure remains compact while being highly readable
+//!
+//! ## Public API
+//!
+//! The public API is what is exported from the crate root, the [`prelude`]
+//! (the subset of it most programs need, for a glob import) and the [`gen`]
+//! module. The modules that
+//! implement formatting are private, so their contents can change in any
+//! release.
 
 mod buffer;
+mod comment_spacing;
+mod compare;
 mod convert;
 mod error;
+mod exponent;
+mod export;
 mod formatter;
+pub mod gen;
+mod hex;
+mod key_case;
+mod key_order;
 mod model;
+mod notation;
 mod options;
 mod parser;
+pub mod prelude;
+mod round_trip;
+mod table_info;
 mod table_template;
 mod tokenizer;
+mod trace;
+mod trailing_zeros;
+mod truncate;
+mod type_skeleton;
+mod unicode_escape;
 
+pub use crate::compare::OptionsComparison;
 pub use crate::error::FracturedJsonError;
 pub use crate::formatter::Formatter;
-pub use crate::model::{InputPosition, JsonItemType};
+pub use crate::model::{
+    CommentMap, InputEdit, InputPosition, ItemComments, JsonItem, JsonItemType, Span, Trivia,
+};
 pub use crate::options::{
-    CommentPolicy, EolStyle, FracturedJsonOptions, NumberListAlignment, TableCommaPlacement,
+    CellAlignment, CommentPolicy, CommentSpacing, CommentStyle, ControlCharPolicy, DecimalRounding,
+    EolStyle, ExponentStyle, FracturedJsonOptions, KeyCase, LayoutHint, NormalizeFallback,
+    NumberListAlignment, NumberNotation, StandaloneCommentOrder, StringColumnAlignment,
+    TableColumnOrder, TableCommaPlacement,
 };
+pub use crate::parser::Parser;
+pub use crate::table_info::{TableColumnInfo, TableColumnKind, TableInfo};
+pub use crate::trace::{FormatDecision, FormatEvent, FormatTrace, NumberChange};
+pub use crate::type_skeleton::SkeletonLanguage;
@@ -60,6 +60,8 @@ pub enum TableColumnType {
     Unknown,
     Simple,
     Number,
+    Boolean,
+    DateTime,
     Array,
     Object,
     Mixed,
@@ -80,7 +82,7 @@ pub enum TableColumnType {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct InputPosition {
-    /// Byte offset from the start of the input (zero-indexed).
+    /// Character (not byte) offset from the start of the input (zero-indexed).
     pub index: usize,
     /// Line number (zero-indexed, so first line is 0).
     pub row: usize,
@@ -88,17 +90,122 @@ pub struct InputPosition {
     pub column: usize,
 }
 
+/// A range of bytes within the JSON input text.
+///
+/// `start` is inclusive and `end` is exclusive, so `&input[span.start..span.end]`
+/// is the source text that the span covers.
+///
+/// # Example
+///
+/// ```rust
+/// use fracturedjson::Span;
+///
+/// let input = "[\"é\", 1]";
+/// let span = Span { start: 1, end: 5 };
+/// assert_eq!(&input[span.start..span.end], "\"é\"");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the first byte covered (zero-indexed).
+    pub start: usize,
+    /// Byte offset just past the last byte covered.
+    pub end: usize,
+}
+
+/// Describes an edit to a document for [`Parser::reparse`](crate::Parser::reparse).
+///
+/// All values are byte offsets. The bytes `start..old_end` of the old text
+/// were replaced by the bytes `start..new_end` of the new text.
+///
+/// # Example
+///
+/// ```rust
+/// use fracturedjson::InputEdit;
+///
+/// // "[1, 2]" -> "[1, 20, 3]": replaced "2" with "20, 3"
+/// let edit = InputEdit { start: 4, old_end: 5, new_end: 9 };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEdit {
+    /// Offset where the edit begins, in both the old and new text.
+    pub start: usize,
+    /// Offset just past the replaced bytes in the old text.
+    pub old_end: usize,
+    /// Offset just past the replacement bytes in the new text.
+    pub new_end: usize,
+}
+
+/// A standalone comment or blank line recorded by
+/// [`Formatter::parse_to_value`](crate::Formatter::parse_to_value).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trivia {
+    /// A comment on its own line(s), including its `//` or `/* */` markers.
+    Comment(String),
+    /// A blank line (only recorded when `preserve_blank_lines` is enabled).
+    BlankLine,
+}
+
+/// Comments and blank lines belonging to one element of a parsed document.
+///
+/// See [`Formatter::parse_to_value`](crate::Formatter::parse_to_value).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemComments {
+    /// Standalone comments and blank lines immediately before the element.
+    pub leading: Vec<Trivia>,
+    /// A comment before the element on the same line, like `/* a */ 1`.
+    pub prefix_comment: Option<String>,
+    /// A comment between a property name and its value.
+    pub middle_comment: Option<String>,
+    /// A comment after the element on the same line, like `1, // a`.
+    pub postfix_comment: Option<String>,
+    /// For arrays and objects, standalone comments and blank lines after the
+    /// last child, before the closing bracket. For the root element, those at
+    /// the end of the document follow.
+    pub trailing: Vec<Trivia>,
+}
+
+impl ItemComments {
+    /// Returns true if nothing is recorded for the element.
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty()
+            && self.prefix_comment.is_none()
+            && self.middle_comment.is_none()
+            && self.postfix_comment.is_none()
+            && self.trailing.is_empty()
+    }
+}
+
+/// Comments and blank lines of a parsed document, keyed by the
+/// [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) of the element they
+/// belong to. The root element's key is the empty string.
+pub type CommentMap = std::collections::BTreeMap<String, ItemComments>;
+
+/// A single lexical token produced from the JSON input.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JsonToken {
     pub token_type: TokenType,
     pub text: String,
     pub input_position: InputPosition,
+    /// Byte range of the token in the input. For blank lines this covers the
+    /// whole line, including its line break.
+    pub span: Span,
 }
 
+/// An element of the parsed document: a value, a comment, or a blank line.
+///
+/// Object properties carry their name in `name`; containers hold their
+/// elements in `children`.
 #[derive(Debug, Clone)]
 pub struct JsonItem {
     pub item_type: JsonItemType,
     pub input_position: InputPosition,
+    /// Byte range of the value in the input. For arrays and objects this runs
+    /// from the opening bracket through the closing one. Attached comments are
+    /// not included.
+    pub span: Span,
+    /// Byte range of the property name in the input, or an empty span if the
+    /// item is not an object property.
+    pub name_span: Span,
     pub complexity: usize,
     pub name: String,
     pub value: String,
@@ -126,6 +233,8 @@ impl Default for JsonItem {
                 row: 0,
                 column: 0,
             },
+            span: Span::default(),
+            name_span: Span::default(),
             complexity: 0,
             name: String::new(),
             value: String::new(),
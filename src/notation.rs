@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use crate::model::{JsonItem, JsonItemType};
+use crate::options::NumberNotation;
+
+pub type NumberFormatFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Most zeros written to place a number's digits in fixed notation; numbers
+/// further from one are left as they are.
+const MAX_FIXED_ZEROS: u64 = 1000;
+
+/// Numbers whose scientific exponent is within this many of zero are
+/// written in fixed notation by `canonical_number`.
+const CANONICAL_FIXED_RANGE: u32 = 20;
+
+/// Rewrites every number in `doc_model` in `notation`, except that numbers
+/// whose scientific exponent is within `threshold` of zero are written in
+/// fixed notation.
+pub fn apply_number_notation(
+    doc_model: &mut [JsonItem],
+    notation: NumberNotation,
+    threshold: Option<u32>,
+) {
+    if notation == NumberNotation::Preserve {
+        return;
+    }
+    for item in doc_model.iter_mut() {
+        if item.item_type == JsonItemType::Number {
+            if let Some(rewritten) = rewrite_number(&item.value, notation, threshold) {
+                item.value = rewritten;
+            }
+        }
+        apply_number_notation(&mut item.children, notation, threshold);
+    }
+}
+
+/// Replaces the text of every number in `doc_model` with what `format`
+/// returns for it.
+pub fn apply_number_format(doc_model: &mut [JsonItem], format: &dyn Fn(&str) -> String) {
+    for item in doc_model.iter_mut() {
+        if item.item_type == JsonItemType::Number {
+            item.value = format(&item.value);
+        }
+        apply_number_format(&mut item.children, format);
+    }
+}
+
+/// Whether the numbers `a` and `b` have the same value, however they're
+/// written. A number that can't be read differs from everything.
+pub fn same_number(a: &str, b: &str) -> bool {
+    match (Decimal::parse(a), Decimal::parse(b)) {
+        (Some(a), Some(b)) => {
+            a.sign == b.sign && a.digits == b.digits && (a.digits.is_empty() || a.power == b.power)
+        }
+        _ => false,
+    }
+}
+
+/// `number` written the way every number with its value is, so numbers can
+/// be compared exactly: in fixed notation near one and scientific notation
+/// otherwise, like `2.5`, `-0` and `1.5e30`. None if it can't be read.
+pub fn canonical_number(number: &str) -> Option<String> {
+    let decimal = Decimal::parse(number)?;
+    if decimal.digits.is_empty() {
+        return Some(format!("{}0", decimal.sign));
+    }
+    rewrite_number(
+        number,
+        NumberNotation::Scientific,
+        Some(CANONICAL_FIXED_RANGE),
+    )
+}
+
+/// A number split into its sign, its significant digits, and the power of
+/// ten they're multiplied by: `-0.0250` is `-`, `25` and -3.
+struct Decimal<'a> {
+    sign: &'a str,
+    digits: String,
+    power: i64,
+}
+
+impl<'a> Decimal<'a> {
+    fn parse(number: &'a str) -> Option<Self> {
+        let (sign, unsigned) = match number.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", number),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(marker) => (&unsigned[..marker], &unsigned[marker + 1..]),
+            None => (unsigned, "0"),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let exponent: i64 = exponent
+            .strip_prefix('+')
+            .unwrap_or(exponent)
+            .parse()
+            .ok()?;
+
+        let all_digits = format!("{}{}", integer, fraction);
+        let significant = all_digits.trim_start_matches('0');
+        let digits = significant.trim_end_matches('0');
+        let trailing_zeros = (significant.len() - digits.len()) as i64;
+        let power = exponent
+            .checked_sub(fraction.len() as i64)?
+            .checked_add(trailing_zeros)?;
+        Some(Self {
+            sign,
+            digits: digits.to_string(),
+            power,
+        })
+    }
+
+    /// The exponent of the number in scientific notation, or None if it
+    /// doesn't fit in an `i64`.
+    fn scientific_exponent(&self) -> Option<i64> {
+        self.power.checked_add(self.digits.len() as i64 - 1)
+    }
+
+    /// The number without an exponent, or None if that takes more than
+    /// `MAX_FIXED_ZEROS` zeros.
+    fn fixed(&self) -> Option<String> {
+        let len = self.digits.len() as i64;
+        let point = len.checked_add(self.power)?;
+        let zeros = if self.power >= 0 {
+            self.power.unsigned_abs()
+        } else {
+            point.min(0).unsigned_abs()
+        };
+        if zeros > MAX_FIXED_ZEROS {
+            return None;
+        }
+        let body = if self.power >= 0 {
+            format!("{}{}", self.digits, "0".repeat(self.power as usize))
+        } else if point > 0 {
+            let (integer, fraction) = self.digits.split_at(point as usize);
+            format!("{}.{}", integer, fraction)
+        } else {
+            format!("0.{}{}", "0".repeat(-point as usize), self.digits)
+        };
+        Some(format!("{}{}", self.sign, body))
+    }
+
+    /// The number with `integer_len` digits before the decimal point,
+    /// followed by an exponent, whose scientific exponent is `exponent`.
+    fn with_exponent(&self, exponent: i64, integer_len: usize) -> String {
+        let exponent = exponent - (integer_len as i64 - 1);
+        let mut digits = self.digits.clone();
+        if digits.len() < integer_len {
+            digits.push_str(&"0".repeat(integer_len - digits.len()));
+        }
+        let (integer, fraction) = digits.split_at(integer_len);
+        let point = if fraction.is_empty() { "" } else { "." };
+        format!("{}{}{}{}e{}", self.sign, integer, point, fraction, exponent)
+    }
+}
+
+/// Returns `number` written in `notation`, or None if it can't be read or
+/// is too far from one to write in fixed notation.
+fn rewrite_number(
+    number: &str,
+    notation: NumberNotation,
+    threshold: Option<u32>,
+) -> Option<String> {
+    let decimal = Decimal::parse(number)?;
+    if decimal.digits.is_empty() {
+        return Some("0".to_string());
+    }
+
+    let exponent = decimal.scientific_exponent()?;
+    let within_threshold = threshold.is_some_and(|limit| exponent.unsigned_abs() <= limit as u64);
+    let rewritten = match notation {
+        NumberNotation::Preserve => return None,
+        _ if within_threshold => decimal.fixed()?,
+        NumberNotation::Fixed if threshold.is_none() => decimal.fixed()?,
+        NumberNotation::Fixed | NumberNotation::Scientific => decimal.with_exponent(exponent, 1),
+        NumberNotation::Engineering => {
+            decimal.with_exponent(exponent, (exponent.rem_euclid(3) + 1) as usize)
+        }
+    };
+    Some(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_rewritten_in_each_notation() {
+        let fixed = |n| rewrite_number(n, NumberNotation::Fixed, None);
+        assert_eq!(fixed("7e2").as_deref(), Some("700"));
+        assert_eq!(fixed("-2.50E-3").as_deref(), Some("-0.0025"));
+        assert_eq!(fixed("12.5").as_deref(), Some("12.5"));
+
+        let scientific = |n| rewrite_number(n, NumberNotation::Scientific, None);
+        assert_eq!(scientific("700").as_deref(), Some("7e2"));
+        assert_eq!(scientific("0.0007").as_deref(), Some("7e-4"));
+        assert_eq!(scientific("-123.45").as_deref(), Some("-1.2345e2"));
+        assert_eq!(scientific("0.000").as_deref(), Some("0"));
+
+        let engineering = |n| rewrite_number(n, NumberNotation::Engineering, None);
+        assert_eq!(engineering("70000").as_deref(), Some("70e3"));
+        assert_eq!(engineering("0.0007").as_deref(), Some("700e-6"));
+        assert_eq!(engineering("1234.5").as_deref(), Some("1.2345e3"));
+    }
+
+    #[test]
+    fn numbers_near_one_stay_fixed_within_the_threshold() {
+        let scientific = |n| rewrite_number(n, NumberNotation::Scientific, Some(3));
+        assert_eq!(scientific("7e2").as_deref(), Some("700"));
+        assert_eq!(scientific("70000").as_deref(), Some("7e4"));
+
+        let fixed = |n| rewrite_number(n, NumberNotation::Fixed, Some(3));
+        assert_eq!(fixed("1e500").as_deref(), Some("1e500"));
+        assert_eq!(fixed("0.5e1").as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn numbers_too_far_from_one_keep_their_exponent() {
+        let fixed = |n| rewrite_number(n, NumberNotation::Fixed, None);
+        assert_eq!(fixed("1e9223372036854775807"), None);
+        assert_eq!(fixed("12e9223372036854775807"), None);
+        assert_eq!(fixed("1e1000000"), None);
+        assert_eq!(fixed("-1.5e-1000000"), None);
+        assert_eq!(fixed("1e1000").map(|n| n.len()), Some(1001));
+
+        let scientific = |n| rewrite_number(n, NumberNotation::Scientific, None);
+        assert_eq!(scientific("12e9223372036854775807"), None);
+        assert_eq!(scientific("1e1000000").as_deref(), Some("1e1000000"));
+    }
+}
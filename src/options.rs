@@ -1,5 +1,10 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
 /// Line ending style for the formatted output.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EolStyle {
     /// Windows-style line endings (`\r\n`).
     Crlf,
@@ -12,7 +17,8 @@ pub enum EolStyle {
 /// Standard JSON does not support comments, but many JSON-like formats
 /// (such as JSONC used by VS Code) do allow them. This enum controls
 /// how comments are handled during formatting.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CommentPolicy {
     /// Return an error if comments are encountered in the input.
     /// This is the default, enforcing strict JSON compliance.
@@ -21,13 +27,85 @@ pub enum CommentPolicy {
     Remove,
     /// Keep comments in the output, preserving their relative positions.
     Preserve,
+    /// Keep the standalone comments before the top-level value, such as a
+    /// license header or a "generated by" banner, and remove the rest.
+    PreserveHeader,
+}
+
+/// Order of standalone comments relative to the blank lines around them.
+///
+/// Only matters when comments are preserved and `preserve_blank_lines` is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StandaloneCommentOrder {
+    /// Keep comments and blank lines in their original order.
+    /// This is the default.
+    Preserve,
+    /// Within each run of standalone comments and blank lines, write the
+    /// comments first and the blank lines after them.
+    CommentsFirst,
+}
+
+/// Spacing between comment markers and comment text, applied by
+/// `FracturedJsonOptions::comment_spacing`.
+///
+/// Comments with no text, such as `//` or `/**/`, and runs of marker
+/// characters like `////////` are always left alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentSpacing {
+    /// Write comments exactly as they appear in the input.
+    /// This is the default.
+    Preserve,
+    /// Put a space after `//` and inside `/*` and `*/` where there is none:
+    /// `//note` becomes `// note` and `/*x*/` becomes `/* x */`. Existing
+    /// spacing, such as indentation in commented-out code, is kept.
+    Spaced,
+    /// Remove spaces and tabs after `//` and inside `/*` and `*/`. Line breaks
+    /// inside multi-line block comments are kept.
+    Unspaced,
+}
+
+/// Comment syntax preferred by `FracturedJsonOptions::comment_style`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentStyle {
+    /// Write comments with the syntax they have in the input.
+    /// This is the default.
+    Preserve,
+    /// Write single-line block comments that end their line as line
+    /// comments: `/* note */` becomes `// note`. Block comments followed by
+    /// something on the same line stay block comments.
+    PreferLine,
+    /// Write line comments as block comments: `// note` becomes
+    /// `/* note */`. Line comments containing `*/` stay line comments.
+    PreferBlock,
+}
+
+/// Policy for handling raw control characters (such as tabs or newlines)
+/// inside string values.
+///
+/// Standard JSON requires these to be escaped, but data such as log output
+/// sometimes contains them verbatim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlCharPolicy {
+    /// Return an error if a string contains a raw control character.
+    /// This is the default, enforcing strict JSON compliance.
+    TreatAsError,
+    /// Accept raw control characters and write them as escape sequences
+    /// (`\t`, `\n`, `\u0001`, ...), producing valid JSON.
+    Escape,
+    /// Accept raw control characters and write them unchanged.
+    Preserve,
 }
 
 /// Alignment style for numbers in arrays formatted as tables.
 ///
 /// When arrays of numbers are formatted across multiple lines,
 /// this setting controls how the numbers are aligned within their columns.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NumberListAlignment {
     /// Align numbers to the left of their column.
     Left,
@@ -37,7 +115,129 @@ pub enum NumberListAlignment {
     /// This is often the most readable option for mixed integer/decimal data.
     Decimal,
     /// Normalize numbers to a consistent format and align by decimal point.
+    /// Negative zeros keep their sign, as in `-0.00`, unless
+    /// `normalize_negative_zero` is set; numbers rounded to zero by
+    /// `normalize_max_decimal_places` lose theirs.
     Normalize,
+    /// Align numbers by decimal point, adding zeros to the fractions of
+    /// numbers with an exponent so their exponents line up, as in `1.50e10`
+    /// and `2.00E-3`: suits columns mostly in scientific notation. Values
+    /// don't change.
+    Exponent,
+}
+
+/// What [`NumberListAlignment::Normalize`] does with a number it can't
+/// normalize exactly, such as `1e400` or one with more digits than an `f64`
+/// holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizeFallback {
+    /// Leave the number's whole column unaligned.
+    Unaligned,
+    /// Keep the number's text, padded like a string, and normalize the rest
+    /// of its column.
+    KeepText,
+}
+
+/// How numbers are rounded when
+/// [`normalize_max_decimal_places`](FracturedJsonOptions::normalize_max_decimal_places)
+/// cuts off some of their decimal places.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecimalRounding {
+    /// Round to the nearest value, with halves rounded away from zero:
+    /// `2.675` becomes `2.68`.
+    Nearest,
+    /// Drop the extra digits: `2.679` becomes `2.67`.
+    TowardZero,
+    /// Round up in magnitude if any dropped digit isn't zero: `2.671`
+    /// becomes `2.68`.
+    AwayFromZero,
+}
+
+/// Alignment of strings and nulls within their table columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StringColumnAlignment {
+    /// Padding goes after the value.
+    Left,
+    /// Padding goes before the value.
+    Right,
+    /// Padding is split around the value, with any odd space after it.
+    Center,
+}
+
+/// How [`Formatter::string_cell_classifier`](crate::Formatter::string_cell_classifier)
+/// wants a string aligned in its table column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellAlignment {
+    /// Padding goes after the value.
+    Left,
+    /// Padding goes before the value.
+    Right,
+    /// Padding is split around the value, with any odd space after it.
+    Center,
+    /// The string counts as a number, aligned as `number_list_alignment`
+    /// says along with any numbers in the column.
+    Number,
+}
+
+/// How the exponents of numbers such as `1.5e10` are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExponentStyle {
+    /// Write exponents as they are in the input.
+    Preserve,
+    /// Lowercase `e`, with a sign only for negative exponents: `1.5e10`.
+    Lowercase,
+    /// Lowercase `e`, always with a sign: `1.5e+10`.
+    LowercaseSigned,
+    /// Uppercase `E`, with a sign only for negative exponents: `1.5E10`.
+    Uppercase,
+    /// Uppercase `E`, always with a sign: `1.5E+10`.
+    UppercaseSigned,
+}
+
+/// How numbers are written, as set by `FracturedJsonOptions::number_notation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberNotation {
+    /// Write numbers as they are in the input.
+    Preserve,
+    /// Without an exponent: `700`, `0.0007`.
+    Fixed,
+    /// With one digit before the decimal point: `7e2`, `7e-4`.
+    Scientific,
+    /// With an exponent that's a multiple of 3: `700e0`, `700e-6`.
+    Engineering,
+}
+
+/// Order of the columns of a table of objects, after any listed in
+/// `FracturedJsonOptions::table_column_keys`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableColumnOrder {
+    /// The order in which keys first appear going down the rows: the first
+    /// row's keys, then those the later rows add.
+    FirstSeen,
+    /// Sorted by key, comparing the unescaped keys by code point.
+    Alphabetical,
+}
+
+/// Naming convention that object keys are converted to when
+/// `FracturedJsonOptions::key_case` is set.
+///
+/// Keys are split into words at `_`, `-` and spaces and at lower-to-upper
+/// case changes (`userID` is `user` + `ID`), then rejoined in the chosen style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyCase {
+    /// `firstName`
+    CamelCase,
+    /// `first_name`
+    SnakeCase,
+    /// `first-name`
+    KebabCase,
 }
 
 /// Controls where commas are placed relative to padding in table-formatted output.
@@ -45,7 +245,8 @@ pub enum NumberListAlignment {
 /// When objects or arrays are formatted in a table layout with aligned columns,
 /// this setting determines whether commas appear before or after the padding
 /// spaces used for alignment.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TableCommaPlacement {
     /// Place commas immediately after values, before any padding.
     /// Example: `"name",    "value"`
@@ -53,11 +254,30 @@ pub enum TableCommaPlacement {
     /// Place commas after padding, at the end of the padded column.
     /// Example: `"name"    ,"value"`
     AfterPadding,
-    /// Place commas before padding for most values, but after padding for numbers.
+    /// Place commas before padding for most values, but after padding for
+    /// numbers and booleans.
     /// This often produces the cleanest-looking output for mixed data.
     BeforePaddingExceptNumbers,
 }
 
+/// Layout requested for the values of particular properties through
+/// `FracturedJsonOptions::key_layout_hints`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutHint {
+    /// Write the container on a single line, whatever its length and
+    /// complexity.
+    Inline,
+    /// Give each element a line of its own.
+    Expand,
+    /// Write the elements as table rows if they can be, even if the
+    /// container would fit on one line.
+    Table,
+    /// Write an array with several elements per line if it can be, even if
+    /// it would fit on one line.
+    Compact,
+}
+
 /// Configuration options for JSON formatting.
 ///
 /// This struct contains all settings that control how JSON is formatted.
@@ -74,15 +294,45 @@ pub enum TableCommaPlacement {
 /// options.indent_spaces = 2;
 /// options.comment_policy = CommentPolicy::Preserve;
 /// ```
-#[derive(Debug, Clone)]
+///
+/// Options can also be deserialized with serde, for example from a TOML
+/// configuration file. Fields use their Rust names, enum values are written
+/// in snake case (`comment_policy = "preserve"`), and missing fields take
+/// their default values:
+///
+/// ```rust
+/// use fracturedjson::{CommentPolicy, FracturedJsonOptions};
+///
+/// let options: FracturedJsonOptions =
+///     serde_json::from_str(r#"{"indent_spaces": 2, "comment_policy": "preserve"}"#).unwrap();
+/// assert_eq!(options.indent_spaces, 2);
+/// assert_eq!(options.comment_policy, CommentPolicy::Preserve);
+/// assert_eq!(options.max_total_line_length, 120);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct FracturedJsonOptions {
     /// Line ending style for the output. Default: [`EolStyle::Lf`].
     pub json_eol_style: EolStyle,
 
+    /// Whether the output ends with a line ending. `Some(true)` always adds
+    /// one (minified output included), `Some(false)` never does, which suits
+    /// output embedded in the middle of another document.
+    /// Default: None (formatted output ends with one, minified output doesn't).
+    pub ensure_final_newline: Option<bool>,
+
     /// Maximum length of a line before it's broken into multiple lines.
     /// Default: 120.
     pub max_total_line_length: usize,
 
+    /// Line length that inline, compact multiline and table layouts aim for.
+    /// An array or object is only written with longer lines (up to
+    /// `max_total_line_length`) when the alternative is giving each element
+    /// a line of its own. Values of `max_total_line_length` or more have no
+    /// effect.
+    /// Default: None (lines may always use `max_total_line_length`).
+    pub preferred_line_length: Option<usize>,
+
     /// Maximum nesting depth for arrays/objects to be written on a single line.
     /// A value of 0 means only primitive values can be inlined.
     /// A value of 1 allows simple arrays/objects with primitive elements.
@@ -90,6 +340,15 @@ pub struct FracturedJsonOptions {
     /// Default: 2.
     pub max_inline_complexity: isize,
 
+    /// Maximum width of an array or object written on a single line, counting
+    /// only its brackets and contents: not the indentation, property name,
+    /// comments or comma. When set, it decides whether a container is inlined
+    /// in place of `max_total_line_length`, which shrinks with depth, so small
+    /// containers are inlined however deeply they're nested while long flat
+    /// ones are expanded.
+    /// Default: None (the total line length decides).
+    pub max_inline_value_length: Option<usize>,
+
     /// Maximum nesting depth for arrays to use compact multi-line formatting
     /// (multiple items per line). Set to -1 to disable.
     /// Default: 2.
@@ -100,12 +359,141 @@ pub struct FracturedJsonOptions {
     /// Default: 2.
     pub max_table_row_complexity: isize,
 
+    /// Maximum width of a table column, counting a string's quotes. A row
+    /// holding a longer value is written unaligned among the table rows
+    /// instead of widening the column for every row, so one outlier doesn't
+    /// spoil the table. If the row is too long for a line as well, it's
+    /// written in the form it'd take outside a table, whatever
+    /// `max_table_outlier_rows` says.
+    /// Default: None (columns are as wide as their widest value).
+    pub max_table_column_width: Option<usize>,
+
+    /// Maximum number of spaces aligning a table column may add to any of
+    /// its cells. A column that would need more, because one of its values is
+    /// much wider than another, is written unaligned, along with the columns
+    /// after it, while the columns before it stay aligned.
+    /// Default: None (no maximum).
+    pub max_column_padding: Option<usize>,
+
+    /// Minimum number of elements an array or object needs to be formatted
+    /// as a table. With only a couple of rows, the alignment padding tends to
+    /// look odd rather than helpful.
+    /// Default: 0 (no minimum).
+    pub min_table_rows: usize,
+
+    /// Maximum number of elements an array or object can have to be
+    /// formatted as a table. Larger ones skip measuring the columns of their
+    /// rows, which can take most of the time spent on huge arrays whose
+    /// tables would never fit anyway.
+    /// Default: None (no maximum).
+    pub max_table_rows: Option<usize>,
+
+    /// Maximum number of elements too long for a table row that a table may
+    /// have. They're written in place in the form they'd take outside a
+    /// table, usually expanded, while the other elements stay aligned.
+    /// Without this, a single such element rules out table formatting.
+    /// Default: 0.
+    pub max_table_outlier_rows: usize,
+
+    /// Minimum percentage of the rows of a table of objects that must have
+    /// the same set of keys. Below it the container isn't written as a table,
+    /// so sparse data with differing keys doesn't make a mostly blank one.
+    /// `Some(100)` allows only rows with identical key sets.
+    /// Default: None (columns are the union of all rows' keys).
+    pub min_table_key_match_percent: Option<usize>,
+
+    /// Order of the columns of tables of objects. Only the layout changes:
+    /// each row's members are written in column order, whatever their order
+    /// in the row.
+    /// Default: [`TableColumnOrder::FirstSeen`].
+    pub table_column_order: TableColumnOrder,
+
+    /// Keys whose columns come first in tables of objects, in this order,
+    /// ahead of the columns ordered by `table_column_order`.
+    /// Default: empty.
+    pub table_column_keys: Vec<String>,
+
+    /// If true, a container that qualifies as a table keeps its aligned
+    /// columns even when the rows are longer than `max_total_line_length`,
+    /// instead of being expanded. For readers who would rather scroll
+    /// sideways than lose the alignment.
+    /// Default: false.
+    pub force_table_formatting: bool,
+
+    /// If true, a table whose aligned rows are slightly too long is measured
+    /// again with its padding squeezed out: no spaces inside the rows'
+    /// brackets or after colons, leaving just the comma padding between
+    /// columns. If that fits, it is used instead of giving up the alignment
+    /// of nested columns or the whole table.
+    /// Default: false.
+    pub tight_table_fallback: bool,
+
+    /// If true, a table of objects starts with a comment row naming its
+    /// columns, such as `// name      age   city`, each name above its
+    /// column. The output then holds comments, so it can't be used with
+    /// `verify_round_trip`.
+    /// Default: false.
+    pub table_header_comments: bool,
+
+    /// If true, members of table rows whose value is `null` are left out,
+    /// leaving blank cells like those of keys a row doesn't have. Columns
+    /// keep their order. Only object rows are affected: elements of array
+    /// rows are placed by position, so their nulls are kept. This makes
+    /// sparse tables easier to read, but the output no longer holds the
+    /// nulls, so it can't be used with `verify_round_trip`.
+    /// Default: false.
+    pub blank_table_nulls: bool,
+
+    /// If true, table rows of objects that lack some of the table's keys are
+    /// written with those keys set to `null`, instead of leaving their cells
+    /// blank, so every row has the same keys when the output is read back.
+    /// This adds members the input didn't have, so it can't be used with
+    /// `verify_round_trip` or `blank_table_nulls`.
+    /// Default: false.
+    pub fill_missing_table_keys: bool,
+
+    /// If true, arrays in a table column, or arrays that are the rows of a
+    /// table, are aligned element by element only when they all have the
+    /// same number of elements. Otherwise each is written whole and
+    /// left-aligned, which avoids the wide blank gaps one long array leaves
+    /// in all the others.
+    /// Default: false.
+    pub require_equal_array_lengths: bool,
+
+    /// If set, the rows of each table are written sorted by this column: a
+    /// key when the rows are objects, or a position such as `"0"` when
+    /// they're arrays. Numbers sort by value and strings by their text;
+    /// rows without the column come last. Comments and blank lines between
+    /// rows stay where they are. Reordering array elements changes the
+    /// data, so it can't be used with `verify_round_trip`.
+    /// Default: `None`.
+    pub table_sort_column: Option<String>,
+
+    /// If true, `table_sort_column` sorts rows in descending order.
+    /// Default: false.
+    pub table_sort_descending: bool,
+
+    /// If true, the padding after the last value of a table row, including
+    /// any filler in place of a comma, is left out, so the row's closing
+    /// bracket comes right after its last value. Rows with fewer columns
+    /// than the table then end early, which keeps interior spaces out of
+    /// line diffs.
+    /// Default: false.
+    pub trim_table_row_padding: bool,
+
     /// Maximum number of spaces to use for property name padding in table format.
     /// If aligning property names would require more padding than this, alignment
     /// is skipped for that container.
     /// Default: 16.
     pub max_prop_name_padding: usize,
 
+    /// Maximum number of spaces to pad the property names of table rows with,
+    /// when the rows are the values of an object. If one long name would need
+    /// more, each row's name is written unpadded, with its cells still padded
+    /// to the widths of the columns.
+    /// Default: `None`.
+    pub max_table_name_padding: Option<usize>,
+
     /// If true, the colon comes before the property name padding.
     /// Example with true: `"a": 1` vs `"aaa": 2`
     /// Example with false: `"a"  : 1` vs `"aaa": 2`
@@ -116,16 +504,99 @@ pub struct FracturedJsonOptions {
     /// Default: [`TableCommaPlacement::BeforePaddingExceptNumbers`].
     pub table_comma_placement: TableCommaPlacement,
 
+    /// Number of spaces after the comma between table columns, e.g. `Some(2)`
+    /// for airier tables. When `None`, columns are spaced like the elements of
+    /// inline containers, as `comma_padding` says. Values within the cells are
+    /// unaffected.
+    /// Default: `None`.
+    pub table_column_spacing: Option<usize>,
+
     /// Minimum number of items required per row when formatting arrays
     /// in compact multi-line mode. Default: 3.
     pub min_compact_array_row_items: usize,
 
+    /// Whether compact multi-line arrays spread their items evenly over the
+    /// rows they need, instead of filling each row in turn and leaving the
+    /// last one short. Every row but the last then has the same number of
+    /// items.
+    /// Default: false.
+    pub balance_compact_array_rows: bool,
+
+    /// Whether the items of compact multi-line arrays that can't be formatted
+    /// as table columns (such as strings of different lengths, or a mix of
+    /// types) are padded to the width of the widest item, so that they line
+    /// up in columns from row to row.
+    /// Default: false.
+    pub align_compact_array_items: bool,
+
+    /// Whether arrays of numbers written inline on a line of their own are
+    /// padded as `number_list_alignment` says, like the rows of a compact
+    /// array: `[ 1.5, 10  , -2.25]`. Arrays nested in other inline values or
+    /// table rows are never padded, and the padding is dropped when the
+    /// padded array wouldn't fit on the line.
+    /// Default: false.
+    pub align_inline_numbers: bool,
+
     /// Depth at which containers are always expanded (never inlined).
     /// Containers at this depth or shallower will always be multi-line.
     /// Set to -1 to disable (allow inlining at any depth).
     /// Default: -1.
     pub always_expand_depth: isize,
 
+    /// Like `always_expand_depth`, but only for objects: objects at this depth
+    /// or shallower are always multi-line, as is any container holding one.
+    /// Containers are expanded if either this or `always_expand_depth` says so.
+    /// Default: -1.
+    pub always_expand_object_depth: isize,
+
+    /// Like `always_expand_depth`, but only for arrays: arrays at this depth
+    /// or shallower are always multi-line, as is any container holding one.
+    /// Containers are expanded if either this or `always_expand_depth` says so.
+    /// Default: -1.
+    pub always_expand_array_depth: isize,
+
+    /// Depth below which containers are always written minified on a single
+    /// line, whatever their length and complexity: containers deeper than
+    /// this have no padding inside their brackets. Containers with comments
+    /// on lines of their own are formatted normally.
+    /// Set to -1 to disable.
+    /// Default: -1.
+    pub compact_at_depth: isize,
+
+    /// Write the closing bracket of an expanded container on the same line as
+    /// the closing bracket of its last element, when that element is itself
+    /// a multi-line container with no comment after it. A deeply nested
+    /// structure then ends with a single line like `]}}`.
+    /// Default: false.
+    pub collapse_closing_brackets: bool,
+
+    /// Keep arrays whose elements are all primitives (numbers, strings, booleans
+    /// or nulls) on a single line even if that exceeds `max_total_line_length`.
+    /// Complexity limits and `always_expand_depth` still apply, and arrays
+    /// containing comments are formatted normally.
+    /// Default: false.
+    pub never_wrap_primitive_arrays: bool,
+
+    /// Arrays and objects with more elements than this are never written on a
+    /// single line or as a compact multi-line array, even if they would fit;
+    /// each element gets a line of its own (or a table row). Containers
+    /// holding one are expanded too. Takes precedence over
+    /// `never_wrap_primitive_arrays`.
+    /// Default: None (no limit).
+    pub max_inline_children: Option<usize>,
+
+    /// Layouts for the arrays and objects stored under particular property
+    /// names, wherever in the document they occur, such as `Inline` for
+    /// `"coordinates"`. Hints override the length, complexity and
+    /// always-expand options for that container, but not for the containers
+    /// inside it, and a container holding one hinted to take several lines is
+    /// never inlined. `Table` and `Compact` fall back to the usual choice when
+    /// the container can't be laid out that way, and `Inline` when it contains
+    /// comments on lines of their own. Keys are matched after any `key_case`
+    /// conversion.
+    /// Default: empty.
+    pub key_layout_hints: HashMap<String, LayoutHint>,
+
     /// Add spaces inside brackets for nested containers: `[ [1, 2] ]` vs `[[1, 2]]`.
     /// Default: true.
     pub nested_bracket_padding: bool,
@@ -146,62 +617,457 @@ pub struct FracturedJsonOptions {
     /// Default: true.
     pub comment_padding: bool,
 
+    /// Overrides the colon written between property names and values, for
+    /// example `" : "`. Must be a single `:` surrounded only by spaces or tabs.
+    /// When `None`, the colon is derived from `colon_padding`.
+    /// Default: `None`.
+    pub colon_token: Option<String>,
+
+    /// Overrides the separator written between a value and a comment on the
+    /// same line, for example `"  "` for a wider gutter before `//`. Must
+    /// contain only spaces or tabs. When `None`, it is derived from
+    /// `comment_padding`.
+    /// Default: `None`.
+    pub comment_separator: Option<String>,
+
+    /// Number of spaces between a value and a comment after it on the same
+    /// line, e.g. `Some(2)` for style guides that want two spaces before `//`.
+    /// They are counted from the comma after the value, if there is one, which
+    /// then gets no padding of its own. In aligned rows this is the gap on the
+    /// longest row. Comments before a value or between a property name and its value are
+    /// unaffected. When `None`, the gutter is the same as before any other
+    /// comment, as `comment_separator` and `comment_padding` say.
+    /// Default: `None`.
+    pub spaces_before_eol_comment: Option<usize>,
+
+    /// Overrides the filler written in place of a comma after the last item of
+    /// a table or compact array, which keeps columns aligned. Must be spaces,
+    /// as many as the comma is wide, or columns after it would be pushed out
+    /// of line. When `None`, it is as many spaces as the comma is wide.
+    /// Default: `None`.
+    pub dummy_comma: Option<String>,
+
     /// Alignment style for numbers in array tables.
     /// Default: [`NumberListAlignment::Decimal`].
     pub number_list_alignment: NumberListAlignment,
 
-    /// Number of spaces per indentation level. Ignored if `use_tab_to_indent` is true.
+    /// Most decimal places written for numbers aligned with
+    /// [`NumberListAlignment::Normalize`]. Numbers with more are rounded as
+    /// `normalize_rounding` says, so one long fraction doesn't widen the whole
+    /// column. This changes their values.
+    /// Default: `None`.
+    pub normalize_max_decimal_places: Option<usize>,
+
+    /// How numbers are rounded to `normalize_max_decimal_places`.
+    /// Default: [`DecimalRounding::Nearest`].
+    pub normalize_rounding: DecimalRounding,
+
+    /// Write `-0` and `-0.0` as `0` when aligning with
+    /// [`NumberListAlignment::Normalize`], instead of keeping the sign.
+    /// Default: false.
+    pub normalize_negative_zero: bool,
+
+    /// What [`NumberListAlignment::Normalize`] does with numbers that can't be
+    /// normalized without losing precision.
+    /// Default: [`NormalizeFallback::Unaligned`].
+    pub normalize_fallback: NormalizeFallback,
+
+    /// Room kept before the decimal point in table and compact array columns
+    /// of numbers aligned with [`NumberListAlignment::Decimal`], `Normalize`
+    /// or `Exponent`, counting a minus sign. Columns of short numbers stay
+    /// the same width when longer ones are added later, so regenerated files
+    /// diff cleanly.
+    /// Default: 0.
+    pub number_min_integer_digits: usize,
+
+    /// Pad the integers of table and compact array columns holding only
+    /// integers with leading zeros to the width of the longest, e.g. `007`,
+    /// for consumers of fixed-width data. The output isn't valid JSON.
+    /// Default: false.
+    pub zero_pad_integers: bool,
+
+    /// Alignment of strings and nulls in table columns that don't hold
+    /// numbers or booleans, which are right-aligned. Right alignment suits
+    /// columns of codes or identifiers whose ends matter most.
+    /// Default: [`StringColumnAlignment::Left`].
+    pub string_column_alignment: StringColumnAlignment,
+
+    /// If true, strings holding nothing but a JSON number, such as `"1.5"`,
+    /// count as numbers in table columns and compact arrays, so a column of
+    /// `"1.5"`, `2` and `3` is aligned on its decimal points like a column of
+    /// numbers. The strings keep their quotes. Otherwise such a column is
+    /// aligned like a column of strings.
+    /// Default: false.
+    pub align_numeric_strings: bool,
+
+    /// If true, table columns of strings that all start with a number, like
+    /// `"12.5 MB"` and `"3 MB"`, are padded so the numbers end together and
+    /// what follows them starts together, whatever `string_column_alignment`
+    /// says.
+    /// Default: false.
+    pub align_unit_strings: bool,
+
+    /// If true, table columns of ISO 8601 date and time strings, such as
+    /// `"2024-05-01T12:30:00Z"` or `"2024-05-01 12:30:00.250+02:00"`, are
+    /// recognized and always left-aligned, whatever
+    /// `string_column_alignment` says, so their fixed-width date and time
+    /// fields line up even when only some have fractional seconds. Such
+    /// columns are reported as [`TableColumnKind::DateTime`](crate::TableColumnKind::DateTime)
+    /// by [`Formatter::analyze`](crate::Formatter::analyze).
+    /// Default: false.
+    pub detect_datetime_columns: bool,
+
+    /// How exponents are written in every number of the output, so the same
+    /// values come out byte-for-byte the same whatever produced the input.
+    /// Only the exponent marker and its sign change; digits are kept.
+    /// Default: [`ExponentStyle::Preserve`].
+    pub exponent_style: ExponentStyle,
+
+    /// Rewrites every number in one notation, so columns mixing `7e2`, `700`
+    /// and `0.0007` come out uniform. Insignificant zeros are dropped, and
+    /// zero is always written `0`. The exponent follows `exponent_style`.
+    /// Default: [`NumberNotation::Preserve`].
+    pub number_notation: NumberNotation,
+
+    /// Numbers whose exponent in scientific notation is within this many of
+    /// zero are written in fixed notation whatever `number_notation` says, and
+    /// the rest, with [`NumberNotation::Fixed`], in scientific notation:
+    /// `Some(3)` writes `700` and `0.007` as they are but `7e4` for `70000`.
+    /// Default: `None`.
+    pub number_notation_threshold: Option<u32>,
+
+    /// Remove insignificant trailing zeros from the fraction of every number:
+    /// `2.10` becomes `2.1` and `3.0` becomes `3`. Number columns still line
+    /// up on their decimal points, but aren't widened to the longest fraction
+    /// the way [`NumberListAlignment::Normalize`] widens them.
+    /// Default: false.
+    pub trim_trailing_zeros: bool,
+
+    /// Properties and elements whose non-negative integers are written as
+    /// strings of hexadecimal digits, like `"0xFF"`, for flag masks and
+    /// colors. Entries starting with `/` are JSON Pointers; the others are
+    /// keys, matched in every object. Keys are matched after any `key_case`
+    /// conversion. This changes the values' types.
+    /// Default: empty.
+    pub hex_integer_keys: Vec<String>,
+
+    /// Number of spaces per indentation level. Ignored if `use_tab_to_indent` is true
+    /// or `indent_string` is set.
     /// Default: 4.
     pub indent_spaces: usize,
 
-    /// Use tabs instead of spaces for indentation.
+    /// Use tabs instead of spaces for indentation. Ignored if `indent_string` is set.
     /// Default: false.
     pub use_tab_to_indent: bool,
 
+    /// Overrides the string written once per indentation level, e.g. `"  "` or
+    /// `"\t "`. Must contain only spaces and tabs. Its width (as measured by the
+    /// formatter's `string_length_func`) counts against `max_total_line_length`.
+    /// Default: None.
+    pub indent_string: Option<String>,
+
+    /// Spaces of indentation added at each nesting depth, starting with
+    /// depth 1 (the children of a top-level container). For example `[2]`
+    /// indents the first level by 2 spaces and each deeper level by the usual
+    /// `indent_spaces`, `use_tab_to_indent` or `indent_string` amount.
+    /// Default: empty (every depth uses the usual amount).
+    pub indent_spaces_by_depth: Vec<usize>,
+
     /// A string to prepend to every line of output. Useful for embedding
-    /// formatted JSON within other content.
+    /// formatted JSON within other content. Its width (as measured by the
+    /// formatter's `string_length_func`) counts against `max_total_line_length`.
+    /// Must not contain line breaks; formatting returns an error if it does.
     /// Default: empty string.
     pub prefix_string: String,
 
+    /// Convert object keys to this naming convention in the output.
+    /// Formatting returns an error if two keys of an object would be given
+    /// the same name, such as `aB` and `a_b` in snake case.
+    /// Default: None (keys are written as they are).
+    pub key_case: Option<KeyCase>,
+
+    /// JSON Pointers (such as `/dependencies`) of properties whose key, and
+    /// every key nested below it, are left alone by `key_case`. Pointers use
+    /// the keys as they appear in the input.
+    /// Default: empty.
+    pub key_case_exclusions: Vec<String>,
+
+    /// Property names in the order object members should follow. Members
+    /// whose key is listed are moved to the front of their object in this
+    /// order; the others follow in their original order. Keys are matched
+    /// after any `key_case` conversion.
+    /// Default: empty (members keep their input order).
+    pub key_order: Vec<String>,
+
+    /// Whether object members whose key isn't in `key_order` are sorted by
+    /// key (comparing the unescaped keys by code point) rather than kept in
+    /// their original order.
+    /// Default: false.
+    pub sort_keys: bool,
+
     /// How to handle comments in the input.
     /// Default: [`CommentPolicy::TreatAsError`].
     pub comment_policy: CommentPolicy,
 
+    /// How to handle `//` comments, if not as `comment_policy` says. For
+    /// example, `Some(CommentPolicy::TreatAsError)` with `comment_policy`
+    /// set to `Preserve` keeps `/* */` comments but rejects `//` ones.
+    /// Default: `None`.
+    pub line_comment_policy: Option<CommentPolicy>,
+
+    /// How to handle `/* */` comments, if not as `comment_policy` says.
+    /// Default: `None`.
+    pub block_comment_policy: Option<CommentPolicy>,
+
     /// Preserve blank lines from the input in the output.
     /// Only meaningful when `comment_policy` is not `TreatAsError`.
     /// Default: false.
     pub preserve_blank_lines: bool,
 
+    /// Most blank lines kept in a row when `preserve_blank_lines` is on;
+    /// longer runs are collapsed to this many.
+    /// Default: `None` (every blank line is kept).
+    pub max_consecutive_blank_lines: Option<usize>,
+
+    /// Order of standalone comments relative to surrounding blank lines.
+    /// Default: [`StandaloneCommentOrder::Preserve`].
+    pub standalone_comment_order: StandaloneCommentOrder,
+
+    /// Precede each run of standalone comments (comments on lines of their
+    /// own) inside an array or object with exactly one blank line, adding one
+    /// or collapsing several as needed. Blank lines before a run that opens
+    /// its container are removed. Works with or without `preserve_blank_lines`.
+    /// Default: false.
+    pub blank_line_before_comments: bool,
+
+    /// Spacing between comment markers (`//`, `/*`, `*/`) and comment text.
+    /// Default: [`CommentSpacing::Preserve`].
+    pub comment_spacing: CommentSpacing,
+
+    /// Comment syntax to normalize the document's comments to. Applied
+    /// before `comment_spacing`.
+    /// Default: [`CommentStyle::Preserve`].
+    pub comment_style: CommentStyle,
+
+    /// Rewrite `//` comments between an object key and its value as `/* */`
+    /// comments, when that lets the member be written on one line: inline,
+    /// or as a table row. Otherwise a line comment there always forces the
+    /// member, and everything containing it, onto several lines.
+    /// Default: false.
+    pub block_middle_comments: bool,
+
+    /// In an array or object with one element per line, pad the comments
+    /// that follow elements on the same line so they all start in the same
+    /// column. Elements written on several lines are left as they are.
+    /// Default: false.
+    pub align_postfix_comments: bool,
+
     /// Allow trailing commas in the input (non-standard JSON).
     /// Default: false.
     pub allow_trailing_commas: bool,
+
+    /// When [`Formatter::reformat`](crate::Formatter::reformat) is given JSON
+    /// Lines input (two or more top-level values, each on a line of its own),
+    /// format it with [`Formatter::reformat_jsonl`](crate::Formatter::reformat_jsonl)
+    /// instead of failing with "Unexpected start of second top level element".
+    /// Default: false.
+    pub auto_detect_jsonl: bool,
+
+    /// If true, [`Formatter::reformat_jsonl`](crate::Formatter::reformat_jsonl)
+    /// measures the columns of all lines together and writes each line as a
+    /// row of one table, so the columns line up from line to line. If the
+    /// lines don't make a table that fits within `max_total_line_length`,
+    /// each is formatted on its own as usual.
+    /// Default: false.
+    pub jsonl_shared_column_widths: bool,
+
+    /// Enforce RFC 8259 rules that are not otherwise checked: unpaired UTF-16
+    /// surrogates in `\u` escapes, duplicate property names within an object,
+    /// and anything other than whitespace (including comments and further
+    /// values) after the top-level value are all errors. Combine with the
+    /// default `comment_policy`, `allow_trailing_commas` and
+    /// `control_char_policy` for full compliance.
+    /// Default: false.
+    pub strict_rfc8259: bool,
+
+    /// How to handle raw control characters inside strings.
+    /// Default: [`ControlCharPolicy::TreatAsError`].
+    pub control_char_policy: ControlCharPolicy,
+
+    /// Whether non-ASCII characters in strings and property names are written
+    /// as `\uXXXX` escapes (surrogate pairs outside the Basic Multilingual
+    /// Plane), for consumers that can't handle UTF-8. Table alignment uses
+    /// the escaped lengths. Comments are left as they are.
+    /// Default: false.
+    pub ascii_only: bool,
+
+    /// Whether `\uXXXX` escapes in strings and property names are decoded
+    /// and written as literal UTF-8 characters. Escapes of control
+    /// characters, quotes, backslashes, unpaired surrogates and invisible
+    /// characters (zero-width spaces, bidirectional controls and the like)
+    /// are kept. `ascii_only` escapes the decoded characters again.
+    /// Default: false.
+    pub unescape_unicode: bool,
+
+    /// Whether escapes in strings and property names are rewritten
+    /// canonically: `\/` becomes `/`, `\uXXXX` escapes that have a short
+    /// form use it (`\n` rather than `\u000a`), and the hex digits of other
+    /// `\u` escapes are lowercased.
+    /// Default: false.
+    pub normalize_string_escapes: bool,
+
+    /// Maximum length, in characters, of string values in the output. Longer
+    /// strings are cut short and end with `…`, which counts towards the
+    /// limit; each escape sequence counts as one character and is never
+    /// split. The output stays valid JSON but loses data, so this is meant
+    /// for previews such as log viewers. Property names are not shortened,
+    /// and a limit of 0 writes every string as `""`.
+    /// Default: None (strings are written in full).
+    pub max_string_display_length: Option<usize>,
+
+    /// Maximum number of lines of formatted output, for previews of large
+    /// documents. Once the limit is near, the remaining elements of each open
+    /// array or object are replaced by a comment such as
+    /// `// … 42 more items …` and the containers are closed, so the output
+    /// stays readable but no longer holds all the data. Minified output is
+    /// not limited.
+    /// Default: None (no limit).
+    pub max_output_lines: Option<usize>,
+
+    /// Leave out object properties whose value is `null` when serializing
+    /// values with `serialize`, `serialize_value` or `serialize_to_writer`.
+    /// Array elements are kept, as are properties of reformatted text.
+    /// Default: false.
+    pub omit_null_values: bool,
+
+    /// Leave out object properties whose value is an empty array or object
+    /// when serializing values, like `omit_null_values`. A container left
+    /// empty by omitting its properties is left out too.
+    /// Default: false.
+    pub omit_empty_containers: bool,
+
+    /// Maximum nesting depth of arrays/objects accepted by the parser.
+    /// Deeper input produces an error instead of overflowing the stack.
+    /// Default: 128.
+    pub max_parse_depth: usize,
+
+    /// Maximum number of characters accepted in the input. Longer input
+    /// produces an error at the position where the limit is reached.
+    /// Default: 2,000,000,000.
+    pub max_document_size: usize,
+
+    /// After formatting text, parse the output again and check that it holds
+    /// the same values, property order and comments as the input, returning
+    /// an error if anything differs. Costs a second parse per call.
+    /// Default: false.
+    pub verify_round_trip: bool,
 }
 
 impl Default for FracturedJsonOptions {
     fn default() -> Self {
         Self {
             json_eol_style: EolStyle::Lf,
+            ensure_final_newline: None,
             max_total_line_length: 120,
+            preferred_line_length: None,
             max_inline_complexity: 2,
+            max_inline_value_length: None,
             max_compact_array_complexity: 2,
             max_table_row_complexity: 2,
+            max_table_column_width: None,
+            max_column_padding: None,
+            min_table_rows: 0,
+            max_table_rows: None,
+            max_table_outlier_rows: 0,
+            min_table_key_match_percent: None,
+            table_column_order: TableColumnOrder::FirstSeen,
+            table_column_keys: Vec::new(),
+            force_table_formatting: false,
+            tight_table_fallback: false,
+            table_header_comments: false,
+            blank_table_nulls: false,
+            fill_missing_table_keys: false,
+            require_equal_array_lengths: false,
+            table_sort_column: None,
+            table_sort_descending: false,
+            trim_table_row_padding: false,
             max_prop_name_padding: 16,
+            max_table_name_padding: None,
             colon_before_prop_name_padding: false,
             table_comma_placement: TableCommaPlacement::BeforePaddingExceptNumbers,
+            table_column_spacing: None,
             min_compact_array_row_items: 3,
+            balance_compact_array_rows: false,
+            align_compact_array_items: false,
+            align_inline_numbers: false,
             always_expand_depth: -1,
+            always_expand_object_depth: -1,
+            always_expand_array_depth: -1,
+            compact_at_depth: -1,
+            collapse_closing_brackets: false,
+            never_wrap_primitive_arrays: false,
+            max_inline_children: None,
+            key_layout_hints: HashMap::new(),
             nested_bracket_padding: true,
             simple_bracket_padding: false,
             colon_padding: true,
             comma_padding: true,
             comment_padding: true,
+            colon_token: None,
+            comment_separator: None,
+            spaces_before_eol_comment: None,
+            dummy_comma: None,
             number_list_alignment: NumberListAlignment::Decimal,
+            normalize_max_decimal_places: None,
+            normalize_rounding: DecimalRounding::Nearest,
+            normalize_negative_zero: false,
+            normalize_fallback: NormalizeFallback::Unaligned,
+            number_min_integer_digits: 0,
+            zero_pad_integers: false,
+            string_column_alignment: StringColumnAlignment::Left,
+            align_numeric_strings: false,
+            align_unit_strings: false,
+            detect_datetime_columns: false,
+            exponent_style: ExponentStyle::Preserve,
+            number_notation: NumberNotation::Preserve,
+            number_notation_threshold: None,
+            trim_trailing_zeros: false,
+            hex_integer_keys: Vec::new(),
             indent_spaces: 4,
             use_tab_to_indent: false,
+            indent_string: None,
+            indent_spaces_by_depth: Vec::new(),
             prefix_string: String::new(),
+            key_case: None,
+            key_case_exclusions: Vec::new(),
+            key_order: Vec::new(),
+            sort_keys: false,
             comment_policy: CommentPolicy::TreatAsError,
+            line_comment_policy: None,
+            block_comment_policy: None,
             preserve_blank_lines: false,
+            max_consecutive_blank_lines: None,
+            standalone_comment_order: StandaloneCommentOrder::Preserve,
+            blank_line_before_comments: false,
+            comment_spacing: CommentSpacing::Preserve,
+            comment_style: CommentStyle::Preserve,
+            block_middle_comments: false,
+            align_postfix_comments: false,
             allow_trailing_commas: false,
+            auto_detect_jsonl: false,
+            jsonl_shared_column_widths: false,
+            strict_rfc8259: false,
+            control_char_policy: ControlCharPolicy::TreatAsError,
+            ascii_only: false,
+            unescape_unicode: false,
+            normalize_string_escapes: false,
+            max_string_display_length: None,
+            max_output_lines: None,
+            omit_null_values: false,
+            omit_empty_containers: false,
+            max_parse_depth: 128,
+            max_document_size: 2_000_000_000,
+            verify_round_trip: false,
         }
     }
 }
@@ -214,4 +1080,37 @@ impl FracturedJsonOptions {
     pub fn recommended() -> Self {
         Self::default()
     }
+
+    /// Creates options that keep version-control diffs small: every element
+    /// on its own line with no alignment padding, object keys sorted, LF line
+    /// endings with a final newline, and numbers written as they appear in
+    /// the input. Changing one value then changes one line.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{Formatter, FracturedJsonOptions};
+    ///
+    /// let mut formatter = Formatter::with_options(FracturedJsonOptions::diff_friendly());
+    /// let output = formatter.reformat(r#"{"b": [1, 2], "a": 1.50}"#, 0).unwrap();
+    ///
+    /// assert_eq!(
+    ///     output,
+    ///     "{\n    \"a\": 1.50,\n    \"b\": [\n        1,\n        2\n    ]\n}\n"
+    /// );
+    /// ```
+    pub fn diff_friendly() -> Self {
+        Self {
+            max_inline_complexity: 0,
+            max_compact_array_complexity: -1,
+            max_table_row_complexity: -1,
+            max_prop_name_padding: 0,
+            number_list_alignment: NumberListAlignment::Left,
+            json_eol_style: EolStyle::Lf,
+            ensure_final_newline: Some(true),
+            sort_keys: true,
+            allow_trailing_commas: false,
+            ..Self::default()
+        }
+    }
 }
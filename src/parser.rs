@@ -1,6 +1,8 @@
+use std::collections::HashSet;
+
 use crate::error::FracturedJsonError;
-use crate::model::{InputPosition, JsonItem, JsonItemType, JsonToken, TokenType};
-use crate::options::{CommentPolicy, FracturedJsonOptions};
+use crate::model::{InputEdit, InputPosition, JsonItem, JsonItemType, JsonToken, Span, TokenType};
+use crate::options::{CommentPolicy, FracturedJsonOptions, StandaloneCommentOrder};
 use crate::tokenizer::TokenGenerator;
 
 pub struct TokenEnumerator<I>
@@ -43,25 +45,283 @@ where
     }
 }
 
+/// Parses JSON text into a document model of [`JsonItem`]s.
+///
+/// Most users only need [`Formatter`](crate::Formatter). The parser is useful
+/// for tools that want to inspect the document structure, for example to map
+/// items back to the input through their [`Span`]s.
+///
+/// # Example
+///
+/// ```rust
+/// use fracturedjson::{FracturedJsonOptions, Parser};
+///
+/// let input = r#"{"name": "Alice"}"#;
+/// let parser = Parser::new(FracturedJsonOptions::default());
+/// let doc = parser.parse_top_level(input, true).unwrap();
+///
+/// let prop = &doc[0].children[0];
+/// assert_eq!(&input[prop.name_span.start..prop.name_span.end], "\"name\"");
+/// assert_eq!(&input[prop.span.start..prop.span.end], "\"Alice\"");
+/// ```
 pub struct Parser {
+    /// Options controlling what input is accepted.
     pub options: FracturedJsonOptions,
 }
 
 impl Parser {
+    /// Creates a parser with the given options.
     pub fn new(options: FracturedJsonOptions) -> Self {
         Self { options }
     }
 
+    /// Parses the input into its top-level items. Comments and blank lines are
+    /// included according to `options`. If `stop_after_first_elem` is true,
+    /// a second top-level value is an error.
     pub fn parse_top_level(
         &self,
         input_json: &str,
         stop_after_first_elem: bool,
     ) -> Result<Vec<JsonItem>, FracturedJsonError> {
-        let token_stream = TokenGenerator::new(input_json);
+        let token_stream = TokenGenerator::new(input_json, &self.options);
         let mut enumerator = TokenEnumerator::new(token_stream);
         self.parse_top_level_from_enum(&mut enumerator, stop_after_first_elem)
     }
 
+    /// Parses the document again after an edit, reusing `old_dom` where possible.
+    ///
+    /// `old_dom` must be the result of parsing the text before the edit, and
+    /// `new_text` is the whole text after it. Only the innermost array or
+    /// object whose brackets enclose the edit is tokenized and parsed again;
+    /// it is spliced into the old model, and the spans and positions of items
+    /// after it are shifted without scanning the rest of the text. If the edit
+    /// touches anything outside such a container, or the container no longer
+    /// parses on its own, the whole document is parsed instead, so the result
+    /// always matches [`parse_top_level`](Self::parse_top_level). A model with
+    /// more than one top-level value is reparsed as a stream of values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fracturedjson::{FracturedJsonOptions, InputEdit, Parser};
+    ///
+    /// let parser = Parser::new(FracturedJsonOptions::default());
+    /// let doc = parser.parse_top_level(r#"{"a": [1, 2], "b": 3}"#, true).unwrap();
+    ///
+    /// let new_text = r#"{"a": [1, 20, 3], "b": 3}"#;
+    /// let edit = InputEdit { start: 10, old_end: 11, new_end: 15 };
+    /// let doc = parser.reparse(doc, edit, new_text).unwrap();
+    ///
+    /// assert_eq!(doc[0].children[0].children.len(), 3);
+    /// let b = &doc[0].children[1];
+    /// assert_eq!(&new_text[b.span.start..b.span.end], "3");
+    /// ```
+    pub fn reparse(
+        &self,
+        mut old_dom: Vec<JsonItem>,
+        edit: InputEdit,
+        new_text: &str,
+    ) -> Result<Vec<JsonItem>, FracturedJsonError> {
+        if self.reparse_in_place(&mut old_dom, edit, new_text) {
+            return Ok(old_dom);
+        }
+
+        let value_count = old_dom
+            .iter()
+            .filter(|item| !Self::is_comment_or_blank_line(item))
+            .count();
+        self.parse_top_level(new_text, value_count <= 1)
+    }
+
+    fn reparse_in_place(&self, dom: &mut [JsonItem], edit: InputEdit, new_text: &str) -> bool {
+        if edit.start > edit.old_end || edit.start > edit.new_end || edit.new_end > new_text.len() {
+            return false;
+        }
+        // A text has at most as many characters as bytes; longer texts are
+        // parsed in full, which checks `max_document_size` exactly.
+        if new_text.len() > self.options.max_document_size {
+            return false;
+        }
+
+        // Find the innermost container whose brackets strictly enclose the edit.
+        let mut path: Vec<usize> = Vec::new();
+        let mut items: &[JsonItem] = dom;
+        while let Some(idx) = items.iter().position(|item| {
+            matches!(item.item_type, JsonItemType::Array | JsonItemType::Object)
+                && item.span.start < edit.start
+                && edit.old_end < item.span.end
+        }) {
+            path.push(idx);
+            items = &items[idx].children;
+        }
+        let Some((&last, ancestors)) = path.split_last() else {
+            return false;
+        };
+
+        let mut container_list: &[JsonItem] = dom;
+        for &idx in ancestors {
+            container_list = &container_list[idx].children;
+        }
+        let old_item = &container_list[last];
+        let new_end = old_item.span.end + edit.new_end - edit.old_end;
+        let Some(fragment) = new_text.get(old_item.span.start..new_end) else {
+            return false;
+        };
+        let Ok(mut new_item) = self.parse_fragment(
+            fragment,
+            old_item.input_position,
+            old_item.span.start,
+            ancestors.len(),
+        ) else {
+            return false;
+        };
+        if new_item.span.end != new_end {
+            return false;
+        }
+
+        new_item.name = old_item.name.clone();
+        new_item.name_span = old_item.name_span;
+        new_item.prefix_comment = old_item.prefix_comment.clone();
+        new_item.middle_comment = old_item.middle_comment.clone();
+        new_item.middle_comment_has_new_line = old_item.middle_comment_has_new_line;
+        new_item.postfix_comment = old_item.postfix_comment.clone();
+        new_item.is_post_comment_line_style = old_item.is_post_comment_line_style;
+
+        // Only the text from the container to the first item after it is
+        // scanned to find how far the items after it have moved.
+        let old_end = old_item.span.end;
+        let shift = match Self::first_item_after(dom, &path) {
+            Some(next) => {
+                let container_end = advance_position(new_item.input_position, fragment);
+                let next_start = next.span.start + new_end - old_end;
+                let Some(gap) = new_text.get(new_end..next_start) else {
+                    return false;
+                };
+                PositionShift::new(next.input_position, advance_position(container_end, gap))
+            }
+            None => PositionShift::default(),
+        };
+        Self::splice_reparsed(dom, &path, new_item, old_end, new_end, &shift);
+        true
+    }
+
+    /// The first item in document order after the one at `path`, if any.
+    fn first_item_after<'a>(dom: &'a [JsonItem], path: &[usize]) -> Option<&'a JsonItem> {
+        let mut levels = Vec::with_capacity(path.len());
+        let mut items = dom;
+        for &idx in path {
+            levels.push((items, idx));
+            items = &items[idx].children;
+        }
+        levels
+            .into_iter()
+            .rev()
+            .find_map(|(items, idx)| items.get(idx + 1))
+    }
+
+    fn parse_fragment(
+        &self,
+        fragment: &str,
+        base_position: InputPosition,
+        base_byte: usize,
+        depth: usize,
+    ) -> Result<JsonItem, FracturedJsonError> {
+        let token_stream =
+            TokenGenerator::with_base(fragment, &self.options, base_position, base_byte);
+        let mut enumerator = TokenEnumerator::new(token_stream);
+        if !enumerator.move_next()? {
+            return Err(FracturedJsonError::simple("Fragment is empty"));
+        }
+        let item = self.parse_item(&mut enumerator, depth)?;
+        if enumerator.move_next()? {
+            return Err(FracturedJsonError::new(
+                "Unexpected content after end of fragment",
+                Some(enumerator.current()?.input_position),
+            ));
+        }
+        Ok(item)
+    }
+
+    /// Replaces the item at `path` and fixes up everything that follows it.
+    fn splice_reparsed(
+        items: &mut [JsonItem],
+        path: &[usize],
+        new_item: JsonItem,
+        old_end: usize,
+        new_end: usize,
+        shift: &PositionShift,
+    ) {
+        let (&idx, rest) = path.split_first().unwrap();
+        for sibling in &mut items[idx + 1..] {
+            Self::shift_item(sibling, old_end, new_end, shift);
+        }
+
+        if rest.is_empty() {
+            items[idx] = new_item;
+            return;
+        }
+
+        let parent = &mut items[idx];
+        parent.span.end = parent.span.end + new_end - old_end;
+        Self::splice_reparsed(
+            &mut parent.children,
+            rest,
+            new_item,
+            old_end,
+            new_end,
+            shift,
+        );
+        parent.complexity = parent
+            .children
+            .iter()
+            .filter(|child| !Self::is_comment_or_blank_line(child))
+            .map(|child| child.complexity + 1)
+            .max()
+            .unwrap_or(0);
+    }
+
+    /// Moves an item that lies after the edit to its place in the new text.
+    fn shift_item(
+        item: &mut JsonItem,
+        old_end: usize,
+        new_end: usize,
+        position_shift: &PositionShift,
+    ) {
+        let shift = |offset: usize| offset + new_end - old_end;
+        item.span = Span {
+            start: shift(item.span.start),
+            end: shift(item.span.end),
+        };
+        if item.name_span != Span::default() {
+            item.name_span = Span {
+                start: shift(item.name_span.start),
+                end: shift(item.name_span.end),
+            };
+        }
+        item.input_position = position_shift.apply(item.input_position);
+        for child in &mut item.children {
+            Self::shift_item(child, old_end, new_end, position_shift);
+        }
+    }
+
+    /// The policy for line comments or block comments: `line_comment_policy`
+    /// or `block_comment_policy` if it's set, otherwise `comment_policy`.
+    fn comment_policy(&self, is_line_comment: bool) -> CommentPolicy {
+        let policy = if is_line_comment {
+            self.options.line_comment_policy
+        } else {
+            self.options.block_comment_policy
+        };
+        policy.unwrap_or(self.options.comment_policy)
+    }
+
+    fn is_comment_or_blank_line(item: &JsonItem) -> bool {
+        matches!(
+            item.item_type,
+            JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+        )
+    }
+
     fn parse_top_level_from_enum<I>(
         &self,
         enumerator: &mut TokenEnumerator<I>,
@@ -75,22 +335,31 @@ impl Parser {
 
         loop {
             if !enumerator.move_next()? {
+                self.order_standalone_comments(&mut top_level_items);
+                self.collapse_blank_lines(&mut top_level_items);
                 return Ok(top_level_items);
             }
 
-            let item = self.parse_item(enumerator)?;
+            let item = self.parse_item(enumerator, 0)?;
             let is_comment = matches!(
                 item.item_type,
                 JsonItemType::BlockComment | JsonItemType::LineComment
             );
             let is_blank = item.item_type == JsonItemType::BlankLine;
 
+            if self.options.strict_rfc8259 && top_level_elem_seen && !is_blank {
+                return Err(FracturedJsonError::new(
+                    "Unexpected content after top-level value",
+                    Some(item.input_position),
+                ));
+            }
+
             if is_blank {
                 if self.options.preserve_blank_lines {
                     top_level_items.push(item);
                 }
             } else if is_comment {
-                match self.options.comment_policy {
+                match self.comment_policy(item.item_type == JsonItemType::LineComment) {
                     CommentPolicy::TreatAsError => {
                         return Err(FracturedJsonError::new(
                             "Comments not allowed with current options",
@@ -98,7 +367,10 @@ impl Parser {
                         ));
                     }
                     CommentPolicy::Preserve => top_level_items.push(item),
-                    CommentPolicy::Remove => {}
+                    CommentPolicy::PreserveHeader if !top_level_elem_seen => {
+                        top_level_items.push(item)
+                    }
+                    CommentPolicy::Remove | CommentPolicy::PreserveHeader => {}
                 }
             } else {
                 if stop_after_first_elem && top_level_elem_seen {
@@ -116,14 +388,26 @@ impl Parser {
     fn parse_item<I>(
         &self,
         enumerator: &mut TokenEnumerator<I>,
+        depth: usize,
     ) -> Result<JsonItem, FracturedJsonError>
     where
         I: Iterator<Item = Result<JsonToken, FracturedJsonError>>,
     {
         let current = enumerator.current()?.clone();
+        let is_container = matches!(
+            current.token_type,
+            TokenType::BeginArray | TokenType::BeginObject
+        );
+        if is_container && depth >= self.options.max_parse_depth {
+            return Err(FracturedJsonError::new(
+                "Maximum nesting depth exceeded",
+                Some(current.input_position),
+            ));
+        }
+
         match current.token_type {
-            TokenType::BeginArray => self.parse_array(enumerator),
-            TokenType::BeginObject => self.parse_object(enumerator),
+            TokenType::BeginArray => self.parse_array(enumerator, depth),
+            TokenType::BeginObject => self.parse_object(enumerator, depth),
             _ => self.parse_simple(&current),
         }
     }
@@ -133,6 +417,7 @@ impl Parser {
             item_type: Self::item_type_from_token_type(token)?,
             value: token.text.clone(),
             input_position: token.input_position,
+            span: token.span,
             complexity: 0,
             ..Default::default()
         })
@@ -141,6 +426,7 @@ impl Parser {
     fn parse_array<I>(
         &self,
         enumerator: &mut TokenEnumerator<I>,
+        depth: usize,
     ) -> Result<JsonItem, FracturedJsonError>
     where
         I: Iterator<Item = Result<JsonToken, FracturedJsonError>>,
@@ -153,6 +439,7 @@ impl Parser {
         }
 
         let starting_input_position = enumerator.current()?.input_position;
+        let starting_span = enumerator.current()?.span;
 
         let mut elem_needing_post_comment_idx: Option<usize> = None;
         let mut elem_needing_post_end_row: isize = -1;
@@ -220,10 +507,13 @@ impl Parser {
                     }
                 }
                 TokenType::BlockComment => {
-                    if self.options.comment_policy == CommentPolicy::Remove {
+                    if matches!(
+                        self.comment_policy(false),
+                        CommentPolicy::Remove | CommentPolicy::PreserveHeader
+                    ) {
                         continue;
                     }
-                    if self.options.comment_policy == CommentPolicy::TreatAsError {
+                    if self.comment_policy(false) == CommentPolicy::TreatAsError {
                         return Err(FracturedJsonError::new(
                             "Comments not allowed with current options",
                             Some(token.input_position),
@@ -254,10 +544,13 @@ impl Parser {
                     unplaced_comment = Some(comment_item);
                 }
                 TokenType::LineComment => {
-                    if self.options.comment_policy == CommentPolicy::Remove {
+                    if matches!(
+                        self.comment_policy(true),
+                        CommentPolicy::Remove | CommentPolicy::PreserveHeader
+                    ) {
                         continue;
                     }
-                    if self.options.comment_policy == CommentPolicy::TreatAsError {
+                    if self.comment_policy(true) == CommentPolicy::TreatAsError {
                         return Err(FracturedJsonError::new(
                             "Comments not allowed with current options",
                             Some(token.input_position),
@@ -295,7 +588,7 @@ impl Parser {
                         ));
                     }
 
-                    let mut element = self.parse_item(enumerator)?;
+                    let mut element = self.parse_item(enumerator, depth + 1)?;
                     comma_status = CommaStatus::ElementSeen;
                     this_array_complexity = this_array_complexity.max(element.complexity + 1);
 
@@ -316,9 +609,16 @@ impl Parser {
             }
         }
 
+        self.order_standalone_comments(&mut child_list);
+        self.separate_comment_blocks(&mut child_list);
+        self.collapse_blank_lines(&mut child_list);
         Ok(JsonItem {
             item_type: JsonItemType::Array,
             input_position: starting_input_position,
+            span: Span {
+                start: starting_span.start,
+                end: enumerator.current()?.span.end,
+            },
             complexity: this_array_complexity,
             children: child_list,
             ..Default::default()
@@ -328,6 +628,7 @@ impl Parser {
     fn parse_object<I>(
         &self,
         enumerator: &mut TokenEnumerator<I>,
+        depth: usize,
     ) -> Result<JsonItem, FracturedJsonError>
     where
         I: Iterator<Item = Result<JsonToken, FracturedJsonError>>,
@@ -340,6 +641,7 @@ impl Parser {
         }
 
         let starting_input_position = enumerator.current()?.input_position;
+        let starting_span = enumerator.current()?.span;
         let mut child_list: Vec<JsonItem> = Vec::new();
 
         let mut property_name: Option<JsonToken> = None;
@@ -350,6 +652,8 @@ impl Parser {
         let mut after_prop_comment: Option<JsonItem> = None;
         let mut after_prop_comment_was_after_comma = false;
 
+        let mut seen_names: HashSet<String> = HashSet::new();
+
         let mut phase = ObjectPhase::BeforePropName;
         let mut this_obj_complexity = 0usize;
         let mut end_of_object = false;
@@ -413,10 +717,14 @@ impl Parser {
                     child_list.push(self.parse_simple(&token)?);
                 }
                 TokenType::BlockComment | TokenType::LineComment => {
-                    if self.options.comment_policy == CommentPolicy::Remove {
+                    let policy = self.comment_policy(token.token_type == TokenType::LineComment);
+                    if matches!(
+                        policy,
+                        CommentPolicy::Remove | CommentPolicy::PreserveHeader
+                    ) {
                         continue;
                     }
-                    if self.options.comment_policy == CommentPolicy::TreatAsError {
+                    if policy == CommentPolicy::TreatAsError {
                         return Err(FracturedJsonError::new(
                             "Comments not allowed with current options",
                             Some(token.input_position),
@@ -444,10 +752,20 @@ impl Parser {
                 }
                 TokenType::String => {
                     if matches!(phase, ObjectPhase::BeforePropName | ObjectPhase::AfterComma) {
+                        if self.options.strict_rfc8259 {
+                            let name = serde_json::from_str::<String>(&token.text)
+                                .unwrap_or_else(|_| token.text.clone());
+                            if !seen_names.insert(name) {
+                                return Err(FracturedJsonError::new(
+                                    "Duplicate property name",
+                                    Some(token.input_position),
+                                ));
+                            }
+                        }
                         property_name = Some(token);
                         phase = ObjectPhase::AfterPropName;
                     } else if matches!(phase, ObjectPhase::AfterColon) {
-                        property_value = Some(self.parse_item(enumerator)?);
+                        property_value = Some(self.parse_item(enumerator, depth + 1)?);
                         line_prop_value_ends = enumerator.current()?.input_position.row as isize;
                         phase = ObjectPhase::AfterPropValue;
                     } else {
@@ -469,7 +787,7 @@ impl Parser {
                             Some(token.input_position),
                         ));
                     }
-                    property_value = Some(self.parse_item(enumerator)?);
+                    property_value = Some(self.parse_item(enumerator, depth + 1)?);
                     line_prop_value_ends = enumerator.current()?.input_position.row as isize;
                     phase = ObjectPhase::AfterPropValue;
                 }
@@ -507,9 +825,19 @@ impl Parser {
             ));
         }
 
+        // Comments between the last property and the closing brace.
+        child_list.append(&mut before_prop_comments);
+
+        self.order_standalone_comments(&mut child_list);
+        self.separate_comment_blocks(&mut child_list);
+        self.collapse_blank_lines(&mut child_list);
         Ok(JsonItem {
             item_type: JsonItemType::Object,
             input_position: starting_input_position,
+            span: Span {
+                start: starting_span.start,
+                end: enumerator.current()?.span.end,
+            },
             complexity: this_obj_complexity,
             children: child_list,
             ..Default::default()
@@ -549,6 +877,88 @@ impl Parser {
         Ok(enumerator.current()?.clone())
     }
 
+    fn order_standalone_comments(&self, items: &mut [JsonItem]) {
+        if self.options.standalone_comment_order != StandaloneCommentOrder::CommentsFirst {
+            return;
+        }
+
+        let is_standalone = |item: &JsonItem| {
+            matches!(
+                item.item_type,
+                JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+            )
+        };
+        let mut run_start = 0;
+        while run_start < items.len() {
+            if !is_standalone(&items[run_start]) {
+                run_start += 1;
+                continue;
+            }
+            let mut run_end = run_start;
+            while run_end < items.len() && is_standalone(&items[run_end]) {
+                run_end += 1;
+            }
+            items[run_start..run_end].sort_by_key(|item| item.item_type == JsonItemType::BlankLine);
+            run_start = run_end;
+        }
+    }
+
+    /// Makes each run of standalone comments in a container follow exactly one
+    /// blank line, or none if the run opens the container.
+    fn separate_comment_blocks(&self, items: &mut Vec<JsonItem>) {
+        if !self.options.blank_line_before_comments {
+            return;
+        }
+
+        let is_comment = |item: &JsonItem| {
+            matches!(
+                item.item_type,
+                JsonItemType::BlockComment | JsonItemType::LineComment
+            )
+        };
+        let mut separated: Vec<JsonItem> = Vec::with_capacity(items.len());
+        for item in items.drain(..) {
+            if is_comment(&item) && !separated.last().is_some_and(is_comment) {
+                while separated
+                    .last()
+                    .is_some_and(|prev| prev.item_type == JsonItemType::BlankLine)
+                {
+                    separated.pop();
+                }
+                if !separated.is_empty() {
+                    separated.push(JsonItem {
+                        item_type: JsonItemType::BlankLine,
+                        input_position: item.input_position,
+                        span: Span {
+                            start: item.span.start,
+                            end: item.span.start,
+                        },
+                        ..Default::default()
+                    });
+                }
+            }
+            separated.push(item);
+        }
+        *items = separated;
+    }
+
+    /// Drops the blank lines in each run of them past the first
+    /// `max_consecutive_blank_lines`.
+    fn collapse_blank_lines(&self, items: &mut Vec<JsonItem>) {
+        let Some(max_blank_lines) = self.options.max_consecutive_blank_lines else {
+            return;
+        };
+        let mut run_length = 0;
+        items.retain(|item| {
+            if item.item_type != JsonItemType::BlankLine {
+                run_length = 0;
+                return true;
+            }
+            run_length += 1;
+            run_length <= max_blank_lines
+        });
+    }
+
     fn is_multiline_comment(item: &JsonItem) -> bool {
         item.item_type == JsonItemType::BlockComment && item.value.contains('\n')
     }
@@ -564,6 +974,7 @@ impl Parser {
     ) {
         let mut element = element.clone();
         element.name = name.text.clone();
+        element.name_span = name.span;
 
         if !mid_comments.is_empty() {
             let mut combined = String::new();
@@ -608,6 +1019,61 @@ impl Parser {
     }
 }
 
+/// The position just past `text`, which starts at `start`.
+fn advance_position(start: InputPosition, text: &str) -> InputPosition {
+    text.chars().fold(start, |position, ch| {
+        if ch == '\n' {
+            InputPosition {
+                index: position.index + 1,
+                row: position.row + 1,
+                column: 0,
+            }
+        } else {
+            InputPosition {
+                index: position.index + 1,
+                column: position.column + 1,
+                ..position
+            }
+        }
+    })
+}
+
+/// How the positions of the items after an edit move: all of them by the
+/// same number of characters and rows, and those on the row of the first one
+/// by a number of columns as well, since the text in between is unchanged.
+#[derive(Default)]
+struct PositionShift {
+    row: usize,
+    chars: isize,
+    rows: isize,
+    columns: isize,
+}
+
+impl PositionShift {
+    /// The shift that moves the first item after an edit from `old` to `new`.
+    fn new(old: InputPosition, new: InputPosition) -> Self {
+        Self {
+            row: old.row,
+            chars: new.index as isize - old.index as isize,
+            rows: new.row as isize - old.row as isize,
+            columns: new.column as isize - old.column as isize,
+        }
+    }
+
+    fn apply(&self, position: InputPosition) -> InputPosition {
+        let columns = if position.row == self.row {
+            self.columns
+        } else {
+            0
+        };
+        InputPosition {
+            index: position.index.saturating_add_signed(self.chars),
+            row: position.row.saturating_add_signed(self.rows),
+            column: position.column.saturating_add_signed(columns),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CommaStatus {
     EmptyCollection,
@@ -903,6 +1369,49 @@ mod tests {
         assert_eq!(remove_expected_types, remove_found_types);
     }
 
+    #[test]
+    fn runs_of_blank_lines_can_be_collapsed() {
+        let input = [
+            "{",
+            "",
+            "",
+            "",
+            "    \"a\": 1,",
+            "",
+            "    \"b\": 2",
+            "}",
+            "",
+            "",
+        ]
+        .join("\n");
+
+        let options = FracturedJsonOptions {
+            preserve_blank_lines: true,
+            max_consecutive_blank_lines: Some(1),
+            ..Default::default()
+        };
+
+        let parser = Parser::new(options);
+        let doc_model = parser.parse_top_level(&input, false).unwrap();
+
+        assert_eq!(doc_model.len(), 2);
+        assert_eq!(doc_model[1].item_type, JsonItemType::BlankLine);
+        let found_types: Vec<JsonItemType> = doc_model[0]
+            .children
+            .iter()
+            .map(|ch| ch.item_type)
+            .collect();
+        assert_eq!(
+            found_types,
+            vec![
+                JsonItemType::BlankLine,
+                JsonItemType::Number,
+                JsonItemType::BlankLine,
+                JsonItemType::Number,
+            ]
+        );
+    }
+
     #[test]
     fn test_simple_and_valid_object() {
         let input = "{ \"a\": 5.2, \"b\": false, \"c\": null, \"d\": true, \"e\":[], \"f\":{}, \"g\": \"a string\" }";
@@ -1263,6 +1772,196 @@ mod tests {
         }
     }
 
+    #[test]
+    fn items_carry_byte_spans() {
+        let input = "{\n  \"ä\": [1, \"ü\"], // note\n  \"b\": {}\n}";
+        let options = FracturedJsonOptions {
+            comment_policy: CommentPolicy::Preserve,
+            ..Default::default()
+        };
+        let parser = Parser::new(options);
+        let doc_model = parser.parse_top_level(input, false).unwrap();
+        let source = |span: Span| &input[span.start..span.end];
+
+        let root = &doc_model[0];
+        assert_eq!(source(root.span), input);
+
+        let first = &root.children[0];
+        assert_eq!(source(first.name_span), "\"ä\"");
+        assert_eq!(source(first.span), "[1, \"ü\"]");
+        assert_eq!(first.postfix_comment, "// note");
+        assert_eq!(source(first.children[1].span), "\"ü\"");
+
+        let second = &root.children[1];
+        assert_eq!(source(second.name_span), "\"b\"");
+        assert_eq!(source(second.span), "{}");
+    }
+
+    #[test]
+    fn nesting_beyond_max_parse_depth_is_an_error() {
+        let options = FracturedJsonOptions {
+            max_parse_depth: 3,
+            ..Default::default()
+        };
+        let parser = Parser::new(options);
+
+        assert!(parser.parse_top_level("[[[1]]]", false).is_ok());
+        assert!(parser.parse_top_level("{\"a\": [{}]}", false).is_ok());
+
+        let err = parser.parse_top_level("[[[[1]]]]", false).unwrap_err();
+        assert_eq!(err.input_position.unwrap().index, 3);
+
+        let err = parser
+            .parse_top_level("{\"a\": [{\"b\": []}]}", false)
+            .unwrap_err();
+        assert_eq!(err.input_position.unwrap().index, 13);
+    }
+
+    #[test]
+    fn pathological_nesting_does_not_overflow_the_stack() {
+        let input = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        let parser = Parser::new(FracturedJsonOptions::default());
+        assert!(parser.parse_top_level(&input, false).is_err());
+    }
+
+    #[test]
+    fn reparse_checks_the_size_of_the_whole_document() {
+        let old_text = "[[1], 2, 3, 4, 5]";
+        let new_text = "[[1, 1, 1], 2, 3, 4, 5]";
+        let edit = InputEdit {
+            start: 2,
+            old_end: 3,
+            new_end: 9,
+        };
+
+        let parser = Parser::new(FracturedJsonOptions {
+            max_document_size: 20,
+            ..Default::default()
+        });
+        let old_dom = parser.parse_top_level(old_text, true).unwrap();
+        let err = parser.reparse(old_dom, edit, new_text).unwrap_err();
+        assert!(err.message.contains("Maximum document length exceeded"));
+    }
+
+    #[test]
+    fn reparse_matches_full_parse() {
+        let old_text = [
+            "{",
+            "    // first",
+            "    \"ä\": [1, 2, /* two */ 3],",
+            "",
+            "    \"b\": {\"c\": [true, null], \"d\": \"ü\"}, // after b",
+            "    \"e\": [[1], [2, 3]]",
+            "}",
+        ]
+        .join("\n");
+        let find = |needle: &str| old_text.find(needle).unwrap();
+
+        // (start, old_end, replacement, expect splice rather than full parse)
+        let edits = vec![
+            (find("2, /*"), find("2, /*") + 1, "20,\n 21", true),
+            (find("true"), find("true") + 4, "false", true),
+            (find("null"), find("null") + 4, "null,\n  \"é\"", true),
+            (find("\"ü\""), find("\"ü\"") + 4, "[\n\n]", true),
+            (find("[2, 3]") + 1, find("[2, 3]") + 4, "", true),
+            (find("[1], ") + 1, find("[1], ") + 1, "// new\n", true),
+            (find("\"ä\""), find("\"ä\"") + 4, "\"x\"", true),
+            (find("1, 2"), find("1, 2") + 1, "1]", false),
+            (find("1, 2"), find("1, 2") + 1, "{", false),
+            (0, 1, "[", false),
+        ];
+
+        let options = FracturedJsonOptions {
+            comment_policy: CommentPolicy::Preserve,
+            preserve_blank_lines: true,
+            ..Default::default()
+        };
+        let parser = Parser::new(options);
+        let old_dom = parser.parse_top_level(&old_text, true).unwrap();
+
+        for (start, old_end, replacement, expect_splice) in edits {
+            let new_text = format!(
+                "{}{}{}",
+                &old_text[..start],
+                replacement,
+                &old_text[old_end..]
+            );
+            let edit = InputEdit {
+                start,
+                old_end,
+                new_end: start + replacement.len(),
+            };
+
+            let mut spliced = old_dom.clone();
+            assert_eq!(
+                parser.reparse_in_place(&mut spliced, edit, &new_text),
+                expect_splice,
+                "new_text={}",
+                new_text
+            );
+
+            let expected = parser.parse_top_level(&new_text, true);
+            let actual = parser.reparse(old_dom.clone(), edit, &new_text);
+            match (expected, actual) {
+                (Ok(expected), Ok(actual)) => assert_eq!(
+                    format!("{:?}", actual),
+                    format!("{:?}", expected),
+                    "new_text={}",
+                    new_text
+                ),
+                (Err(expected), Err(actual)) => {
+                    assert_eq!(actual.message, expected.message, "new_text={}", new_text)
+                }
+                (expected, actual) => panic!(
+                    "new_text={} expected={:?} actual={:?}",
+                    new_text, expected, actual
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn strict_mode_rejects_duplicate_keys() {
+        let input = "{\"a\": 1, \"b\": {\"a\": 2}, \"\\u0061\": 3}";
+        let lenient = Parser::new(FracturedJsonOptions::default());
+        assert!(lenient.parse_top_level(input, true).is_ok());
+
+        let strict = Parser::new(FracturedJsonOptions {
+            strict_rfc8259: true,
+            ..Default::default()
+        });
+        let err = strict.parse_top_level(input, true).unwrap_err();
+        assert!(err.message.starts_with("Duplicate property name"));
+        assert_eq!(err.input_position.unwrap().index, 24);
+
+        assert!(strict
+            .parse_top_level("{\"a\": {\"a\": 1}, \"b\": [{\"a\": 1}, {\"a\": 2}]}", true)
+            .is_ok());
+    }
+
+    #[test]
+    fn strict_mode_rejects_content_after_top_level_value() {
+        let options = FracturedJsonOptions {
+            strict_rfc8259: true,
+            comment_policy: CommentPolicy::Preserve,
+            preserve_blank_lines: true,
+            ..Default::default()
+        };
+        let parser = Parser::new(options);
+
+        assert!(parser.parse_top_level("// lead\n[1]\n\n", true).is_ok());
+
+        for input in ["[1] // tail", "[1]\n\n/* tail */", "[1] [2]", "1 2"] {
+            let err = parser.parse_top_level(input, false).unwrap_err();
+            assert!(
+                err.message
+                    .starts_with("Unexpected content after top-level value"),
+                "input={}",
+                input
+            );
+        }
+    }
+
     #[test]
     fn stops_after_first_element() {
         let input = "[ 1, 2 ],[ 3, 4 ]";
@@ -0,0 +1,24 @@
+//! The types most programs need, for a single glob import.
+//!
+//! ```rust
+//! use fracturedjson::prelude::*;
+//!
+//! let mut formatter = Formatter::with_options(FracturedJsonOptions {
+//!     comment_policy: CommentPolicy::Preserve,
+//!     ..Default::default()
+//! });
+//! let output: Result<String, FracturedJsonError> = formatter.reformat("[1, 2, 3]", 0);
+//! assert_eq!(output.unwrap(), "[1, 2, 3]\n");
+//! ```
+//!
+//! It holds the formatter, its options and error, and the option enums most
+//! configurations set. Everything else, such as the document model, tracing
+//! and table types, is imported from the crate root. Items are added to the
+//! prelude in minor releases and only removed in major ones.
+
+pub use crate::error::FracturedJsonError;
+pub use crate::formatter::Formatter;
+pub use crate::options::{
+    CommentPolicy, CommentStyle, EolStyle, FracturedJsonOptions, KeyCase, NumberListAlignment,
+    TableCommaPlacement,
+};
@@ -0,0 +1,235 @@
+use crate::convert::escape_pointer_token;
+use crate::model::{JsonItem, JsonItemType};
+use crate::notation::canonical_number;
+
+/// One step in a document-order walk of the DOM. Only `what` takes part in
+/// comparisons; `pointer` says where the step happened for error messages.
+struct Step {
+    pointer: String,
+    what: String,
+}
+
+/// Compares two parsed documents semantically and describes the first
+/// difference, if any.
+///
+/// Values, property names and their order, and the text of comments are
+/// compared. Whitespace, blank lines, number formatting (`1` vs `1.0`),
+/// string escaping and the indentation of multi-line block comments are not.
+pub fn find_round_trip_difference(expected: &[JsonItem], actual: &[JsonItem]) -> Option<String> {
+    let expected_steps = walk_document(expected);
+    let actual_steps = walk_document(actual);
+
+    for i in 0..expected_steps.len().max(actual_steps.len()) {
+        let (pointer, expected_what, actual_what) =
+            match (expected_steps.get(i), actual_steps.get(i)) {
+                (Some(exp), Some(act)) if exp.what == act.what => continue,
+                (Some(exp), Some(act)) => (&exp.pointer, exp.what.as_str(), act.what.as_str()),
+                (Some(exp), None) => (&exp.pointer, exp.what.as_str(), "end of document"),
+                (None, Some(act)) => (&act.pointer, "end of document", act.what.as_str()),
+                (None, None) => break,
+            };
+        return Some(format!(
+            "at '{}': expected {}, found {}",
+            pointer, expected_what, actual_what
+        ));
+    }
+    None
+}
+
+fn walk_document(doc_model: &[JsonItem]) -> Vec<Step> {
+    let mut steps = Vec::new();
+    for item in doc_model {
+        walk_item(item, "", &mut steps);
+    }
+
+    // Adjacent comments can be split or joined differently by the two parses
+    // (JSONL lines fold them into one prefix or postfix comment), so compare
+    // each run of them as a single step.
+    let mut merged: Vec<Step> = Vec::with_capacity(steps.len());
+    for step in steps {
+        match (merged.last_mut(), step.what.strip_prefix(COMMENT)) {
+            (Some(last), Some(text)) if last.what.starts_with(COMMENT) => {
+                last.what.push(' ');
+                last.what.push_str(text);
+            }
+            _ => merged.push(step),
+        }
+    }
+    merged
+}
+
+fn walk_item(item: &JsonItem, pointer: &str, steps: &mut Vec<Step>) {
+    match item.item_type {
+        JsonItemType::BlankLine => return,
+        JsonItemType::BlockComment | JsonItemType::LineComment => {
+            push(steps, pointer, describe_comment(&item.value));
+            return;
+        }
+        _ => {}
+    }
+
+    for comment in [&item.prefix_comment, &item.middle_comment] {
+        if !comment.is_empty() {
+            push(steps, pointer, describe_comment(comment));
+        }
+    }
+
+    match item.item_type {
+        JsonItemType::Null => push(steps, pointer, "null".to_string()),
+        JsonItemType::True => push(steps, pointer, "true".to_string()),
+        JsonItemType::False => push(steps, pointer, "false".to_string()),
+        JsonItemType::Number => push(
+            steps,
+            pointer,
+            format!(
+                "number {}",
+                canonical_number(&item.value).unwrap_or_else(|| item.value.clone())
+            ),
+        ),
+        JsonItemType::String => push(
+            steps,
+            pointer,
+            format!("string {:?}", decode_string(&item.value)),
+        ),
+        JsonItemType::Array => {
+            push(steps, pointer, "start of array".to_string());
+            let mut index = 0;
+            for child in &item.children {
+                let child_pointer = format!("{}/{}", pointer, index);
+                walk_item(child, &child_pointer, steps);
+                if is_value(child) {
+                    index += 1;
+                }
+            }
+            push(steps, pointer, "end of array".to_string());
+        }
+        JsonItemType::Object => {
+            push(steps, pointer, "start of object".to_string());
+            for child in &item.children {
+                if !is_value(child) {
+                    walk_item(child, pointer, steps);
+                    continue;
+                }
+                let key = decode_string(&child.name);
+                let child_pointer = format!("{}/{}", pointer, escape_pointer_token(&key));
+                push(steps, &child_pointer, format!("property {:?}", key));
+                walk_item(child, &child_pointer, steps);
+            }
+            push(steps, pointer, "end of object".to_string());
+        }
+        JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment => {}
+    }
+
+    if !item.postfix_comment.is_empty() {
+        push(steps, pointer, describe_comment(&item.postfix_comment));
+    }
+}
+
+fn push(steps: &mut Vec<Step>, pointer: &str, what: String) {
+    steps.push(Step {
+        pointer: pointer.to_string(),
+        what,
+    });
+}
+
+fn is_value(item: &JsonItem) -> bool {
+    !matches!(
+        item.item_type,
+        JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+    )
+}
+
+const COMMENT: &str = "comment ";
+
+fn describe_comment(comment: &str) -> String {
+    let normalized: Vec<&str> = comment.lines().map(str::trim).collect();
+    format!("{}{}", COMMENT, normalized.join("\n"))
+}
+
+fn decode_string(value: &str) -> String {
+    if let Ok(decoded) = serde_json::from_str::<String>(value) {
+        return decoded;
+    }
+
+    // Raw control characters are accepted by some control_char_policy
+    // settings but rejected by serde_json.
+    let escaped: String = value
+        .chars()
+        .map(|ch| {
+            if (ch as u32) < 0x20 {
+                format!("\\u{:04x}", ch as u32)
+            } else {
+                ch.to_string()
+            }
+        })
+        .collect();
+    serde_json::from_str::<String>(&escaped).unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::{CommentPolicy, FracturedJsonOptions};
+    use crate::parser::Parser;
+
+    fn difference(expected: &str, actual: &str) -> Option<String> {
+        let parser = Parser::new(FracturedJsonOptions {
+            comment_policy: CommentPolicy::Preserve,
+            preserve_blank_lines: true,
+            ..Default::default()
+        });
+        let expected = parser.parse_top_level(expected, false).unwrap();
+        let actual = parser.parse_top_level(actual, false).unwrap();
+        find_round_trip_difference(&expected, &actual)
+    }
+
+    #[test]
+    fn formatting_differences_are_ignored() {
+        let expected = "{\"a\":[1,2.5,\"\\u0041\"],\n\n/* x\n     y */\n\"b\":null}";
+        let actual = "{\n    \"a\": [1.0, 2.50, \"A\"],\n    /* x\n    y */\n    \"b\": null\n}";
+        assert_eq!(difference(expected, actual), None);
+    }
+
+    #[test]
+    fn value_and_order_changes_are_reported() {
+        assert_eq!(
+            difference(r#"{"a": [1, 2]}"#, r#"{"a": [1, 3]}"#).as_deref(),
+            Some("at '/a/1': expected number 2, found number 3")
+        );
+        assert_eq!(
+            difference(r#"{"a": 1, "b": 2}"#, r#"{"b": 2, "a": 1}"#).as_deref(),
+            Some("at '/a': expected property \"a\", found property \"b\"")
+        );
+        assert_eq!(
+            difference("[1, 2]", "[1]").as_deref(),
+            Some("at '/1': expected number 2, found end of array")
+        );
+    }
+
+    #[test]
+    fn numbers_are_compared_exactly() {
+        assert_eq!(difference("[1.5e2, -0.0, 2E+30]", "[150, -0, 2e30]"), None);
+        assert_eq!(
+            difference("[12345678901234567890]", "[12345678901234567000]").as_deref(),
+            Some(
+                "at '/0': expected number 12345678901234567890, found number 12345678901234567000"
+            )
+        );
+        assert_eq!(
+            difference("[0.1000000000000000000001]", "[0.1]").as_deref(),
+            Some("at '/0': expected number 0.1000000000000000000001, found number 0.1")
+        );
+    }
+
+    #[test]
+    fn comment_changes_are_reported() {
+        assert_eq!(
+            difference("[1, // one\n 2]", "[1, // uno\n 2]").as_deref(),
+            Some("at '/0': expected comment // one, found comment // uno")
+        );
+        assert_eq!(
+            difference("[1, /* one */ 2]", "[1, 2]").as_deref(),
+            Some("at '/1': expected comment /* one */, found number 2")
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::model::TableColumnType;
+use crate::table_template::TableTemplate;
+
+/// The kind of values in a table column, as reported in a [`TableInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableColumnKind {
+    /// Only nulls.
+    Null,
+    /// Strings, possibly along with booleans and nulls.
+    String,
+    /// Numbers and nulls.
+    Number,
+    /// Booleans and nulls.
+    Boolean,
+    /// ISO 8601 date and time strings and nulls, when
+    /// `detect_datetime_columns` is set.
+    DateTime,
+    /// Arrays and nulls.
+    Array,
+    /// Objects and nulls.
+    Object,
+    /// Values of differing kinds, written without alignment.
+    Mixed,
+}
+
+/// An array or object that [`Formatter::analyze`](crate::Formatter::analyze)
+/// found written as a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableInfo {
+    /// JSON Pointer of the array or object.
+    pub path: String,
+    /// Number of rows, not counting comments and blank lines.
+    pub row_count: usize,
+    /// What the rows are.
+    pub row_kind: TableColumnKind,
+    /// Width of an aligned row, without its indentation and comma.
+    pub row_width: usize,
+    /// The aligned columns of the rows, in output order: one per key when
+    /// the rows are objects, one per position when they're arrays. Empty if
+    /// the rows are aligned as whole values.
+    pub columns: Vec<TableColumnInfo>,
+}
+
+/// A column of a [`TableInfo`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableColumnInfo {
+    /// The unescaped key, for a column of object rows. None for a column of
+    /// array rows, which are matched by position.
+    pub name: Option<String>,
+    /// What the values in the column are.
+    pub kind: TableColumnKind,
+    /// Number of rows with a value, possibly null, in the column.
+    pub row_count: usize,
+    /// Width of the column in each row, including the key and colon.
+    pub width: usize,
+    /// The columns within this one, when its values are arrays or objects
+    /// that are aligned too.
+    pub columns: Vec<TableColumnInfo>,
+}
+
+impl TableInfo {
+    pub(crate) fn new(path: String, row_count: usize, template: &TableTemplate) -> Self {
+        Self {
+            path,
+            row_count,
+            row_kind: column_kind(template.column_type),
+            row_width: template.total_length,
+            columns: template.children.iter().map(column_info).collect(),
+        }
+    }
+}
+
+fn column_info(template: &TableTemplate) -> TableColumnInfo {
+    let name = template
+        .location_in_parent
+        .as_ref()
+        .map(|name| serde_json::from_str::<String>(name).unwrap_or_else(|_| name.clone()));
+    TableColumnInfo {
+        name,
+        kind: column_kind(template.column_type),
+        row_count: template.row_count,
+        width: template.total_length,
+        columns: template.children.iter().map(column_info).collect(),
+    }
+}
+
+fn column_kind(column_type: TableColumnType) -> TableColumnKind {
+    match column_type {
+        TableColumnType::Unknown => TableColumnKind::Null,
+        TableColumnType::Simple => TableColumnKind::String,
+        TableColumnType::Number => TableColumnKind::Number,
+        TableColumnType::Boolean => TableColumnKind::Boolean,
+        TableColumnType::DateTime => TableColumnKind::DateTime,
+        TableColumnType::Array => TableColumnKind::Array,
+        TableColumnType::Object => TableColumnKind::Object,
+        TableColumnType::Mixed => TableColumnKind::Mixed,
+    }
+}
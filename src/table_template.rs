@@ -1,7 +1,39 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
 use crate::buffer::PaddedFormattingTokens;
 use crate::buffer::StringJoinBuffer;
 use crate::model::{BracketPaddingType, JsonItem, JsonItemType, TableColumnType};
-use crate::options::NumberListAlignment;
+use crate::options::{
+    CellAlignment, DecimalRounding, NormalizeFallback, NumberListAlignment, TableColumnOrder,
+};
+
+pub type ClassifyFn = Arc<dyn Fn(&str) -> Option<CellAlignment> + Send + Sync>;
+
+/// A user function classifying string cells, as set in
+/// [`Formatter::string_cell_classifier`](crate::Formatter::string_cell_classifier).
+#[derive(Clone)]
+pub struct StringClassifier(pub ClassifyFn);
+
+impl fmt::Debug for StringClassifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StringClassifier")
+    }
+}
+
+pub type NumberWidthFn = Arc<dyn Fn(&str) -> usize + Send + Sync>;
+
+/// A user function measuring numbers, as set in
+/// [`Formatter::number_width_func`](crate::Formatter::number_width_func).
+#[derive(Clone)]
+pub struct NumberWidth(pub NumberWidthFn);
+
+impl fmt::Debug for NumberWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NumberWidth")
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct TableTemplate {
@@ -23,11 +55,69 @@ pub struct TableTemplate {
     pub total_length: usize,
     pub shorter_than_null_adjustment: usize,
     pub contains_null: bool,
+    /// Whether object members with a `null` value are written as blank
+    /// cells, and so take no room in their column.
+    pub blank_null_members: bool,
+    /// Whether strings holding a JSON number are measured and written as
+    /// numbers.
+    pub numeric_strings: bool,
+    /// Whether columns of strings starting with numbers, like `"3 MB"`, are
+    /// aligned on the end of the numbers.
+    pub unit_strings: bool,
+    /// Whether object rows that lack some of the columns are written with
+    /// `null` in them, so the nulls are measured too.
+    pub fill_missing_members: bool,
+    /// Whether arrays are aligned element by element only when they all
+    /// have the same number of elements.
+    pub equal_array_lengths_only: bool,
+    /// Whether columns of ISO 8601 date and time strings are told apart
+    /// from other strings.
+    pub datetime_strings: bool,
+    /// Whether columns of integers are padded with leading zeros.
+    pub zero_pad_integers: bool,
+    pub string_classifier: Option<StringClassifier>,
+    pub number_width: Option<NumberWidth>,
+    /// Most decimal places written for normalized numbers, and how the rest
+    /// are rounded off.
+    pub max_decimal_places: Option<usize>,
+    pub decimal_rounding: DecimalRounding,
+    /// Whether normalized negative zeros lose their sign.
+    pub unsigned_zero: bool,
+    pub normalize_fallback: NormalizeFallback,
+    /// Least room kept before the decimal point of aligned numbers.
+    pub min_integer_digits: usize,
+    /// Index of the first of the columns that are written unaligned, if any.
+    pub unaligned_from: Option<usize>,
+    /// Whether property names are written without padding to `name_length`.
+    pub unpadded_names: bool,
     pub children: Vec<TableTemplate>,
     pads: PaddedFormattingTokens,
     number_list_alignment: NumberListAlignment,
     max_dig_before_dec: usize,
     max_dig_after_dec: usize,
+    max_exponent_len: usize,
+    /// Length of the longest number kept as text because it couldn't be
+    /// normalized.
+    max_kept_text_len: usize,
+    object_row_count: usize,
+    array_length: Option<usize>,
+    /// Most digits of any integer in the column, and whether it holds numbers
+    /// that aren't integers.
+    max_integer_len: usize,
+    has_negative_integers: bool,
+    has_non_integers: bool,
+    has_uneven_arrays: bool,
+    /// Longest number starting a string in the column, counting the opening
+    /// quote, and longest rest of such a string, and whether the column has
+    /// strings or booleans that don't start with a number.
+    max_unit_number_len: usize,
+    max_unit_suffix_len: usize,
+    has_unitless_values: bool,
+    min_value_length: usize,
+    /// What `string_classifier` made of the column's strings: None before
+    /// the first, then the class they all share, or `Some(None)` if they
+    /// don't.
+    string_class: Option<Option<CellAlignment>>,
 }
 
 impl TableTemplate {
@@ -51,21 +141,146 @@ impl TableTemplate {
             total_length: 0,
             shorter_than_null_adjustment: 0,
             contains_null: false,
+            blank_null_members: false,
+            numeric_strings: false,
+            unit_strings: false,
+            fill_missing_members: false,
+            equal_array_lengths_only: false,
+            datetime_strings: false,
+            zero_pad_integers: false,
+            string_classifier: None,
+            number_width: None,
+            max_decimal_places: None,
+            decimal_rounding: DecimalRounding::Nearest,
+            unsigned_zero: false,
+            normalize_fallback: NormalizeFallback::Unaligned,
+            min_integer_digits: 0,
+            unaligned_from: None,
+            unpadded_names: false,
             children: Vec::new(),
             pads,
             number_list_alignment,
             max_dig_before_dec: 0,
             max_dig_after_dec: 0,
+            max_exponent_len: 0,
+            max_kept_text_len: 0,
+            object_row_count: 0,
+            array_length: None,
+            max_integer_len: 0,
+            has_negative_integers: false,
+            has_non_integers: false,
+            has_uneven_arrays: false,
+            max_unit_number_len: 0,
+            max_unit_suffix_len: 0,
+            has_unitless_values: false,
+            min_value_length: usize::MAX,
+            string_class: None,
         }
     }
 
     pub fn measure_table_root(&mut self, table_root: &JsonItem, recursive: bool) {
+        self.measure_table_root_except(table_root, recursive, |_| false);
+    }
+
+    /// Like `measure_table_root`, but leaves out the rows `is_excluded`
+    /// picks. Those rows can't be written with this template.
+    pub fn measure_table_root_except(
+        &mut self,
+        table_root: &JsonItem,
+        recursive: bool,
+        is_excluded: impl Fn(&JsonItem) -> bool,
+    ) {
         for child in &table_root.children {
-            self.measure_row_segment(child, recursive);
+            // Nothing is aligned in an array of mixed rows, so there's no
+            // need to measure the rest.
+            if self.column_type == TableColumnType::Mixed
+                && table_root.item_type == JsonItemType::Array
+            {
+                break;
+            }
+            if !is_excluded(child) {
+                self.measure_row_segment(child, recursive);
+            }
         }
         self.prune_and_recompute(usize::MAX);
     }
 
+    /// Measures the rows of `table_root` like `measure_table_root`, but
+    /// keeps every column, however few rows there are. For exports, which
+    /// need the columns but not their widths.
+    pub fn measure_table_columns(&mut self, table_root: &JsonItem) {
+        for child in &table_root.children {
+            self.measure_row_segment(child, true);
+        }
+    }
+
+    /// Reorders the columns of this template and those nested in it: keys
+    /// in `leading_keys` first, in that order, then the rest as `order` says.
+    pub fn order_columns(&mut self, order: TableColumnOrder, leading_keys: &[String]) {
+        if order == TableColumnOrder::FirstSeen && leading_keys.is_empty() {
+            return;
+        }
+        for child in &mut self.children {
+            child.order_columns(order, leading_keys);
+        }
+        if self.column_type != TableColumnType::Object {
+            return;
+        }
+
+        // The sort is stable, so unlisted keys keep their first-seen order
+        // unless they're sorted.
+        self.children.sort_by_cached_key(|column| {
+            let name = column.location_in_parent.as_deref().unwrap_or_default();
+            let key = serde_json::from_str::<String>(name).unwrap_or_default();
+            match leading_keys.iter().position(|leading| *leading == key) {
+                Some(rank) => (rank, String::new()),
+                None if order == TableColumnOrder::Alphabetical => (usize::MAX, key),
+                None => (usize::MAX, String::new()),
+            }
+        });
+    }
+
+    /// Marks the first column, at any depth, that aligning would pad by more
+    /// than `max_padding` spaces in some row. It and the columns after it are
+    /// written unaligned. A column holding unaligned columns counts too,
+    /// since its width varies.
+    pub fn limit_column_padding(&mut self, max_padding: usize) {
+        for child in &mut self.children {
+            child.limit_column_padding(max_padding);
+        }
+        self.unaligned_from = self.children.iter().position(|child| {
+            child.unaligned_from.is_some()
+                || child
+                    .composite_value_length
+                    .saturating_sub(child.min_value_length)
+                    > max_padding
+        });
+    }
+
+    /// The alignment `string_classifier` gave every string in the column,
+    /// if it gave them all the same one.
+    pub fn string_cell_alignment(&self) -> Option<CellAlignment> {
+        self.string_class.flatten()
+    }
+
+    /// The padding before the string `item` that lines the end of its leading
+    /// number up with the others in the column, or None if the column isn't
+    /// aligned that way.
+    pub fn unit_padding(&self, item: &JsonItem) -> Option<usize> {
+        if !self.is_unit_aligned() || item.item_type != JsonItemType::String {
+            return None;
+        }
+        let number_len = unit_number_len(&item.value)?;
+        Some(self.max_unit_number_len - number_len)
+    }
+
+    fn is_unit_aligned(&self) -> bool {
+        self.unit_strings
+            && self.column_type == TableColumnType::Simple
+            && self.max_unit_number_len > 0
+            && !self.has_unitless_values
+    }
+
     pub fn try_to_fit(&mut self, maximum_length: usize) -> bool {
         let mut complexity = self.get_template_complexity();
         loop {
@@ -86,17 +301,47 @@ impl TableTemplate {
         item: &JsonItem,
         comma_before_pad_type: &str,
     ) {
+        if self.zero_pad_integers
+            && !self.has_non_integers
+            && item.item_type == JsonItemType::Number
+        {
+            let (sign, digits) = match item.value.strip_prefix('-') {
+                Some(digits) => ("-", digits),
+                None => ("", item.value.as_str()),
+            };
+            let zeros = "0".repeat(self.max_integer_len.saturating_sub(digits.len()));
+            let padding = self
+                .composite_value_length
+                .saturating_sub(sign.len() + self.max_integer_len);
+            if self.number_list_alignment == NumberListAlignment::Left {
+                buffer
+                    .add(sign)
+                    .add(&zeros)
+                    .add(digits)
+                    .add(comma_before_pad_type)
+                    .spaces(padding);
+            } else {
+                buffer
+                    .spaces(padding)
+                    .add(sign)
+                    .add(&zeros)
+                    .add(digits)
+                    .add(comma_before_pad_type);
+            }
+            return;
+        }
+
         match self.number_list_alignment {
             NumberListAlignment::Left => {
                 buffer
                     .add(&item.value)
                     .add(comma_before_pad_type)
-                    .spaces(self.max_value_length - item.value_length);
+                    .spaces(self.composite_value_length - item.value_length);
                 return;
             }
             NumberListAlignment::Right => {
                 buffer
-                    .spaces(self.max_value_length - item.value_length)
+                    .spaces(self.composite_value_length - item.value_length)
                     .add(&item.value)
                     .add(comma_before_pad_type);
                 return;
@@ -113,11 +358,38 @@ impl TableTemplate {
             return;
         }
 
+        if self.number_list_alignment == NumberListAlignment::Exponent {
+            let (mantissa, exponent) = split_exponent(&item.value);
+            if !exponent.is_empty() {
+                let mantissa = self.zero_extended_mantissa(mantissa);
+                let integer = dot_or_e_index(&mantissa).map_or(&*mantissa, |dot| &mantissa[..dot]);
+                let left_pad = self
+                    .max_dig_before_dec
+                    .saturating_sub(self.number_width(integer));
+                let written_len = left_pad + self.number_width(&mantissa) + exponent.len();
+                buffer
+                    .spaces(left_pad)
+                    .add(&mantissa)
+                    .add(exponent)
+                    .add(comma_before_pad_type)
+                    .spaces(self.composite_value_length.saturating_sub(written_len));
+                return;
+            }
+        }
+
         if self.number_list_alignment == NumberListAlignment::Normalize {
-            let parsed_val: f64 = item.value.parse().unwrap_or(f64::NAN);
-            let reformatted = format!("{:.*}", self.max_dig_after_dec, parsed_val);
+            let Some(reformatted) = self.normalized_text(item) else {
+                buffer.add(&item.value).add(comma_before_pad_type).spaces(
+                    self.composite_value_length
+                        .saturating_sub(item.value_length),
+                );
+                return;
+            };
             buffer
-                .spaces(self.composite_value_length - reformatted.len())
+                .spaces(
+                    self.composite_value_length
+                        .saturating_sub(self.number_width(&reformatted)),
+                )
                 .add(&reformatted)
                 .add(comma_before_pad_type);
             return;
@@ -125,7 +397,9 @@ impl TableTemplate {
 
         let index_of_dot = dot_or_e_index(&item.value);
         let (left_pad, right_pad) = if let Some(dot) = index_of_dot {
-            let left_pad = self.max_dig_before_dec.saturating_sub(dot);
+            let left_pad = self
+                .max_dig_before_dec
+                .saturating_sub(self.number_width(&item.value[..dot]));
             let right_pad = self
                 .composite_value_length
                 .saturating_sub(left_pad + item.value_length);
@@ -145,6 +419,41 @@ impl TableTemplate {
             .spaces(right_pad);
     }
 
+    /// `mantissa`, the part of a number before its exponent, with zeros added
+    /// to its fraction so it has as many decimal places as the column's
+    /// longest. The value doesn't change, and the exponents line up.
+    fn zero_extended_mantissa(&self, mantissa: &str) -> String {
+        let places = mantissa.find('.').map(|dot| mantissa.len() - dot - 1);
+        let zeros = self.max_dig_after_dec.saturating_sub(places.unwrap_or(0));
+        let mut extended = mantissa.to_string();
+        if zeros > 0 && places.is_none() {
+            extended.push('.');
+        }
+        extended.push_str(&"0".repeat(zeros));
+        extended
+    }
+
+    /// The text the number `item` is written as when it's normalized, or
+    /// None if it's written as it is.
+    pub fn normalized_text(&self, item: &JsonItem) -> Option<String> {
+        if self.number_list_alignment != NumberListAlignment::Normalize
+            || item.item_type != JsonItemType::Number
+        {
+            return None;
+        }
+        let normalized = self.normalized_number(&item.value)?;
+        let mut reformatted =
+            round_decimal_text(&normalized, self.max_dig_after_dec, self.decimal_rounding);
+        let decimals = reformatted
+            .find('.')
+            .map_or(0, |dot| reformatted.len() - dot - 1);
+        if decimals == 0 && self.max_dig_after_dec > 0 {
+            reformatted.push('.');
+        }
+        reformatted.push_str(&"0".repeat(self.max_dig_after_dec - decimals));
+        Some(reformatted)
+    }
+
     pub fn atomic_item_size(&self) -> usize {
         self.name_length
             + self.pads.colon_len()
@@ -157,7 +466,7 @@ impl TableTemplate {
             + self.max_atomic_value_length
             + self.postfix_comment_length
             + if self.postfix_comment_length > 0 {
-                self.pads.comment_len()
+                self.pads.postfix_comment_len()
             } else {
                 0
             }
@@ -172,18 +481,83 @@ impl TableTemplate {
             return;
         }
 
+        let string_class = match (&self.string_classifier, row_segment.item_type) {
+            (Some(StringClassifier(classify)), JsonItemType::String) => {
+                let value = serde_json::from_str::<String>(&row_segment.value)
+                    .unwrap_or_else(|_| row_segment.value.clone());
+                let class = classify(&value);
+                self.string_class = match self.string_class {
+                    Some(seen) if seen != class => Some(None),
+                    Some(seen) => Some(seen),
+                    None => Some(class),
+                };
+                class
+            }
+            _ => None,
+        };
+
         let row_table_type = match row_segment.item_type {
             JsonItemType::Null => TableColumnType::Unknown,
             JsonItemType::Number => TableColumnType::Number,
+            JsonItemType::True | JsonItemType::False => TableColumnType::Boolean,
             JsonItemType::Array => TableColumnType::Array,
             JsonItemType::Object => TableColumnType::Object,
+            JsonItemType::String if string_class == Some(CellAlignment::Number) => {
+                TableColumnType::Number
+            }
+            JsonItemType::String
+                if self.numeric_strings && is_numeric_string(&row_segment.value) =>
+            {
+                TableColumnType::Number
+            }
+            JsonItemType::String
+                if self.datetime_strings && is_iso_datetime(&row_segment.value) =>
+            {
+                TableColumnType::DateTime
+            }
             _ => TableColumnType::Simple,
         };
 
         if self.column_type == TableColumnType::Unknown {
             self.column_type = row_table_type;
         } else if row_table_type != TableColumnType::Unknown && self.column_type != row_table_type {
-            self.column_type = TableColumnType::Mixed;
+            // Booleans among strings are padded like strings.
+            let is_simple = |column_type| {
+                matches!(
+                    column_type,
+                    TableColumnType::Simple | TableColumnType::Boolean | TableColumnType::DateTime
+                )
+            };
+            self.column_type = if is_simple(self.column_type) && is_simple(row_table_type) {
+                TableColumnType::Simple
+            } else {
+                TableColumnType::Mixed
+            };
+        }
+
+        if self.unit_strings {
+            match (row_segment.item_type, unit_number_len(&row_segment.value)) {
+                (JsonItemType::String, Some(number_len)) => {
+                    self.max_unit_number_len = self.max_unit_number_len.max(number_len);
+                    self.max_unit_suffix_len = self
+                        .max_unit_suffix_len
+                        .max(row_segment.value_length.saturating_sub(number_len));
+                }
+                (JsonItemType::String | JsonItemType::True | JsonItemType::False, _) => {
+                    self.has_unitless_values = true;
+                }
+                _ => {}
+            }
+        }
+
+        if row_table_type == TableColumnType::Number {
+            if row_segment.item_type == JsonItemType::Number && is_integer(&row_segment.value) {
+                let digits = row_segment.value.trim_start_matches('-');
+                self.max_integer_len = self.max_integer_len.max(digits.len());
+                self.has_negative_integers |= digits.len() < row_segment.value.len();
+            } else {
+                self.has_non_integers = true;
+            }
         }
 
         if row_segment.item_type == JsonItemType::Null {
@@ -200,6 +574,7 @@ impl TableTemplate {
         self.name_length = self.name_length.max(row_segment.name_length);
         self.name_minimum = self.name_minimum.min(row_segment.name_length);
         self.max_value_length = self.max_value_length.max(row_segment.value_length);
+        self.min_value_length = self.min_value_length.min(row_segment.value_length);
         self.middle_comment_length = self
             .middle_comment_length
             .max(row_segment.middle_comment_length);
@@ -228,13 +603,27 @@ impl TableTemplate {
             return;
         }
 
+        if row_segment.item_type == JsonItemType::Array {
+            let length = row_segment
+                .children
+                .iter()
+                .filter(|child| {
+                    !matches!(
+                        child.item_type,
+                        JsonItemType::BlankLine
+                            | JsonItemType::BlockComment
+                            | JsonItemType::LineComment
+                    )
+                })
+                .count();
+            self.has_uneven_arrays |= self.array_length.is_some_and(|first| first != length);
+            self.array_length.get_or_insert(length);
+        }
+
         if self.column_type == TableColumnType::Array && recursive {
             for (i, child) in row_segment.children.iter().enumerate() {
                 if self.children.len() <= i {
-                    self.children.push(TableTemplate::new(
-                        self.pads.clone(),
-                        self.number_list_alignment,
-                    ));
+                    self.children.push(self.new_child());
                 }
                 self.children[i].measure_row_segment(child, true);
             }
@@ -244,6 +633,7 @@ impl TableTemplate {
                 return;
             }
 
+            self.object_row_count += 1;
             for row_child in &row_segment.children {
                 let mut idx = None;
                 for (i, child) in self.children.iter().enumerate() {
@@ -253,14 +643,34 @@ impl TableTemplate {
                     }
                 }
 
-                if let Some(index) = idx {
-                    self.children[index].measure_row_segment(row_child, true);
-                } else {
-                    let mut sub_template =
-                        TableTemplate::new(self.pads.clone(), self.number_list_alignment);
+                let index = idx.unwrap_or_else(|| {
+                    let mut sub_template = self.new_child();
                     sub_template.location_in_parent = Some(row_child.name.clone());
-                    sub_template.measure_row_segment(row_child, true);
+                    if self.fill_missing_members {
+                        // Earlier rows get a null in this column.
+                        let filler = null_member(row_child.name_length, &self.pads);
+                        for _ in 1..self.object_row_count {
+                            sub_template.measure_row_segment(&filler, true);
+                        }
+                    }
                     self.children.push(sub_template);
+                    self.children.len() - 1
+                });
+
+                // A null written as a blank cell places its column, but
+                // takes no room in it.
+                if !(self.blank_null_members && row_child.item_type == JsonItemType::Null) {
+                    self.children[index].measure_row_segment(row_child, true);
+                }
+            }
+
+            if self.fill_missing_members {
+                for child in &mut self.children {
+                    let name = child.location_in_parent.as_deref().unwrap_or_default();
+                    if !row_segment.children.iter().any(|ch| ch.name == name) {
+                        let filler = null_member(child.name_length, &self.pads);
+                        child.measure_row_segment(&filler, true);
+                    }
                 }
             }
         }
@@ -276,42 +686,95 @@ impl TableTemplate {
 
         let mut normalized_str = row_segment.value.clone();
         if self.number_list_alignment == NumberListAlignment::Normalize {
-            let parsed_val: f64 = normalized_str.parse().unwrap_or(f64::NAN);
-            normalized_str = parsed_val.to_string();
-
-            let can_normalize = parsed_val.is_finite()
-                && normalized_str.len() <= 16
-                && !normalized_str.contains('e')
-                && (parsed_val != 0.0 || is_truly_zero(&row_segment.value));
-            if !can_normalize {
-                self.number_list_alignment = NumberListAlignment::Left;
-                return;
+            match self.normalized_number(&row_segment.value) {
+                Some(normalized) => normalized_str = normalized,
+                None if self.normalize_fallback == NormalizeFallback::KeepText => {
+                    self.max_kept_text_len = self.max_kept_text_len.max(row_segment.value_length);
+                    return;
+                }
+                None => {
+                    self.number_list_alignment = NumberListAlignment::Left;
+                    return;
+                }
             }
+            if let Some(places) = self.max_decimal_places {
+                normalized_str = round_decimal_text(&normalized_str, places, self.decimal_rounding);
+            }
+        }
+        if self.number_list_alignment == NumberListAlignment::Exponent {
+            let (mantissa, exponent) = split_exponent(&row_segment.value);
+            self.max_exponent_len = self.max_exponent_len.max(exponent.len());
+            normalized_str = mantissa.to_string();
         }
 
         let index_of_dot = dot_or_e_index(&normalized_str);
         let before_dec = match index_of_dot {
-            Some(idx) => idx,
-            None => normalized_str.len(),
+            Some(idx) => self.number_width(&normalized_str[..idx]),
+            None => self.number_width(&normalized_str),
         };
         let after_dec = match index_of_dot {
             Some(idx) => normalized_str.len().saturating_sub(idx + 1),
             None => 0,
         };
-        self.max_dig_before_dec = self.max_dig_before_dec.max(before_dec);
+        self.max_dig_before_dec = self
+            .max_dig_before_dec
+            .max(before_dec)
+            .max(self.min_integer_digits);
         self.max_dig_after_dec = self.max_dig_after_dec.max(after_dec);
     }
 
+    /// `value` as written under [`NumberListAlignment::Normalize`], before
+    /// any rounding, or None if it can't be normalized.
+    fn normalized_number(&self, value: &str) -> Option<String> {
+        let normalized = normalized_number(value)?;
+        if self.unsigned_zero && normalized == "-0" {
+            return Some("0".to_string());
+        }
+        Some(normalized)
+    }
+
+    /// Width of the number `text`, or of its integer part, as
+    /// `number_width` measures it. Strings aligned as numbers are measured
+    /// as they are.
+    fn number_width(&self, text: &str) -> usize {
+        match &self.number_width {
+            Some(NumberWidth(width)) if !text.starts_with('"') => width(text),
+            _ => text.len(),
+        }
+    }
+
+    fn new_child(&self) -> TableTemplate {
+        let mut child = TableTemplate::new(self.pads.clone(), self.number_list_alignment);
+        child.blank_null_members = self.blank_null_members;
+        child.numeric_strings = self.numeric_strings;
+        child.unit_strings = self.unit_strings;
+        child.fill_missing_members = self.fill_missing_members;
+        child.equal_array_lengths_only = self.equal_array_lengths_only;
+        child.datetime_strings = self.datetime_strings;
+        child.zero_pad_integers = self.zero_pad_integers;
+        child.string_classifier = self.string_classifier.clone();
+        child.number_width = self.number_width.clone();
+        child.max_decimal_places = self.max_decimal_places;
+        child.decimal_rounding = self.decimal_rounding;
+        child.unsigned_zero = self.unsigned_zero;
+        child.normalize_fallback = self.normalize_fallback;
+        child.min_integer_digits = self.min_integer_digits;
+        child
+    }
+
     fn prune_and_recompute(&mut self, max_allowed_complexity: usize) {
         let clear_children = max_allowed_complexity == 0
             || (!matches!(
                 self.column_type,
                 TableColumnType::Array | TableColumnType::Object
             ))
-            || self.row_count < 2;
+            || self.row_count < 2
+            || (self.equal_array_lengths_only && self.has_uneven_arrays);
         if clear_children {
             self.children.clear();
         }
+        // Columns that only ever held blank nulls have nothing to write.
+        self.children.retain(|child| child.row_count > 0);
 
         for child in &mut self.children {
             child.prune_and_recompute(max_allowed_complexity.saturating_sub(1));
@@ -324,7 +787,7 @@ impl TableTemplate {
             self.composite_value_length = total_child_len
                 + self
                     .pads
-                    .comma_len()
+                    .table_comma_len()
                     .saturating_mul(self.children.len().saturating_sub(1))
                 + self.pads.arr_start_len(self.pad_type)
                 + self.pads.arr_end_len(self.pad_type);
@@ -333,6 +796,10 @@ impl TableTemplate {
                     self.pads.literal_null_len() - self.composite_value_length;
                 self.composite_value_length = self.pads.literal_null_len();
             }
+        } else if self.is_unit_aligned() {
+            self.composite_value_length = self
+                .max_value_length
+                .max(self.max_unit_number_len + self.max_unit_suffix_len);
         } else {
             self.composite_value_length = self.max_value_length;
         }
@@ -351,7 +818,7 @@ impl TableTemplate {
             0
         }) + self.composite_value_length
             + if self.postfix_comment_length > 0 {
-                self.postfix_comment_length + self.pads.comment_len()
+                self.postfix_comment_length + self.pads.postfix_comment_len()
             } else {
                 0
             };
@@ -371,21 +838,347 @@ impl TableTemplate {
     }
 
     fn get_number_field_width(&self) -> usize {
+        let zero_padded_width = if self.zero_pad_integers && !self.has_non_integers {
+            usize::from(self.has_negative_integers) + self.max_integer_len
+        } else {
+            0
+        };
+        self.aligned_number_width()
+            .max(zero_padded_width)
+            .max(self.max_kept_text_len)
+    }
+
+    fn aligned_number_width(&self) -> usize {
         if matches!(
             self.number_list_alignment,
-            NumberListAlignment::Normalize | NumberListAlignment::Decimal
+            NumberListAlignment::Normalize
+                | NumberListAlignment::Decimal
+                | NumberListAlignment::Exponent
         ) {
             let raw_dec_len = if self.max_dig_after_dec > 0 { 1 } else { 0 };
-            return self.max_dig_before_dec + raw_dec_len + self.max_dig_after_dec;
+            return self.max_dig_before_dec
+                + raw_dec_len
+                + self.max_dig_after_dec
+                + self.max_exponent_len;
         }
         self.max_value_length
     }
 }
 
+/// `value`, a decimal number without an exponent, with at most `places`
+/// decimal places, the rest rounded off as `rounding` says.
+fn round_decimal_text(value: &str, places: usize, rounding: DecimalRounding) -> String {
+    let (sign, unsigned) = match value.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", value),
+    };
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if fraction.len() <= places {
+        return value.to_string();
+    }
+
+    let (kept, dropped) = fraction.split_at(places);
+    let round_up = match rounding {
+        DecimalRounding::Nearest => dropped.as_bytes()[0] >= b'5',
+        DecimalRounding::TowardZero => false,
+        DecimalRounding::AwayFromZero => dropped.bytes().any(|digit| digit != b'0'),
+    };
+
+    let mut digits: Vec<u8> = integer.bytes().chain(kept.bytes()).collect();
+    if round_up {
+        let mut carry = true;
+        for digit in digits.iter_mut().rev() {
+            if *digit == b'9' {
+                *digit = b'0';
+            } else {
+                *digit += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            digits.insert(0, b'1');
+        }
+    }
+
+    let digits = String::from_utf8(digits).unwrap_or_default();
+    let sign = if digits.bytes().all(|digit| digit == b'0') {
+        ""
+    } else {
+        sign
+    };
+    let (integer, fraction) = digits.split_at(digits.len() - places);
+    if places == 0 {
+        format!("{}{}", sign, integer)
+    } else {
+        format!("{}{}.{}", sign, integer, fraction)
+    }
+}
+
+/// Whether `item` is, or contains, a value longer than `max_width`.
+pub fn has_value_wider_than(item: &JsonItem, max_width: usize) -> bool {
+    match item.item_type {
+        JsonItemType::Array | JsonItemType::Object => item
+            .children
+            .iter()
+            .any(|child| has_value_wider_than(child, max_width)),
+        JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment => false,
+        _ => item.value_length > max_width,
+    }
+}
+
+/// Percentage of the object rows of `table_root` that have the most common
+/// set of keys among them: 100 if they all have the same keys.
+pub fn matching_key_set_percent(table_root: &JsonItem) -> usize {
+    let mut key_set_counts: std::collections::HashMap<Vec<&str>, usize> =
+        std::collections::HashMap::new();
+    let mut row_count = 0;
+    for row in &table_root.children {
+        if row.item_type != JsonItemType::Object {
+            continue;
+        }
+        let mut keys: Vec<&str> = row
+            .children
+            .iter()
+            .filter(|ch| !ch.name.is_empty())
+            .map(|ch| ch.name.as_str())
+            .collect();
+        keys.sort_unstable();
+        *key_set_counts.entry(keys).or_default() += 1;
+        row_count += 1;
+    }
+    match key_set_counts.values().max() {
+        Some(most_common) => most_common * 100 / row_count,
+        None => 100,
+    }
+}
+
+/// The children of `table_root` with its rows sorted by the value in
+/// `column`: a key for rows that are objects, a position for rows that are
+/// arrays. Comments and blank lines keep their places, and rows without the
+/// column come last in either direction.
+pub fn sorted_table_rows<'a>(
+    table_root: &'a JsonItem,
+    column: &str,
+    descending: bool,
+) -> Vec<&'a JsonItem> {
+    let is_trivia = |item: &JsonItem| {
+        matches!(
+            item.item_type,
+            JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+        )
+    };
+    let cell = |row: &'a JsonItem| -> Option<&'a JsonItem> {
+        let mut elements = row.children.iter().filter(|child| !is_trivia(child));
+        match row.item_type {
+            JsonItemType::Object => elements.find(|child| {
+                serde_json::from_str::<String>(&child.name).is_ok_and(|key| key == column)
+            }),
+            JsonItemType::Array => elements.nth(column.parse().ok()?),
+            _ => None,
+        }
+    };
+
+    let mut rows: Vec<&JsonItem> = table_root
+        .children
+        .iter()
+        .filter(|row| !is_trivia(row))
+        .collect();
+    rows.sort_by(|a, b| match (cell(a), cell(b)) {
+        (Some(a), Some(b)) if descending => compare_cells(b, a),
+        (Some(a), Some(b)) => compare_cells(a, b),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+
+    let mut sorted = rows.into_iter();
+    table_root
+        .children
+        .iter()
+        .map(|child| {
+            if is_trivia(child) {
+                child
+            } else {
+                sorted.next().unwrap_or(child)
+            }
+        })
+        .collect()
+}
+
+/// Orders table cells: nulls, then booleans, numbers, strings, arrays and
+/// objects. Numbers compare by value and strings by their unescaped text;
+/// arrays and objects compare equal.
+fn compare_cells(a: &JsonItem, b: &JsonItem) -> Ordering {
+    let rank = |item: &JsonItem| match item.item_type {
+        JsonItemType::Null => 0,
+        JsonItemType::False => 1,
+        JsonItemType::True => 2,
+        JsonItemType::Number => 3,
+        JsonItemType::String => 4,
+        JsonItemType::Array => 5,
+        _ => 6,
+    };
+    match (a.item_type, b.item_type) {
+        (JsonItemType::Number, JsonItemType::Number) => {
+            let a: f64 = a.value.parse().unwrap_or(f64::NAN);
+            let b: f64 = b.value.parse().unwrap_or(f64::NAN);
+            a.total_cmp(&b)
+        }
+        (JsonItemType::String, JsonItemType::String) => {
+            let text = |item: &JsonItem| {
+                serde_json::from_str::<String>(&item.value).unwrap_or_else(|_| item.value.clone())
+            };
+            text(a).cmp(&text(b))
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// A `null` member standing in for one a row lacks, for measuring.
+fn null_member(name_length: usize, pads: &PaddedFormattingTokens) -> JsonItem {
+    JsonItem {
+        item_type: JsonItemType::Null,
+        name_length,
+        value: "null".to_string(),
+        value_length: pads.literal_null_len(),
+        ..Default::default()
+    }
+}
+
+/// Whether `value`, a JSON string, holds an ISO 8601 date, optionally with a
+/// time (`T` or a space, `hh:mm`, optional seconds and fraction) and a `Z`
+/// or `±hh:mm` offset.
+fn is_iso_datetime(value: &str) -> bool {
+    let Some(content) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return false;
+    };
+    let bytes = content.as_bytes();
+    let digits = |range: std::ops::Range<usize>| {
+        bytes
+            .get(range)
+            .is_some_and(|part| part.iter().all(u8::is_ascii_digit))
+    };
+    let is_date = digits(0..4)
+        && bytes.get(4) == Some(&b'-')
+        && digits(5..7)
+        && bytes.get(7) == Some(&b'-')
+        && digits(8..10);
+    if !is_date {
+        return false;
+    }
+    if bytes.len() == 10 {
+        return true;
+    }
+
+    let is_time = matches!(bytes[10], b'T' | b' ')
+        && digits(11..13)
+        && bytes.get(13) == Some(&b':')
+        && digits(14..16);
+    if !is_time {
+        return false;
+    }
+    let mut rest = &content[16..];
+    if let Some(seconds) = rest.strip_prefix(':') {
+        let seconds_len = seconds.bytes().take_while(u8::is_ascii_digit).count();
+        if seconds_len != 2 {
+            return false;
+        }
+        rest = &seconds[2..];
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let fraction_len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if fraction_len == 0 {
+                return false;
+            }
+            rest = &fraction[fraction_len..];
+        }
+    }
+
+    match rest.as_bytes() {
+        [] | [b'Z'] => true,
+        [b'+' | b'-', h1, h2, b':', m1, m2] => {
+            [h1, h2, m1, m2].iter().all(|digit| digit.is_ascii_digit())
+        }
+        _ => false,
+    }
+}
+
+/// Whether `value`, a JSON string, holds just a number, with no escapes or
+/// whitespace.
+fn is_numeric_string(value: &str) -> bool {
+    let Some(content) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return false;
+    };
+    !content.contains(['\\', ' ']) && serde_json::from_str::<serde_json::Number>(content).is_ok()
+}
+
+/// Length of the number starting the JSON string `value`, counting the
+/// opening quote, or None if it doesn't start with one.
+fn unit_number_len(value: &str) -> Option<usize> {
+    let text = value.strip_prefix('"')?;
+    let digits = |start: usize| {
+        text[start..]
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(text.len() - start)
+    };
+    let sign_len = usize::from(text.starts_with('-'));
+    let integer_len = digits(sign_len);
+    if integer_len == 0 {
+        return None;
+    }
+    let mut number_len = sign_len + integer_len;
+    if text[number_len..].starts_with('.') {
+        let fraction_len = digits(number_len + 1);
+        if fraction_len > 0 {
+            number_len += 1 + fraction_len;
+        }
+    }
+    Some(1 + number_len)
+}
+
 fn dot_or_e_index(value: &str) -> Option<usize> {
+    // Strings aligned as numbers split where their leading number stops
+    // being an integer, so `"2 kg"` lines up with `"12.5 kg"`.
+    if let Some(text) = value.strip_prefix('"') {
+        let integer_len = text
+            .find(|ch: char| !(ch.is_ascii_digit() || ch == '-' || ch == '+'))
+            .unwrap_or(text.len());
+        return Some(1 + integer_len);
+    }
     value.find(['.', 'e', 'E'])
 }
 
+/// `value` written as a plain decimal number, with no exponent or
+/// insignificant zeros, or None if it can't be written that way exactly.
+/// Integers are kept as they are, however many digits they have; other
+/// numbers go through `f64`.
+fn normalized_number(value: &str) -> Option<String> {
+    if is_integer(value) {
+        return Some(value.to_string());
+    }
+
+    let parsed_val: f64 = value.parse().unwrap_or(f64::NAN);
+    let normalized = parsed_val.to_string();
+    let can_normalize = parsed_val.is_finite()
+        && normalized.len() <= 16
+        && !normalized.contains('e')
+        && (parsed_val != 0.0 || is_truly_zero(value));
+    can_normalize.then_some(normalized)
+}
+
+/// `value` split into its mantissa and its exponent, starting at the `e`,
+/// which is empty if it has none. Strings are never split.
+fn split_exponent(value: &str) -> (&str, &str) {
+    match value.find(['e', 'E']) {
+        Some(marker) if !value.starts_with('"') => value.split_at(marker),
+        _ => (value, ""),
+    }
+}
+
+/// Whether `value` is an integer without a fraction or exponent.
+fn is_integer(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    !digits.is_empty() && digits.bytes().all(|ch| ch.is_ascii_digit())
+}
+
 fn is_truly_zero(value: &str) -> bool {
     let mut chars = value.chars();
     if let Some('-') = chars.clone().next() {
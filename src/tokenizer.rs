@@ -1,20 +1,39 @@
 use crate::error::FracturedJsonError;
-use crate::model::{InputPosition, JsonToken, TokenType};
-
-const MAX_DOC_SIZE: usize = 2_000_000_000;
+use crate::model::{InputPosition, JsonToken, Span, TokenType};
+use crate::options::{ControlCharPolicy, FracturedJsonOptions};
 
 #[derive(Clone)]
 pub struct ScannerState {
     original_text: String,
     chars: Vec<char>,
     byte_indices: Vec<usize>,
+    max_doc_size: usize,
+    base_position: InputPosition,
+    base_byte: usize,
     pub current_position: InputPosition,
     pub token_position: InputPosition,
     pub non_whitespace_since_last_newline: bool,
 }
 
 impl ScannerState {
-    pub fn new(original_text: &str) -> Self {
+    pub fn new(original_text: &str, max_doc_size: usize) -> Self {
+        let origin = InputPosition {
+            index: 0,
+            row: 0,
+            column: 0,
+        };
+        Self::with_base(original_text, max_doc_size, origin, 0)
+    }
+
+    /// Creates a scanner for text that starts at `base_position` (and byte
+    /// offset `base_byte`) of a larger document. Token positions, spans and
+    /// errors are reported relative to the larger document.
+    pub fn with_base(
+        original_text: &str,
+        max_doc_size: usize,
+        base_position: InputPosition,
+        base_byte: usize,
+    ) -> Self {
         let mut chars: Vec<char> = Vec::new();
         let mut byte_indices: Vec<usize> = Vec::new();
         for (idx, ch) in original_text.char_indices() {
@@ -27,6 +46,9 @@ impl ScannerState {
             original_text: original_text.to_string(),
             chars,
             byte_indices,
+            max_doc_size,
+            base_position,
+            base_byte,
             current_position: InputPosition {
                 index: 0,
                 row: 0,
@@ -41,31 +63,47 @@ impl ScannerState {
         }
     }
 
-    pub fn advance(&mut self, is_whitespace: bool) {
-        if self.current_position.index >= MAX_DOC_SIZE {
-            panic!("Maximum document length exceeded");
-        }
+    pub fn advance(&mut self, is_whitespace: bool) -> Result<(), FracturedJsonError> {
+        self.check_doc_size()?;
         self.current_position.index += 1;
         self.current_position.column += 1;
         if !is_whitespace {
             self.non_whitespace_since_last_newline = true;
         }
+        Ok(())
     }
 
-    pub fn new_line(&mut self) {
-        if self.current_position.index >= MAX_DOC_SIZE {
-            panic!("Maximum document length exceeded");
-        }
+    pub fn new_line(&mut self) -> Result<(), FracturedJsonError> {
+        self.check_doc_size()?;
         self.current_position.index += 1;
         self.current_position.row += 1;
         self.current_position.column = 0;
         self.non_whitespace_since_last_newline = false;
+        Ok(())
+    }
+
+    fn check_doc_size(&self) -> Result<(), FracturedJsonError> {
+        if self.base_position.index + self.current_position.index >= self.max_doc_size {
+            return Err(self.error("Maximum document length exceeded"));
+        }
+        Ok(())
     }
 
     pub fn set_token_start(&mut self) {
         self.token_position = self.current_position;
     }
 
+    /// Marks the start of the current line as the token start. Blank lines
+    /// use this since a preceding line comment may have consumed the newline
+    /// that would otherwise have reset the token start.
+    pub fn set_token_start_to_line_start(&mut self) {
+        self.token_position = InputPosition {
+            index: self.current_position.index - self.current_position.column,
+            row: self.current_position.row,
+            column: 0,
+        };
+    }
+
     pub fn make_token_from_buffer(&self, token_type: TokenType, trim_end: bool) -> JsonToken {
         let start = self.byte_indices[self.token_position.index];
         let end = self.byte_indices[self.current_position.index];
@@ -73,18 +111,28 @@ impl ScannerState {
         if trim_end {
             substring = substring.trim_end().to_string();
         }
+        let span = Span {
+            start: self.base_byte + start,
+            end: self.base_byte + start + substring.len(),
+        };
         JsonToken {
             token_type,
             text: substring,
-            input_position: self.token_position,
+            input_position: self.absolute(self.token_position),
+            span,
         }
     }
 
+    /// Makes a token whose source ends with the current character.
     pub fn make_token(&self, token_type: TokenType, text: &str) -> JsonToken {
         JsonToken {
             token_type,
             text: text.to_string(),
-            input_position: self.token_position,
+            input_position: self.absolute(self.token_position),
+            span: Span {
+                start: self.base_byte + self.byte_indices[self.token_position.index],
+                end: self.base_byte + self.byte_indices[self.current_position.index + 1],
+            },
         }
     }
 
@@ -101,18 +149,59 @@ impl ScannerState {
     }
 
     pub fn error(&self, message: &str) -> FracturedJsonError {
-        FracturedJsonError::new(message, Some(self.current_position))
+        self.error_at(message, self.current_position)
+    }
+
+    pub fn error_at(&self, message: &str, position: InputPosition) -> FracturedJsonError {
+        FracturedJsonError::new(message, Some(self.absolute(position)))
+    }
+
+    fn absolute(&self, position: InputPosition) -> InputPosition {
+        let column = if position.row == 0 {
+            self.base_position.column + position.column
+        } else {
+            position.column
+        };
+        InputPosition {
+            index: self.base_position.index + position.index,
+            row: self.base_position.row + position.row,
+            column,
+        }
     }
 }
 
 pub struct TokenGenerator {
     state: ScannerState,
+    control_char_policy: ControlCharPolicy,
+    strict_rfc8259: bool,
 }
 
 impl TokenGenerator {
-    pub fn new(input_json: &str) -> Self {
+    pub fn new(input_json: &str, options: &FracturedJsonOptions) -> Self {
+        Self {
+            state: ScannerState::new(input_json, options.max_document_size),
+            control_char_policy: options.control_char_policy,
+            strict_rfc8259: options.strict_rfc8259,
+        }
+    }
+
+    /// Creates a generator for a fragment of a larger document that starts at
+    /// `base_position` and byte offset `base_byte`.
+    pub fn with_base(
+        fragment: &str,
+        options: &FracturedJsonOptions,
+        base_position: InputPosition,
+        base_byte: usize,
+    ) -> Self {
         Self {
-            state: ScannerState::new(input_json),
+            state: ScannerState::with_base(
+                fragment,
+                options.max_document_size,
+                base_position,
+                base_byte,
+            ),
+            control_char_policy: options.control_char_policy,
+            strict_rfc8259: options.strict_rfc8259,
         }
     }
 }
@@ -129,15 +218,20 @@ impl Iterator for TokenGenerator {
             let ch = self.state.current()?;
             match ch {
                 ' ' | '\t' | '\r' => {
-                    self.state.advance(true);
+                    if let Err(err) = self.state.advance(true) {
+                        return Some(Err(err));
+                    }
                 }
                 '\n' => {
                     let token = if !self.state.non_whitespace_since_last_newline {
+                        self.state.set_token_start_to_line_start();
                         Some(self.state.make_token(TokenType::BlankLine, "\n"))
                     } else {
                         None
                     };
-                    self.state.new_line();
+                    if let Err(err) = self.state.new_line() {
+                        return Some(Err(err));
+                    }
                     self.state.set_token_start();
                     if let Some(token) = token {
                         return Some(Ok(token));
@@ -177,7 +271,13 @@ impl Iterator for TokenGenerator {
                 'f' => return Some(process_keyword(&mut self.state, "false", TokenType::False)),
                 'n' => return Some(process_keyword(&mut self.state, "null", TokenType::Null)),
                 '/' => return Some(process_comment(&mut self.state)),
-                '"' => return Some(process_string(&mut self.state)),
+                '"' => {
+                    return Some(process_string(
+                        &mut self.state,
+                        self.control_char_policy,
+                        self.strict_rfc8259,
+                    ))
+                }
                 '-' => return Some(process_number(&mut self.state)),
                 _ => {
                     if !is_digit(ch) {
@@ -197,7 +297,7 @@ fn process_single_char(
 ) -> Result<JsonToken, FracturedJsonError> {
     state.set_token_start();
     let token = state.make_token(token_type, symbol);
-    state.advance(false);
+    state.advance(false)?;
     Ok(token)
 }
 
@@ -213,7 +313,7 @@ fn process_keyword(
         if state.at_end() {
             return Err(state.error("Unexpected end of input while processing keyword"));
         }
-        state.advance(false);
+        state.advance(false)?;
         if state.at_end() {
             return Err(state.error("Unexpected end of input while processing keyword"));
         }
@@ -224,7 +324,7 @@ fn process_keyword(
     }
 
     let token = state.make_token(token_type, keyword);
-    state.advance(false);
+    state.advance(false)?;
     Ok(token)
 }
 
@@ -235,7 +335,7 @@ fn process_comment(state: &mut ScannerState) -> Result<JsonToken, FracturedJsonE
         return Err(state.error("Unexpected end of input while processing comment"));
     }
 
-    state.advance(false);
+    state.advance(false)?;
     let mut is_block_comment = false;
     match state.current() {
         Some('*') => is_block_comment = true,
@@ -243,7 +343,7 @@ fn process_comment(state: &mut ScannerState) -> Result<JsonToken, FracturedJsonE
         _ => return Err(state.error("Bad character for start of comment")),
     }
 
-    state.advance(false);
+    state.advance(false)?;
     let mut last_char_was_asterisk = false;
     loop {
         if state.at_end() {
@@ -255,14 +355,14 @@ fn process_comment(state: &mut ScannerState) -> Result<JsonToken, FracturedJsonE
 
         let ch = state.current().unwrap();
         if ch == '\n' {
-            state.new_line();
+            state.new_line()?;
             if !is_block_comment {
                 return Ok(state.make_token_from_buffer(TokenType::LineComment, true));
             }
             continue;
         }
 
-        state.advance(false);
+        state.advance(false)?;
         if ch == '/' && last_char_was_asterisk {
             return Ok(state.make_token_from_buffer(TokenType::BlockComment, false));
         }
@@ -270,12 +370,20 @@ fn process_comment(state: &mut ScannerState) -> Result<JsonToken, FracturedJsonE
     }
 }
 
-fn process_string(state: &mut ScannerState) -> Result<JsonToken, FracturedJsonError> {
+fn process_string(
+    state: &mut ScannerState,
+    control_char_policy: ControlCharPolicy,
+    strict_rfc8259: bool,
+) -> Result<JsonToken, FracturedJsonError> {
     state.set_token_start();
-    state.advance(false);
+    state.advance(false)?;
 
     let mut last_char_began_escape = false;
+    let mut saw_control_char = false;
     let mut expected_hex_count = 0usize;
+    let mut escape_position = state.token_position;
+    let mut code_unit = 0u32;
+    let mut unpaired_high_surrogate: Option<InputPosition> = None;
     loop {
         if state.at_end() {
             return Err(state.error("Unexpected end of input while processing string"));
@@ -287,8 +395,22 @@ fn process_string(state: &mut ScannerState) -> Result<JsonToken, FracturedJsonEr
             if !is_hex(ch) {
                 return Err(state.error("Bad unicode escape in string"));
             }
+            code_unit = code_unit * 16 + ch.to_digit(16).unwrap();
             expected_hex_count -= 1;
-            state.advance(false);
+            if expected_hex_count == 0 && strict_rfc8259 {
+                match (unpaired_high_surrogate.take(), code_unit) {
+                    (Some(_), 0xDC00..=0xDFFF) => {}
+                    (Some(high_position), _) => {
+                        return Err(state.error_at("Unpaired surrogate in string", high_position));
+                    }
+                    (None, 0xD800..=0xDBFF) => unpaired_high_surrogate = Some(escape_position),
+                    (None, 0xDC00..=0xDFFF) => {
+                        return Err(state.error_at("Unpaired surrogate in string", escape_position));
+                    }
+                    (None, _) => {}
+                }
+            }
+            state.advance(false)?;
             continue;
         }
 
@@ -298,22 +420,44 @@ fn process_string(state: &mut ScannerState) -> Result<JsonToken, FracturedJsonEr
             }
             if ch == 'u' {
                 expected_hex_count = 4;
+                code_unit = 0;
+            } else if let Some(high_position) = unpaired_high_surrogate {
+                return Err(state.error_at("Unpaired surrogate in string", high_position));
             }
             last_char_began_escape = false;
-            state.advance(false);
+            state.advance(false)?;
             continue;
         }
 
+        if ch != '\\' {
+            if let Some(high_position) = unpaired_high_surrogate {
+                return Err(state.error_at("Unpaired surrogate in string", high_position));
+            }
+        }
+
         if is_control(ch) {
-            return Err(state.error("Control characters are not allowed in strings"));
+            if control_char_policy == ControlCharPolicy::TreatAsError {
+                return Err(state.error("Control characters are not allowed in strings"));
+            }
+            saw_control_char = true;
+            if ch == '\n' {
+                state.new_line()?;
+                continue;
+            }
         }
 
-        state.advance(false);
+        let char_position = state.current_position;
+        state.advance(false)?;
         if ch == '"' {
-            return Ok(state.make_token_from_buffer(TokenType::String, false));
+            let mut token = state.make_token_from_buffer(TokenType::String, false);
+            if saw_control_char && control_char_policy == ControlCharPolicy::Escape {
+                token.text = escape_control_chars(&token.text);
+            }
+            return Ok(token);
         }
         if ch == '\\' {
             last_char_began_escape = true;
+            escape_position = char_position;
         }
     }
 }
@@ -421,7 +565,7 @@ fn process_number(state: &mut ScannerState) -> Result<JsonToken, FracturedJsonEr
             return Ok(state.make_token_from_buffer(TokenType::Number, false));
         }
 
-        state.advance(false);
+        state.advance(false)?;
     }
 }
 
@@ -437,6 +581,22 @@ fn is_legal_after_backslash(ch: char) -> bool {
     matches!(ch, '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u')
 }
 
+fn escape_control_chars(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\u{08}' => escaped.push_str("\\b"),
+            '\u{0C}' => escaped.push_str("\\f"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ if is_control(ch) => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
 fn is_control(ch: char) -> bool {
     let code = ch as u32;
     (code <= 0x1F) || (code == 0x7F) || (0x80..=0x9F).contains(&code)
@@ -465,7 +625,7 @@ enum CharHandling {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{InputPosition, JsonToken, TokenType};
+    use crate::model::{InputPosition, JsonToken, Span, TokenType};
 
     #[test]
     fn echoes_tokens() {
@@ -511,16 +671,35 @@ mod tests {
             };
 
             let results: Vec<JsonToken> =
-                match TokenGenerator::new(input).collect::<Result<Vec<_>, _>>() {
+                match TokenGenerator::new(input, &FracturedJsonOptions::default())
+                    .collect::<Result<Vec<_>, _>>()
+                {
                     Ok(tokens) => tokens,
                     Err(err) => panic!("input={} err={}", input, err),
                 };
             assert_eq!(results.len(), 1, "input={}", input);
             assert_eq!(results[0].text, possibly_trimmed);
             assert_eq!(results[0].token_type, token_type);
+            let span = results[0].span;
+            assert_eq!(&input[span.start..span.end], possibly_trimmed);
         }
     }
 
+    #[test]
+    fn spans_are_byte_offsets() {
+        let input = "[\"é\", /* ü */ null]";
+        let results: Vec<JsonToken> = TokenGenerator::new(input, &FracturedJsonOptions::default())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let texts: Vec<&str> = results
+            .iter()
+            .map(|token| &input[token.span.start..token.span.end])
+            .collect();
+        assert_eq!(texts, vec!["[", "\"é\"", ",", "/* ü */", "null", "]"]);
+        assert_eq!(results[4].input_position.index, 14);
+        assert_eq!(results[4].span.start, 16);
+    }
+
     #[test]
     fn correct_position_for_second_token() {
         let cases: Vec<(&str, usize, usize, usize)> = vec![
@@ -539,11 +718,14 @@ mod tests {
             ("/*1*/,", 5, 0, 5),
             ("/*1\n*/,", 6, 1, 2),
             ("\n\n", 1, 1, 0),
+            ("//co\n\n", 5, 1, 0),
         ];
 
         for (input, index, row, column) in cases {
             let results: Vec<JsonToken> =
-                match TokenGenerator::new(input).collect::<Result<Vec<_>, _>>() {
+                match TokenGenerator::new(input, &FracturedJsonOptions::default())
+                    .collect::<Result<Vec<_>, _>>()
+                {
                     Ok(tokens) => tokens,
                     Err(err) => panic!("input={} err={}", input, err),
                 };
@@ -580,7 +762,7 @@ mod tests {
 
         for input in cases {
             let result: Result<Vec<JsonToken>, FracturedJsonError> =
-                TokenGenerator::new(input).collect();
+                TokenGenerator::new(input, &FracturedJsonOptions::default()).collect();
             assert!(result.is_err(), "input={}", input);
             let err = result.err().unwrap();
             let pos = err.input_position.unwrap();
@@ -616,6 +798,7 @@ mod tests {
                     row: 0,
                     column: 0,
                 },
+                span: Span { start: 0, end: 1 },
             },
             JsonToken {
                 token_type: TokenType::LineComment,
@@ -625,6 +808,7 @@ mod tests {
                     row: 1,
                     column: 4,
                 },
+                span: Span { start: 34, end: 51 },
             },
             JsonToken {
                 token_type: TokenType::String,
@@ -634,6 +818,7 @@ mod tests {
                     row: 2,
                     column: 4,
                 },
+                span: Span { start: 64, end: 71 },
             },
             JsonToken {
                 token_type: TokenType::Colon,
@@ -643,6 +828,7 @@ mod tests {
                     row: 2,
                     column: 11,
                 },
+                span: Span { start: 71, end: 72 },
             },
             JsonToken {
                 token_type: TokenType::String,
@@ -652,6 +838,7 @@ mod tests {
                     row: 2,
                     column: 13,
                 },
+                span: Span { start: 73, end: 83 },
             },
             JsonToken {
                 token_type: TokenType::Comma,
@@ -661,6 +848,7 @@ mod tests {
                     row: 2,
                     column: 23,
                 },
+                span: Span { start: 83, end: 84 },
             },
             JsonToken {
                 token_type: TokenType::BlankLine,
@@ -670,6 +858,10 @@ mod tests {
                     row: 3,
                     column: 0,
                 },
+                span: Span {
+                    start: 90,
+                    end: 120,
+                },
             },
             JsonToken {
                 token_type: TokenType::BlockComment,
@@ -679,6 +871,10 @@ mod tests {
                     row: 4,
                     column: 4,
                 },
+                span: Span {
+                    start: 124,
+                    end: 167,
+                },
             },
             JsonToken {
                 token_type: TokenType::String,
@@ -688,6 +884,10 @@ mod tests {
                     row: 6,
                     column: 4,
                 },
+                span: Span {
+                    start: 184,
+                    end: 191,
+                },
             },
             JsonToken {
                 token_type: TokenType::Colon,
@@ -697,6 +897,10 @@ mod tests {
                     row: 6,
                     column: 11,
                 },
+                span: Span {
+                    start: 191,
+                    end: 192,
+                },
             },
             JsonToken {
                 token_type: TokenType::BeginArray,
@@ -706,6 +910,10 @@ mod tests {
                     row: 6,
                     column: 13,
                 },
+                span: Span {
+                    start: 193,
+                    end: 194,
+                },
             },
             JsonToken {
                 token_type: TokenType::Null,
@@ -715,6 +923,10 @@ mod tests {
                     row: 6,
                     column: 14,
                 },
+                span: Span {
+                    start: 194,
+                    end: 198,
+                },
             },
             JsonToken {
                 token_type: TokenType::Comma,
@@ -724,6 +936,10 @@ mod tests {
                     row: 6,
                     column: 18,
                 },
+                span: Span {
+                    start: 198,
+                    end: 199,
+                },
             },
             JsonToken {
                 token_type: TokenType::Number,
@@ -733,6 +949,10 @@ mod tests {
                     row: 6,
                     column: 20,
                 },
+                span: Span {
+                    start: 200,
+                    end: 204,
+                },
             },
             JsonToken {
                 token_type: TokenType::EndArray,
@@ -742,6 +962,10 @@ mod tests {
                     row: 6,
                     column: 24,
                 },
+                span: Span {
+                    start: 204,
+                    end: 205,
+                },
             },
             JsonToken {
                 token_type: TokenType::EndObject,
@@ -751,11 +975,17 @@ mod tests {
                     row: 7,
                     column: 0,
                 },
+                span: Span {
+                    start: 210,
+                    end: 211,
+                },
             },
         ];
 
         let results: Vec<JsonToken> =
-            match TokenGenerator::new(&input_string).collect::<Result<Vec<_>, _>>() {
+            match TokenGenerator::new(&input_string, &FracturedJsonOptions::default())
+                .collect::<Result<Vec<_>, _>>()
+            {
                 Ok(tokens) => tokens,
                 Err(err) => panic!("err={}", err),
             };
@@ -763,9 +993,94 @@ mod tests {
         assert_eq!(results, expected_tokens);
     }
 
+    #[test]
+    fn error_if_document_exceeds_max_size() {
+        let options = FracturedJsonOptions {
+            max_document_size: 5,
+            ..Default::default()
+        };
+        let tokens: Vec<JsonToken> = TokenGenerator::new("[1,2]", &options)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(tokens.len(), 5);
+
+        let result: Result<Vec<JsonToken>, FracturedJsonError> =
+            TokenGenerator::new("[1, 2]", &options).collect();
+        let err = result.unwrap_err();
+        assert_eq!(err.input_position.unwrap().index, 5);
+    }
+
+    #[test]
+    fn control_chars_in_strings_follow_policy() {
+        let input = "\"a\tb\nc\u{1}\"";
+        let result: Result<Vec<JsonToken>, FracturedJsonError> =
+            TokenGenerator::new(input, &FracturedJsonOptions::default()).collect();
+        assert!(result.is_err());
+
+        let escape_options = FracturedJsonOptions {
+            control_char_policy: ControlCharPolicy::Escape,
+            ..Default::default()
+        };
+        let tokens: Vec<JsonToken> = TokenGenerator::new(input, &escape_options)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].text, "\"a\\tb\\nc\\u0001\"");
+
+        let preserve_options = FracturedJsonOptions {
+            control_char_policy: ControlCharPolicy::Preserve,
+            ..Default::default()
+        };
+        let tokens: Vec<JsonToken> =
+            TokenGenerator::new(&format!("{},1", input), &preserve_options)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+        assert_eq!(tokens[0].text, input);
+        assert_eq!(tokens[1].input_position.row, 1);
+        assert_eq!(tokens[1].input_position.column, 3);
+    }
+
+    #[test]
+    fn strict_mode_rejects_unpaired_surrogates() {
+        let strict = FracturedJsonOptions {
+            strict_rfc8259: true,
+            ..Default::default()
+        };
+        let valid = vec![
+            "\"\\ud83d\\ude00\"",
+            "\"\\uD83D\\uDE00 x\"",
+            "\"\\u00e9\\ud7ff\\ue000\"",
+        ];
+        for input in valid {
+            let result: Result<Vec<JsonToken>, FracturedJsonError> =
+                TokenGenerator::new(input, &strict).collect();
+            assert!(result.is_ok(), "input={}", input);
+        }
+
+        // (input, index of the offending escape)
+        let invalid = vec![
+            ("\"\\ud83d\"", 1),
+            ("\"ab\\ud83dx\"", 3),
+            ("\"\\ud83d\\n\"", 1),
+            ("\"\\ud83d\\u0041\"", 1),
+            ("\"\\ude00\"", 1),
+            ("\"\\ud83d\\ude00\\ude00\"", 13),
+        ];
+        for (input, index) in invalid {
+            let result: Result<Vec<JsonToken>, FracturedJsonError> =
+                TokenGenerator::new(input, &strict).collect();
+            let err = result.expect_err(input);
+            assert_eq!(err.input_position.unwrap().index, index, "input={}", input);
+
+            let lenient: Result<Vec<JsonToken>, FracturedJsonError> =
+                TokenGenerator::new(input, &FracturedJsonOptions::default()).collect();
+            assert!(lenient.is_ok(), "input={}", input);
+        }
+    }
+
     #[test]
     fn empty_input_is_handled() {
-        let results: Vec<JsonToken> = TokenGenerator::new("")
+        let results: Vec<JsonToken> = TokenGenerator::new("", &FracturedJsonOptions::default())
             .collect::<Result<Vec<_>, _>>()
             .unwrap();
         assert_eq!(results.len(), 0);
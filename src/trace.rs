@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use crate::convert::escape_pointer_token;
+use crate::model::{JsonItem, JsonItemType};
+use crate::notation::same_number;
+use crate::table_info::TableInfo;
+use crate::table_template::TableTemplate;
+
+/// How an item was laid out, as recorded in a [`FormatEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatDecision {
+    /// An array or object written on a single line.
+    Inline,
+    /// An array written with several elements per line.
+    CompactMultiline,
+    /// An array or object whose elements are written as aligned table rows.
+    Table,
+    /// A row of a table.
+    TableRow,
+    /// An array or object with one element per line.
+    Expanded,
+    /// A string, number, boolean or null, including one inside a compact
+    /// multiline array.
+    Value,
+    /// A comment on a line of its own.
+    Comment,
+}
+
+/// One item written by [`Formatter::reformat_with_trace`](crate::Formatter::reformat_with_trace).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatEvent {
+    /// JSON Pointer of the item in the output document. Comments use the
+    /// pointer of the array or object they are in (`""` at the top level).
+    pub path: String,
+    /// Nesting depth of the item: 0 for top-level items.
+    pub depth: usize,
+    /// How the item was laid out.
+    pub decision: FormatDecision,
+    /// First output line (0-based) holding the item.
+    pub first_line: usize,
+    /// Last output line (0-based) holding the item.
+    pub last_line: usize,
+}
+
+/// A number written with a different value than it had in the input, as
+/// reported by [`Formatter::reformat_with_trace`](crate::Formatter::reformat_with_trace).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberChange {
+    /// JSON Pointer of the number in the output document.
+    pub path: String,
+    /// The number's text in the input.
+    pub original: String,
+    /// The number's text in the output, without padding.
+    pub written: String,
+}
+
+/// Formatted output together with the items each line came from.
+///
+/// Items written inside an inline container or a table row have no events
+/// of their own; their lines belong to the container or row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatTrace {
+    /// The formatted text, as [`Formatter::reformat`](crate::Formatter::reformat)
+    /// would return it.
+    pub output: String,
+    /// Every traced item, in document order (containers before their contents).
+    pub events: Vec<FormatEvent>,
+    /// For each output line, the paths of the innermost items on it: a single
+    /// path for most lines, several for a row of a compact multiline array.
+    pub line_paths: Vec<Vec<String>>,
+    /// Every number whose value was changed by rounding, precision lost
+    /// going through `f64`, or `number_format_func`, in document order.
+    /// Numbers only rewritten in another notation or padded aren't listed.
+    pub number_changes: Vec<NumberChange>,
+}
+
+/// Collects [`FormatEvent`]s while a document is formatted.
+#[derive(Debug, Default)]
+pub struct TraceRecorder {
+    /// Path and depth of every item, keyed by the item's address, which stays
+    /// fixed while the document is formatted.
+    locations: HashMap<usize, (String, usize)>,
+    events: Vec<FormatEvent>,
+    /// Each table with the output line it starts on.
+    tables: Vec<(usize, TableInfo)>,
+    /// Text of each number before the document's numbers were rewritten,
+    /// and as finally written, keyed by address.
+    original_numbers: HashMap<usize, String>,
+    written_numbers: HashMap<usize, String>,
+    /// Addresses of the numbers, in document order.
+    number_order: Vec<usize>,
+}
+
+impl TraceRecorder {
+    /// Works out the path of every item in `doc_model`. Must be called once
+    /// the document will no longer be modified or moved.
+    pub fn index(&mut self, doc_model: &[JsonItem]) {
+        for item in doc_model {
+            self.index_item(item, String::new(), 0);
+        }
+    }
+
+    fn index_item(&mut self, item: &JsonItem, path: String, depth: usize) {
+        let mut index = 0;
+        for child in &item.children {
+            let child_path = match child.item_type {
+                JsonItemType::BlankLine
+                | JsonItemType::BlockComment
+                | JsonItemType::LineComment => path.clone(),
+                _ if item.item_type == JsonItemType::Object => {
+                    let key = serde_json::from_str::<String>(&child.name)
+                        .unwrap_or_else(|_| child.name.clone());
+                    format!("{}/{}", path, escape_pointer_token(&key))
+                }
+                _ => {
+                    index += 1;
+                    format!("{}/{}", path, index - 1)
+                }
+            };
+            self.index_item(child, child_path, depth + 1);
+        }
+        let address = item as *const JsonItem as usize;
+        if item.item_type == JsonItemType::Number {
+            self.number_order.push(address);
+            self.written_numbers
+                .entry(address)
+                .or_insert_with(|| item.value.clone());
+        }
+        self.locations.insert(address, (path, depth));
+    }
+
+    /// Notes the text of every number in `doc_model`, before it's rewritten.
+    pub fn snapshot_numbers(&mut self, doc_model: &[JsonItem]) {
+        for item in doc_model {
+            if item.item_type == JsonItemType::Number {
+                self.original_numbers
+                    .insert(item as *const JsonItem as usize, item.value.clone());
+            }
+            self.snapshot_numbers(&item.children);
+        }
+    }
+
+    /// Records that the number `item` was written as `text`.
+    pub fn record_number(&mut self, item: &JsonItem, text: String) {
+        self.written_numbers
+            .insert(item as *const JsonItem as usize, text);
+    }
+
+    pub fn record(
+        &mut self,
+        item: &JsonItem,
+        decision: FormatDecision,
+        first_line: usize,
+        last_line: usize,
+    ) {
+        let Some((path, depth)) = self.locations.get(&(item as *const JsonItem as usize)) else {
+            return;
+        };
+        self.events.push(FormatEvent {
+            path: path.clone(),
+            depth: *depth,
+            decision,
+            first_line,
+            last_line,
+        });
+    }
+
+    /// Records that `item`, with `row_count` rows, was written as a table
+    /// laid out by `template`, starting on `first_line`.
+    pub fn record_table(
+        &mut self,
+        item: &JsonItem,
+        row_count: usize,
+        template: &TableTemplate,
+        first_line: usize,
+    ) {
+        let Some((path, _)) = self.locations.get(&(item as *const JsonItem as usize)) else {
+            return;
+        };
+        self.tables.push((
+            first_line,
+            TableInfo::new(path.clone(), row_count, template),
+        ));
+    }
+
+    /// Forgets the events and tables of items written on or after `line`,
+    /// which have been removed from the output.
+    pub fn discard_from(&mut self, line: usize) {
+        self.events.retain(|event| event.first_line < line);
+        self.tables.retain(|(first_line, _)| *first_line < line);
+    }
+
+    /// The tables recorded, in document order.
+    pub fn into_tables(self) -> Vec<TableInfo> {
+        self.tables.into_iter().map(|(_, table)| table).collect()
+    }
+
+    /// Whether the item at `path`, which has no children with events of
+    /// their own, is in the output: it has an event, or the nearest item
+    /// above it with one was written on a single line. Otherwise it was
+    /// left out under `max_output_lines`.
+    fn is_written(path: &str, decisions: &HashMap<&str, FormatDecision>) -> bool {
+        if decisions.contains_key(path) {
+            return true;
+        }
+        let mut ancestor = path;
+        while let Some((parent, _)) = ancestor.rsplit_once('/') {
+            ancestor = parent;
+            if let Some(decision) = decisions.get(ancestor) {
+                return matches!(decision, FormatDecision::Inline | FormatDecision::TableRow);
+            }
+        }
+        false
+    }
+
+    pub fn finish(mut self, output: String) -> FormatTrace {
+        // Containers are recorded after their contents, once their last line
+        // is known.
+        self.events
+            .sort_by_key(|event| (event.first_line, event.depth));
+
+        let line_count = output.lines().count();
+        let mut line_paths: Vec<Vec<String>> = vec![Vec::new(); line_count];
+        let mut line_depths: Vec<Option<usize>> = vec![None; line_count];
+        for event in &self.events {
+            if event.first_line >= line_count {
+                continue;
+            }
+            for line in event.first_line..=event.last_line.min(line_count - 1) {
+                match line_depths[line] {
+                    Some(depth) if depth > event.depth => continue,
+                    Some(depth) if depth == event.depth => {}
+                    _ => {
+                        line_paths[line].clear();
+                        line_depths[line] = Some(event.depth);
+                    }
+                }
+                line_paths[line].push(event.path.clone());
+            }
+        }
+
+        // Comments are recorded under the path of their container.
+        let decisions: HashMap<&str, FormatDecision> = self
+            .events
+            .iter()
+            .filter(|event| event.decision != FormatDecision::Comment)
+            .map(|event| (event.path.as_str(), event.decision))
+            .collect();
+        let mut number_changes = Vec::new();
+        for address in &self.number_order {
+            let (Some(original), Some(written), Some((path, _))) = (
+                self.original_numbers.get(address),
+                self.written_numbers.get(address),
+                self.locations.get(address),
+            ) else {
+                continue;
+            };
+            if !same_number(original, written) && Self::is_written(path, &decisions) {
+                number_changes.push(NumberChange {
+                    path: path.clone(),
+                    original: original.clone(),
+                    written: written.clone(),
+                });
+            }
+        }
+
+        FormatTrace {
+            output,
+            events: self.events,
+            line_paths,
+            number_changes,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::model::{JsonItem, JsonItemType};
+
+/// Removes the insignificant trailing zeros from the fraction of every
+/// number in `doc_model`, along with the decimal point if nothing is left
+/// after it.
+pub fn apply_trim_trailing_zeros(doc_model: &mut [JsonItem]) {
+    for item in doc_model.iter_mut() {
+        if item.item_type == JsonItemType::Number {
+            if let Some(trimmed) = trim_number(&item.value) {
+                item.value = trimmed;
+            }
+        }
+        apply_trim_trailing_zeros(&mut item.children);
+    }
+}
+
+/// Returns `number` without trailing zeros in its fraction, or None if it
+/// has none to remove.
+fn trim_number(number: &str) -> Option<String> {
+    let dot = number.find('.')?;
+    let fraction_end = number.find(['e', 'E']).unwrap_or(number.len());
+    let fraction = &number[dot + 1..fraction_end];
+    let kept = fraction.trim_end_matches('0');
+    if kept.len() == fraction.len() {
+        return None;
+    }
+
+    let mantissa_end = if kept.is_empty() {
+        dot
+    } else {
+        dot + 1 + kept.len()
+    };
+    Some(format!(
+        "{}{}",
+        &number[..mantissa_end],
+        &number[fraction_end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_zeros_are_trimmed() {
+        assert_eq!(trim_number("2.10").as_deref(), Some("2.1"));
+        assert_eq!(trim_number("-3.000").as_deref(), Some("-3"));
+        assert_eq!(trim_number("1.50E+3").as_deref(), Some("1.5E+3"));
+        assert_eq!(trim_number("100"), None);
+        assert_eq!(trim_number("0.25"), None);
+    }
+}
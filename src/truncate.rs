@@ -0,0 +1,106 @@
+use crate::model::{JsonItem, JsonItemType};
+
+/// Marks the end of a shortened string.
+const ELLIPSIS: char = '\u{2026}';
+
+/// Shortens every string value in `doc_model` longer than `max_length`
+/// characters to `max_length`, the last of which is an ellipsis. A limit of
+/// 0 empties every string, as the ellipsis alone would exceed it.
+pub fn apply_string_truncation(doc_model: &mut [JsonItem], max_length: usize) {
+    for item in doc_model.iter_mut() {
+        if item.item_type == JsonItemType::String {
+            if let Some(truncated) = truncate_string(&item.value, max_length) {
+                item.value = truncated;
+            }
+        }
+        apply_string_truncation(&mut item.children, max_length);
+    }
+}
+
+/// Shortens a JSON string literal, counting each escape sequence (a
+/// surrogate pair included) as one character and never splitting one.
+/// Returns None if the string is short enough already.
+fn truncate_string(literal: &str, max_length: usize) -> Option<String> {
+    let content = literal.strip_prefix('"')?.strip_suffix('"')?;
+    if max_length == 0 {
+        return (!content.is_empty()).then(|| "\"\"".to_string());
+    }
+
+    let mut char_count = 0;
+    let mut kept_len = None;
+    let mut rest = content;
+    while !rest.is_empty() {
+        if char_count == max_length - 1 && kept_len.is_none() {
+            kept_len = Some(content.len() - rest.len());
+        }
+        rest = &rest[char_len(rest)..];
+        char_count += 1;
+        if char_count > max_length {
+            let kept = &content[..kept_len.unwrap_or(0)];
+            return Some(format!("\"{}{}\"", kept, ELLIPSIS));
+        }
+    }
+    None
+}
+
+/// Length in bytes of the character or escape sequence at the start of
+/// `text`.
+fn char_len(text: &str) -> usize {
+    let is_unicode_escape = |text: &str| {
+        text.len() >= 6
+            && text.starts_with("\\u")
+            && text.as_bytes()[2..6].iter().all(u8::is_ascii_hexdigit)
+    };
+    let is_high_surrogate = |text: &str| {
+        matches!(text.as_bytes()[2].to_ascii_lowercase(), b'd')
+            && matches!(text.as_bytes()[3].to_ascii_lowercase(), b'8'..=b'9' | b'a'..=b'b')
+    };
+
+    if is_unicode_escape(text) {
+        if is_high_surrogate(text) && is_unicode_escape(&text[6..]) {
+            return 12;
+        }
+        return 6;
+    }
+    match text.strip_prefix('\\') {
+        Some(escaped) => 1 + escaped.chars().next().map_or(0, char::len_utf8),
+        None => text.chars().next().map_or(0, char::len_utf8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_strings_are_cut_with_an_ellipsis() {
+        assert_eq!(
+            truncate_string("\"abcdef\"", 4).as_deref(),
+            Some("\"abc\u{2026}\"")
+        );
+        assert_eq!(truncate_string("\"abcd\"", 4), None);
+        assert_eq!(truncate_string("\"\"", 0), None);
+        assert_eq!(truncate_string("\"ab\"", 0).as_deref(), Some("\"\""));
+        assert_eq!(
+            truncate_string("\"ab\"", 1).as_deref(),
+            Some("\"\u{2026}\"")
+        );
+    }
+
+    #[test]
+    fn escapes_count_as_one_character() {
+        assert_eq!(truncate_string(r#""a\nb\"céd""#, 7), None);
+        assert_eq!(
+            truncate_string(r#""a\nb\"céd""#, 5).as_deref(),
+            Some("\"a\\nb\\\"\u{2026}\"")
+        );
+        assert_eq!(
+            truncate_string(r#""\ud83d\ude00\ud83d\ude00xy""#, 3).as_deref(),
+            Some("\"\\ud83d\\ude00\\ud83d\\ude00\u{2026}\"")
+        );
+        assert_eq!(
+            truncate_string("\"日本語です\"", 3).as_deref(),
+            Some("\"日本\u{2026}\"")
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use crate::model::{JsonItem, JsonItemType};
+
+/// Target language for [`Formatter::type_skeleton`](crate::Formatter::type_skeleton).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkeletonLanguage {
+    /// Rust structs deriving `serde::Serialize`/`serde::Deserialize`.
+    Rust,
+    /// TypeScript interfaces.
+    TypeScript,
+}
+
+#[derive(Debug, Clone)]
+struct InferredType {
+    kind: InferredKind,
+    nullable: bool,
+}
+
+#[derive(Debug, Clone)]
+enum InferredKind {
+    Unknown,
+    Bool,
+    Integer,
+    Float,
+    String,
+    Array(Box<InferredType>),
+    Object(ObjectShape),
+    Mixed,
+}
+
+#[derive(Debug, Clone)]
+struct ObjectShape {
+    fields: Vec<FieldShape>,
+    instance_count: usize,
+}
+
+#[derive(Debug, Clone)]
+struct FieldShape {
+    key: String,
+    field_type: InferredType,
+    seen_count: usize,
+}
+
+impl InferredType {
+    fn unknown() -> Self {
+        Self {
+            kind: InferredKind::Unknown,
+            nullable: false,
+        }
+    }
+
+    fn from_item(item: &JsonItem) -> Self {
+        let kind = match item.item_type {
+            JsonItemType::Null => {
+                return Self {
+                    kind: InferredKind::Unknown,
+                    nullable: true,
+                }
+            }
+            JsonItemType::True | JsonItemType::False => InferredKind::Bool,
+            JsonItemType::Number => {
+                if item.value.contains(['.', 'e', 'E']) {
+                    InferredKind::Float
+                } else {
+                    InferredKind::Integer
+                }
+            }
+            JsonItemType::String => InferredKind::String,
+            JsonItemType::Array => {
+                let mut element = Self::unknown();
+                for child in item.children.iter().filter(|ch| is_value(ch)) {
+                    element = element.merge(Self::from_item(child));
+                }
+                InferredKind::Array(Box::new(element))
+            }
+            JsonItemType::Object => {
+                let mut shape = ObjectShape {
+                    fields: Vec::new(),
+                    instance_count: 1,
+                };
+                for child in item.children.iter().filter(|ch| is_value(ch)) {
+                    let key = unquote(&child.name);
+                    let child_type = Self::from_item(child);
+                    if let Some(field) = shape.fields.iter_mut().find(|f| f.key == key) {
+                        field.field_type = field.field_type.clone().merge(child_type);
+                    } else {
+                        shape.fields.push(FieldShape {
+                            key,
+                            field_type: child_type,
+                            seen_count: 1,
+                        });
+                    }
+                }
+                InferredKind::Object(shape)
+            }
+            _ => InferredKind::Unknown,
+        };
+        Self {
+            kind,
+            nullable: false,
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        let nullable = self.nullable || other.nullable;
+        let kind = match (self.kind, other.kind) {
+            (InferredKind::Unknown, kind) | (kind, InferredKind::Unknown) => kind,
+            (InferredKind::Bool, InferredKind::Bool) => InferredKind::Bool,
+            (InferredKind::String, InferredKind::String) => InferredKind::String,
+            (InferredKind::Integer, InferredKind::Integer) => InferredKind::Integer,
+            (
+                InferredKind::Integer | InferredKind::Float,
+                InferredKind::Integer | InferredKind::Float,
+            ) => InferredKind::Float,
+            (InferredKind::Array(a), InferredKind::Array(b)) => {
+                InferredKind::Array(Box::new(a.merge(*b)))
+            }
+            (InferredKind::Object(a), InferredKind::Object(b)) => InferredKind::Object(a.merge(b)),
+            _ => InferredKind::Mixed,
+        };
+        Self { kind, nullable }
+    }
+}
+
+impl ObjectShape {
+    fn merge(mut self, other: Self) -> Self {
+        for other_field in other.fields {
+            if let Some(field) = self.fields.iter_mut().find(|f| f.key == other_field.key) {
+                field.field_type = field.field_type.clone().merge(other_field.field_type);
+                field.seen_count += other_field.seen_count;
+            } else {
+                self.fields.push(other_field);
+            }
+        }
+        self.instance_count += other.instance_count;
+        self
+    }
+}
+
+/// Infers a type skeleton for the given top-level items and renders it.
+pub fn render_type_skeleton(doc_model: &[JsonItem], language: SkeletonLanguage) -> String {
+    let mut root = InferredType::unknown();
+    for item in doc_model.iter().filter(|item| is_value(item)) {
+        root = root.merge(InferredType::from_item(item));
+    }
+
+    let mut renderer = SkeletonRenderer {
+        language,
+        definitions: Vec::new(),
+        used_names: Vec::new(),
+    };
+    let root_ref = renderer.type_ref(&root, "Root");
+    if !matches!(root.kind, InferredKind::Object(_)) {
+        let alias = match language {
+            SkeletonLanguage::Rust => format!("pub type Root = {};\n", root_ref),
+            SkeletonLanguage::TypeScript => format!("export type Root = {};\n", root_ref),
+        };
+        renderer.definitions.insert(0, alias);
+    }
+
+    renderer.definitions.join("\n")
+}
+
+struct SkeletonRenderer {
+    language: SkeletonLanguage,
+    definitions: Vec<String>,
+    used_names: Vec<String>,
+}
+
+impl SkeletonRenderer {
+    fn type_ref(&mut self, inferred: &InferredType, name_hint: &str) -> String {
+        let base = match &inferred.kind {
+            InferredKind::Unknown | InferredKind::Mixed => match self.language {
+                SkeletonLanguage::Rust => "serde_json::Value".to_string(),
+                SkeletonLanguage::TypeScript => "unknown".to_string(),
+            },
+            InferredKind::Bool => match self.language {
+                SkeletonLanguage::Rust => "bool".to_string(),
+                SkeletonLanguage::TypeScript => "boolean".to_string(),
+            },
+            InferredKind::Integer => match self.language {
+                SkeletonLanguage::Rust => "i64".to_string(),
+                SkeletonLanguage::TypeScript => "number".to_string(),
+            },
+            InferredKind::Float => match self.language {
+                SkeletonLanguage::Rust => "f64".to_string(),
+                SkeletonLanguage::TypeScript => "number".to_string(),
+            },
+            InferredKind::String => match self.language {
+                SkeletonLanguage::Rust => "String".to_string(),
+                SkeletonLanguage::TypeScript => "string".to_string(),
+            },
+            InferredKind::Array(element) => {
+                let element_ref = self.type_ref(element, &format!("{}Item", name_hint));
+                match self.language {
+                    SkeletonLanguage::Rust => format!("Vec<{}>", element_ref),
+                    SkeletonLanguage::TypeScript if element_ref.contains(' ') => {
+                        format!("({})[]", element_ref)
+                    }
+                    SkeletonLanguage::TypeScript => format!("{}[]", element_ref),
+                }
+            }
+            InferredKind::Object(shape) => self.define_object(shape, name_hint),
+        };
+
+        if !inferred.nullable {
+            return base;
+        }
+        match self.language {
+            SkeletonLanguage::Rust => format!("Option<{}>", base),
+            SkeletonLanguage::TypeScript => format!("{} | null", base),
+        }
+    }
+
+    fn define_object(&mut self, shape: &ObjectShape, name_hint: &str) -> String {
+        let name = self.unique_name(name_hint);
+        let slot = self.definitions.len();
+        self.definitions.push(String::new());
+
+        let mut body = match self.language {
+            SkeletonLanguage::Rust => format!(
+                "#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{\n",
+                name
+            ),
+            SkeletonLanguage::TypeScript => format!("export interface {} {{\n", name),
+        };
+
+        let mut used_idents: Vec<String> = Vec::new();
+        for field in &shape.fields {
+            let is_optional = field.seen_count < shape.instance_count;
+            let field_ref = self.type_ref(&field.field_type, &to_pascal_case(&field.key));
+            match self.language {
+                SkeletonLanguage::Rust => {
+                    // Keys such as "a-b" and "a_b" give the same identifier.
+                    let base = rust_field_ident(&field.key);
+                    let mut ident = base.clone();
+                    let mut suffix = 2;
+                    while used_idents.contains(&ident) {
+                        ident = format!("{}_{}", base.trim_start_matches("r#"), suffix);
+                        suffix += 1;
+                    }
+                    used_idents.push(ident.clone());
+                    if ident.trim_start_matches("r#") != field.key {
+                        body.push_str(&format!("    #[serde(rename = {:?})]\n", field.key));
+                    }
+                    let field_ref = if is_optional && !field.field_type.nullable {
+                        format!("Option<{}>", field_ref)
+                    } else {
+                        field_ref
+                    };
+                    body.push_str(&format!("    pub {}: {},\n", ident, field_ref));
+                }
+                SkeletonLanguage::TypeScript => {
+                    body.push_str(&format!(
+                        "    {}{}: {};\n",
+                        ts_property_name(&field.key),
+                        if is_optional { "?" } else { "" },
+                        field_ref
+                    ));
+                }
+            }
+        }
+
+        body.push_str("}\n");
+        self.definitions[slot] = body;
+        name
+    }
+
+    fn unique_name(&mut self, name_hint: &str) -> String {
+        let mut name = name_hint.to_string();
+        let mut suffix = 2;
+        while self.used_names.contains(&name) {
+            name = format!("{}{}", name_hint, suffix);
+            suffix += 1;
+        }
+        self.used_names.push(name.clone());
+        name
+    }
+}
+
+fn is_value(item: &JsonItem) -> bool {
+    !matches!(
+        item.item_type,
+        JsonItemType::BlankLine | JsonItemType::BlockComment | JsonItemType::LineComment
+    )
+}
+
+fn unquote(name: &str) -> String {
+    serde_json::from_str::<String>(name).unwrap_or_else(|_| name.trim_matches('"').to_string())
+}
+
+fn split_words(key: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for ch in key.chars() {
+        if !ch.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if ch.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = ch.is_lowercase() || ch.is_ascii_digit();
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn to_pascal_case(key: &str) -> String {
+    let mut name: String = split_words(key)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "Type");
+    }
+    name
+}
+
+fn rust_field_ident(key: &str) -> String {
+    let mut ident = split_words(key)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert_str(0, "field_");
+    }
+    if matches!(ident.as_str(), "self" | "super" | "crate") {
+        ident.push('_');
+    } else if RUST_KEYWORDS.contains(&ident.as_str()) {
+        ident.insert_str(0, "r#");
+    }
+    ident
+}
+
+fn ts_property_name(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        key.to_string()
+    } else {
+        // JSON string syntax is valid TypeScript; Rust's `{:?}` escapes aren't.
+        serde_json::to_string(key).unwrap_or_else(|_| format!("\"{}\"", key))
+    }
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
@@ -0,0 +1,229 @@
+use crate::model::{JsonItem, JsonItemType};
+
+/// Rewrites every string value and property name in `doc_model` so that
+/// non-ASCII characters are written as `\uXXXX` escapes.
+pub fn apply_ascii_only(doc_model: &mut [JsonItem]) {
+    rewrite_strings(doc_model, &|text| {
+        if text.is_ascii() {
+            None
+        } else {
+            Some(escape_non_ascii(text))
+        }
+    });
+}
+
+/// Rewrites every string value and property name in `doc_model` so that
+/// `\uXXXX` escapes of printable characters are written as the characters
+/// themselves.
+pub fn apply_unescape_unicode(doc_model: &mut [JsonItem]) {
+    rewrite_strings(doc_model, &|text| {
+        if text.contains("\\u") {
+            Some(unescape_unicode(text))
+        } else {
+            None
+        }
+    });
+}
+
+/// Rewrites every string value and property name in `doc_model` to use
+/// canonical escapes.
+pub fn apply_normalize_escapes(doc_model: &mut [JsonItem]) {
+    rewrite_strings(doc_model, &|text| {
+        if text.contains('\\') {
+            Some(normalize_escapes(text))
+        } else {
+            None
+        }
+    });
+}
+
+/// Replaces each string value and property name for which `rewrite` returns
+/// a new text.
+fn rewrite_strings(items: &mut [JsonItem], rewrite: &dyn Fn(&str) -> Option<String>) {
+    for item in items.iter_mut() {
+        if let Some(name) = rewrite(&item.name) {
+            item.name = name;
+        }
+        if item.item_type == JsonItemType::String {
+            if let Some(value) = rewrite(&item.value) {
+                item.value = value;
+            }
+        }
+        rewrite_strings(&mut item.children, rewrite);
+    }
+}
+
+/// Escapes each non-ASCII character in a JSON string literal, writing
+/// characters outside the Basic Multilingual Plane as surrogate pairs.
+/// Existing escapes are left as they are.
+fn escape_non_ascii(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 8);
+    let mut units = [0u16; 2];
+    for ch in text.chars() {
+        if ch.is_ascii() {
+            escaped.push(ch);
+            continue;
+        }
+        for unit in ch.encode_utf16(&mut units) {
+            escaped.push_str(&format!("\\u{:04x}", unit));
+        }
+    }
+    escaped
+}
+
+/// Decodes the `\uXXXX` escapes in a JSON string literal whose characters
+/// can be written literally. Escapes of control characters, quotes,
+/// backslashes, unpaired surrogates and invisible characters such as
+/// zero-width spaces, bidirectional controls and line separators are kept.
+fn unescape_unicode(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('\\') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = parse_unicode_escape(rest).and_then(|(unit, len)| {
+            if !(0xD800..0xDC00).contains(&unit) {
+                return char::from_u32(unit).map(|ch| (ch, len));
+            }
+            let (low, low_len) = parse_unicode_escape(&rest[len..])?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return None;
+            }
+            let code = 0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00);
+            char::from_u32(code).map(|ch| (ch, len + low_len))
+        });
+
+        match decoded {
+            Some((ch, len)) if is_safe_literal(ch) => {
+                unescaped.push(ch);
+                rest = &rest[len..];
+            }
+            _ => {
+                // Copy the backslash and the character it escapes unchanged.
+                let len = 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+                unescaped.push_str(&rest[..len]);
+                rest = &rest[len..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Rewrites the escapes in a JSON string literal canonically: `\/` becomes
+/// `/`, `\uXXXX` escapes with a two-character form (such as `\n`) use it,
+/// and the hex digits of the remaining `\u` escapes are lowercased.
+fn normalize_escapes(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('\\') {
+        normalized.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("\\/") {
+            normalized.push('/');
+            rest = after;
+            continue;
+        }
+        if let Some((unit, len)) = parse_unicode_escape(rest) {
+            match unit {
+                0x08 => normalized.push_str("\\b"),
+                0x09 => normalized.push_str("\\t"),
+                0x0A => normalized.push_str("\\n"),
+                0x0C => normalized.push_str("\\f"),
+                0x0D => normalized.push_str("\\r"),
+                0x22 => normalized.push_str("\\\""),
+                0x5C => normalized.push_str("\\\\"),
+                _ => normalized.push_str(&format!("\\u{:04x}", unit)),
+            }
+            rest = &rest[len..];
+            continue;
+        }
+
+        let len = 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+        normalized.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    normalized.push_str(rest);
+    normalized
+}
+
+/// Parses a `\uXXXX` escape at the start of `text`, returning the code unit
+/// and the escape's length.
+fn parse_unicode_escape(text: &str) -> Option<(u32, usize)> {
+    let hex = text.strip_prefix("\\u")?.get(..4)?;
+    if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok().map(|unit| (unit, 6))
+}
+
+fn is_safe_literal(ch: char) -> bool {
+    !ch.is_control()
+        && !matches!(
+            ch,
+            '"' | '\\'
+                | '\u{00AD}'
+                | '\u{200B}'..='\u{200F}'
+                | '\u{2028}'..='\u{202E}'
+                | '\u{2060}'..='\u{2064}'
+                | '\u{2066}'..='\u{2069}'
+                | '\u{FEFF}'
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_ascii_characters_are_escaped() {
+        assert_eq!(escape_non_ascii("\"caf\u{e9}\""), "\"caf\\u00e9\"");
+        assert_eq!(
+            escape_non_ascii("\"\u{65e5}\u{672c}\""),
+            "\"\\u65e5\\u672c\""
+        );
+        assert_eq!(escape_non_ascii("\"plain\\n\""), "\"plain\\n\"");
+    }
+
+    #[test]
+    fn astral_characters_become_surrogate_pairs() {
+        assert_eq!(escape_non_ascii("\"\u{1F600}\""), "\"\\ud83d\\ude00\"");
+    }
+
+    #[test]
+    fn printable_escapes_are_decoded() {
+        assert_eq!(unescape_unicode("\"caf\\u00e9\""), "\"caf\u{e9}\"");
+        assert_eq!(unescape_unicode("\"\\u65E5\\u0041\""), "\"\u{65e5}A\"");
+        assert_eq!(unescape_unicode("\"\\ud83d\\ude00\""), "\"\u{1F600}\"");
+        assert_eq!(unescape_unicode("\"a\\\\u00e9\""), "\"a\\\\u00e9\"");
+    }
+
+    #[test]
+    fn escapes_are_normalized() {
+        assert_eq!(normalize_escapes(r#""a\/b""#), r#""a/b""#);
+        assert_eq!(
+            normalize_escapes(r#""\u000A\u0009\u0022\u005c\u0008\u000c\u000D""#),
+            r#""\n\t\"\\\b\f\r""#
+        );
+        assert_eq!(
+            normalize_escapes(r#""\u00E9\uD83D\uDE00""#),
+            r#""\u00e9\ud83d\ude00""#
+        );
+        assert_eq!(normalize_escapes(r#""\\/ \n""#), r#""\\/ \n""#);
+    }
+
+    #[test]
+    fn unsafe_escapes_are_kept() {
+        for text in [
+            "\"\\u0000\\u001f\\u007f\\u0085\"",
+            "\"\\u0022\\u005c\"",
+            "\"\\ud83d\\u0022\\ude00\"",
+            "\"\\u200b\\u2028\\u202e\\ufeff\"",
+            "\"\\u12\\n\"",
+        ] {
+            assert_eq!(unescape_unicode(text), text);
+        }
+    }
+}
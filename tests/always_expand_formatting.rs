@@ -1,6 +1,6 @@
 mod helpers;
 
-use fracturedjson::Formatter;
+use fracturedjson::{CommentPolicy, Formatter};
 use helpers::{do_instances_line_up, normalize_quotes};
 
 #[test]
@@ -45,3 +45,105 @@ fn always_expand_depth_doesnt_prevent_table_formatting() {
     assert!(do_instances_line_up(&output_lines, ","));
     assert!(do_instances_line_up(&output_lines, "9"));
 }
+
+#[test]
+fn objects_and_arrays_can_expand_separately() {
+    let input = "{'point': {'x': 1, 'y': 2}, 'values': [1, 2, 3], 'rows': [{'a': 1}]}";
+    let input = normalize_quotes(input);
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_table_row_complexity = -1;
+    formatter.options.max_prop_name_padding = 0;
+    formatter.options.always_expand_object_depth = 2;
+
+    // Objects down to depth 2 are expanded, and so is the array holding one.
+    let output = formatter.reformat(&input, 0).unwrap();
+    let expected_lines = [
+        "{",
+        "    \"point\": {",
+        "        \"x\": 1,",
+        "        \"y\": 2",
+        "    },",
+        "    \"values\": [1, 2, 3],",
+        "    \"rows\": [",
+        "        {",
+        "            \"a\": 1",
+        "        }",
+        "    ]",
+        "}",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+
+    formatter.options.always_expand_object_depth = -1;
+    formatter.options.always_expand_array_depth = 1;
+    let output = formatter.reformat(&input, 0).unwrap();
+    let expected_lines = [
+        "{",
+        "    \"point\": {\"x\": 1, \"y\": 2},",
+        "    \"values\": [",
+        "        1,",
+        "        2,",
+        "        3",
+        "    ],",
+        "    \"rows\": [",
+        "        {\"a\": 1}",
+        "    ]",
+        "}",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+}
+
+#[test]
+fn containers_below_compact_depth_are_minified() {
+    let input = "{'servers': {'web': {'ports': [80, 443], 'tags': ['a', 'b']}, 'db': [{'x': 1}]}}";
+    let input = normalize_quotes(input);
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.always_expand_depth = 1;
+    formatter.options.compact_at_depth = 1;
+
+    // Depth-2 containers stay on one line even though they're too long.
+    let output = formatter.reformat(&input, 0).unwrap();
+    let expected_lines = [
+        "{",
+        "    \"servers\": {",
+        "        \"web\": {\"ports\":[80,443],\"tags\":[\"a\",\"b\"]},",
+        "        \"db\" : [{\"x\":1}]",
+        "    }",
+        "}",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+}
+
+#[test]
+fn closing_brackets_can_be_collapsed() {
+    let input = "{'a': {'b': [{'c': [1, 2, 3]}]}, 'z': [[1, 2], {'y': 3} /* end */]}";
+    let input = normalize_quotes(input);
+
+    let mut formatter = Formatter::new();
+    formatter.options.always_expand_depth = 3;
+    formatter.options.collapse_closing_brackets = true;
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+
+    // Brackets after a comment or an inline element stay on lines of their own.
+    let output = formatter.reformat(&input, 0).unwrap();
+    let expected_lines = [
+        "{",
+        "    \"a\": {",
+        "        \"b\": [",
+        "            {",
+        "                \"c\": [1, 2, 3]",
+        "            }]},",
+        "    \"z\": [",
+        "        [",
+        "            1,",
+        "            2",
+        "        ],",
+        "        {",
+        "            \"y\": 3",
+        "        } /* end */",
+        "    ]}",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+}
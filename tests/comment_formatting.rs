@@ -1,6 +1,8 @@
 mod helpers;
 
-use fracturedjson::{CommentPolicy, Formatter};
+use fracturedjson::{
+    CommentPolicy, CommentSpacing, CommentStyle, Formatter, StandaloneCommentOrder,
+};
 use helpers::{do_instances_line_up, normalize_quotes};
 
 #[test]
@@ -175,3 +177,311 @@ fn top_level_comments_ignored_if_set() {
     let output = formatter.reformat(&input, 0).unwrap();
     assert!(!output.contains("//"));
 }
+
+#[test]
+fn blank_line_after_line_comment_keeps_its_place() {
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.preserve_blank_lines = true;
+
+    let output = formatter.reformat("[1, // c\n\n2]", 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(output_lines, vec!["[", "    1,  // c", "", "    2", "]"]);
+
+    let output = formatter
+        .reformat(&normalize_quotes("{'a': 1, // c\n\n'b': 2}"), 0)
+        .unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec!["{", "    \"a\": 1,  // c", "", "    \"b\": 2", "}"]
+    );
+}
+
+#[test]
+fn comment_before_closing_brace_is_kept() {
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+
+    let output = formatter
+        .reformat(&normalize_quotes("{'a': 1 // x\n// end\n}"), 0)
+        .unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec!["{", "    \"a\": 1   // x", "    // end", "}"]
+    );
+
+    let output = formatter
+        .minify(&normalize_quotes("{'a': 1\n/* end */}"))
+        .unwrap();
+    assert_eq!(output, "{\"a\":1\n/* end */\n}");
+}
+
+#[test]
+fn standalone_comments_can_be_put_before_blank_lines() {
+    let input_lines = [
+        "// top",
+        "",
+        "{",
+        "    'a': [",
+        "        1,",
+        "",
+        "        // about two",
+        "        2",
+        "    ],",
+        "",
+        "    /* about b */",
+        "",
+        "    // more about b",
+        "    'b': 3",
+        "}",
+    ];
+    let input = normalize_quotes(&input_lines.join("\n"));
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.preserve_blank_lines = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert_eq!(output.trim_end(), input.replace('\'', "\""));
+
+    formatter.options.standalone_comment_order = StandaloneCommentOrder::CommentsFirst;
+    let output = formatter.reformat(&input, 0).unwrap();
+    let expected_lines = [
+        "// top",
+        "",
+        "{",
+        "    \"a\": [",
+        "        1,",
+        "        // about two",
+        "",
+        "        2",
+        "    ],",
+        "    /* about b */",
+        "    // more about b",
+        "",
+        "",
+        "    \"b\": 3",
+        "}",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+}
+
+#[test]
+fn blank_line_before_comments_inserts_and_collapses() {
+    let input_lines = [
+        "{",
+        "",
+        "    // first",
+        "    'a': 1,",
+        "    // about b",
+        "    // more about b",
+        "    'b': [",
+        "        1,",
+        "",
+        "",
+        "",
+        "        /* about two */",
+        "        2",
+        "    ],",
+        "    'c': 3 // not standalone",
+        "}",
+    ];
+    let input = normalize_quotes(&input_lines.join("\n"));
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.blank_line_before_comments = true;
+
+    let expected_lines = [
+        "{",
+        "    // first",
+        "    \"a\": 1,",
+        "",
+        "    // about b",
+        "    // more about b",
+        "    \"b\": [",
+        "        1,",
+        "",
+        "        /* about two */",
+        "        2",
+        "    ],",
+        "    \"c\": 3 // not standalone",
+        "}",
+    ];
+    for preserve_blank_lines in [false, true] {
+        formatter.options.preserve_blank_lines = preserve_blank_lines;
+        let output = formatter.reformat(&input, 0).unwrap();
+        assert_eq!(output.trim_end(), expected_lines.join("\n"));
+    }
+}
+
+#[test]
+fn comment_spacing_is_normalized() {
+    let input = "[\n    //top\n    /*one*/ 1, //after one\n    [/*x*/ 2]\n]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.comment_spacing = CommentSpacing::Spaced;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let expected_lines = [
+        "[",
+        "    // top",
+        "    /* one */ 1,  // after one",
+        "    [/* x */ 2]",
+        "]",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+
+    formatter.options.comment_spacing = CommentSpacing::Unspaced;
+    let output = formatter.minify(&output).unwrap();
+    assert_eq!(output, "[\n//top\n/*one*/1//after one\n,[/*x*/2]]");
+}
+
+#[test]
+fn postfix_comments_can_be_aligned() {
+    let input_lines = [
+        "{",
+        "    'port': 8080, // listen here",
+        "    'hostname': 'example.com', /* public name */",
+        "    'tls': {",
+        "        'cert': 'a.pem' // nested",
+        "    }, // not aligned",
+        "    'debug': false // off in production",
+        "}",
+    ];
+    let input = normalize_quotes(&input_lines.join("\n"));
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.max_prop_name_padding = 0;
+    formatter.options.align_postfix_comments = true;
+
+    // Comments after elements written on several lines aren't moved.
+    let output = formatter.reformat(&input, 0).unwrap();
+    let expected_lines = [
+        "{",
+        "    \"port\": 8080,             // listen here",
+        "    \"hostname\": \"example.com\" /* public name */,",
+        "    \"tls\": {",
+        "        \"cert\": \"a.pem\"   // nested",
+        "    },  // not aligned",
+        "    \"debug\": false            // off in production",
+        "}",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+}
+
+#[test]
+fn middle_line_comments_can_become_block_comments() {
+    let input = "[\n    {\"a\": // one\n        1, \"b\": 2},\n    {\"a\": 3, \"b\": 4}\n]";
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.max_total_line_length = 40;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.contains("// one\n"));
+
+    formatter.options.block_middle_comments = true;
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\": /* one */ 1, \"b\": 2},",
+            "    {\"a\":           3, \"b\": 4}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn middle_comments_of_expanded_members_stay_line_comments() {
+    let items: Vec<String> = (0..18).map(|i| (1000 + i).to_string()).collect();
+    let input = format!(
+        "{{\"a\": // why\n {{\"x\": 1, \"y\": [{}]}}}}",
+        items.join(", ")
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.block_middle_comments = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.contains("// why\n"));
+    assert!(!output.contains("/* why */"));
+
+    let output = formatter.minify(&input).unwrap();
+    assert!(output.contains("\"a\":/* why */{"));
+}
+
+#[test]
+fn comments_can_be_normalized_to_one_style() {
+    let input = "{\n    // header\n    \"a\": 1, /* one */\n    \"b\": /* mid */ 2 // two\n}";
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.comment_style = CommentStyle::PreferBlock;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    /* header */",
+            "    \"a\":           1 /* one */,",
+            "    \"b\": /* mid */ 2 /* two */",
+            "}",
+        ]
+    );
+
+    formatter.options.comment_style = CommentStyle::PreferLine;
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    // header",
+            "    \"a\":           1,  // one",
+            "    \"b\": /* mid */ 2   // two",
+            "}",
+        ]
+    );
+}
+
+#[test]
+fn only_header_comments_can_be_kept() {
+    let input =
+        "// Copyright\n/* generated */\n{\n    // inner\n    \"a\": 1 // trailing\n}\n// footer";
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::PreserveHeader;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec!["// Copyright", "/* generated */", "{\"a\": 1}"]
+    );
+}
+
+#[test]
+fn line_and_block_comments_can_have_their_own_policies() {
+    let input = "{\n    \"a\": 1, /* kept */\n    \"b\": 2 // dropped\n}";
+    let mut formatter = Formatter::new();
+    formatter.options.block_comment_policy = Some(CommentPolicy::Preserve);
+    assert!(formatter.reformat(input, 0).is_err());
+
+    formatter.options.line_comment_policy = Some(CommentPolicy::Remove);
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.contains("/* kept */"));
+    assert!(!output.contains("dropped"));
+
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.block_comment_policy = Some(CommentPolicy::TreatAsError);
+    formatter.options.line_comment_policy = None;
+    assert!(formatter.reformat(input, 0).is_err());
+    assert!(formatter.reformat("[1, // fine\n 2]", 0).is_ok());
+}
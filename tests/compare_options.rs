@@ -0,0 +1,74 @@
+use fracturedjson::{CommentPolicy, Formatter, FracturedJsonOptions};
+
+#[test]
+fn identical_options_have_no_differences() {
+    let mut formatter = Formatter::new();
+    let other = formatter.options().clone();
+
+    let comparison = formatter
+        .compare_options(r#"{"a": [1, 2]}"#, &other)
+        .unwrap();
+    assert!(!comparison.has_differences());
+    assert_eq!(comparison.output_a, comparison.output_b);
+    #[cfg(feature = "diff")]
+    assert_eq!(comparison.unified_diff("a", "b"), "");
+}
+
+#[cfg(feature = "diff")]
+#[test]
+fn differences_are_shown_as_unified_diff() {
+    let input = r#"{"a": [1, 2], "b": 3}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = 1;
+    let other = FracturedJsonOptions {
+        max_inline_complexity: 1,
+        indent_spaces: 2,
+        ..Default::default()
+    };
+
+    let comparison = formatter.compare_options(input, &other).unwrap();
+    assert_eq!(
+        comparison.output_a,
+        "{\n    \"a\": [1, 2],\n    \"b\": 3\n}\n"
+    );
+    assert_eq!(comparison.output_b, "{\n  \"a\": [1, 2],\n  \"b\": 3\n}\n");
+    assert_eq!(
+        comparison.unified_diff("old", "new"),
+        "--- old\n+++ new\n@@ -1,4 +1,4 @@\n {\n-    \"a\": [1, 2],\n-    \"b\": 3\n+  \"a\": [1, 2],\n+  \"b\": 3\n }\n"
+    );
+}
+
+#[test]
+fn own_options_are_restored() {
+    let mut formatter = Formatter::new();
+    formatter.options.indent_spaces = 3;
+    let other = FracturedJsonOptions {
+        comment_policy: CommentPolicy::TreatAsError,
+        ..Default::default()
+    };
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+
+    // Fails with the other options, which reject comments
+    assert!(formatter.compare_options("[1, /* c */ 2]", &other).is_err());
+    assert_eq!(formatter.options.indent_spaces, 3);
+    assert_eq!(formatter.options.comment_policy, CommentPolicy::Preserve);
+}
+
+#[test]
+fn options_deserialize_with_defaults() {
+    let options: FracturedJsonOptions = serde_json::from_str(
+        r#"{"indent_spaces": 2, "comment_policy": "preserve", "table_comma_placement": "after_padding"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        options,
+        FracturedJsonOptions {
+            indent_spaces: 2,
+            comment_policy: CommentPolicy::Preserve,
+            table_comma_placement: fracturedjson::TableCommaPlacement::AfterPadding,
+            ..Default::default()
+        }
+    );
+
+    assert!(serde_json::from_str::<FracturedJsonOptions>(r#"{"indent": 2}"#).is_err());
+}
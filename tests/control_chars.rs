@@ -0,0 +1,55 @@
+use fracturedjson::{ControlCharPolicy, Formatter};
+
+#[test]
+fn raw_control_chars_are_rejected_by_default() {
+    let input = "{\"msg\": \"a\tb\"}";
+    let mut formatter = Formatter::new();
+    assert!(formatter.reformat(input, 0).is_err());
+}
+
+#[test]
+fn escape_policy_produces_valid_json() {
+    let input = "{\"msg\": \"line1\nline2\tend\", \"k\u{1}\": 1}";
+    let mut formatter = Formatter::new();
+    formatter.options.control_char_policy = ControlCharPolicy::Escape;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output,
+        "{\"msg\": \"line1\\nline2\\tend\", \"k\\u0001\": 1}\n"
+    );
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value["msg"], "line1\nline2\tend");
+
+    let minified = formatter.minify(input).unwrap();
+    assert!(serde_json::from_str::<serde_json::Value>(&minified).is_ok());
+}
+
+#[test]
+fn preserve_policy_passes_text_through() {
+    let input = "[\"a\tb\", \"c\nd\"]";
+    let mut formatter = Formatter::new();
+    formatter.options.control_char_policy = ControlCharPolicy::Preserve;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.contains("\"a\tb\""));
+    assert!(output.contains("\"c\nd\""));
+}
+
+#[test]
+fn string_escapes_can_be_normalized() {
+    let input = r#"{"url\/path": "a\/b\u000Ac\u00E9", "q": "\u0022"}"#;
+    let mut formatter = Formatter::new();
+
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output, format!("{}\n", input));
+
+    formatter.options.normalize_string_escapes = true;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output,
+        "{\"url/path\": \"a/b\\nc\\u00e9\", \"q\": \"\\\"\"}\n"
+    );
+    let minified = formatter.minify(input).unwrap();
+    assert_eq!(minified, "{\"url/path\":\"a/b\\nc\\u00e9\",\"q\":\"\\\"\"}");
+}
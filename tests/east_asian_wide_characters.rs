@@ -35,3 +35,70 @@ fn pads_wide_chars_correctly() {
     assert_eq!(find_char_index(output_lines[2], "Job"), Some(28));
     assert_eq!(find_char_index(output_lines[3], "Job"), Some(26));
 }
+
+#[test]
+fn built_in_width_functions() {
+    assert_eq!(Formatter::string_length_by_char_count("李小龍 x"), 5);
+    assert_eq!(Formatter::string_length_by_display_width("李小龍 x"), 8);
+    assert_eq!(Formatter::string_length_by_byte_count("李小龍 x"), 11);
+
+    let input = normalize_quotes(
+        "[{'Name': '李小龍', 'Job': 'Actor'}, {'Name': 'Mark Twain', 'Job': 'Writer'}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 50;
+    formatter.string_length_func = Arc::new(Formatter::string_length_by_display_width);
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+
+    assert_eq!(find_char_index(output_lines[1], "Job"), Some(25));
+    assert_eq!(find_char_index(output_lines[2], "Job"), Some(28));
+}
+
+#[test]
+fn ascii_only_escapes_and_aligns_on_escaped_text() {
+    let input_lines = [
+        "[",
+        "    {'Name': '李小龍', 'Job': 'Actor', '😀': 1940},",
+        "    {'Name': 'Mark Twain', 'Job': 'Writer', '😀': 1835}",
+        "]",
+    ];
+    let input = normalize_quotes(&input_lines.join("\n"));
+    let mut formatter = Formatter::new();
+    formatter.options.ascii_only = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.is_ascii());
+    let output_lines: Vec<String> = output
+        .trim_end()
+        .split('\n')
+        .map(|s| s.to_string())
+        .collect();
+    assert!(output_lines[1].contains("\"\\u674e\\u5c0f\\u9f8d\""));
+    assert!(output_lines[1].contains("\"\\ud83d\\ude00\": 1940"));
+    assert!(do_instances_line_up(&output_lines, "Job"));
+
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value[0]["Name"], "李小龍");
+    assert_eq!(value[1]["😀"], 1835);
+}
+
+#[test]
+fn unescape_unicode_writes_literal_characters() {
+    let input = "{\"city\": \"M\\u00fcnchen\", \"name\": \"\\u674e\\u5c0f\\u9f8d\", \"tab\": \"\\u0009\\u200b\"}";
+    let mut formatter = Formatter::new();
+    formatter.options.unescape_unicode = true;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output,
+        "{\"city\": \"München\", \"name\": \"李小龍\", \"tab\": \"\\u0009\\u200b\"}\n"
+    );
+
+    formatter.options.ascii_only = true;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output,
+        "{\"city\": \"M\\u00fcnchen\", \"name\": \"\\u674e\\u5c0f\\u9f8d\", \"tab\": \"\\u0009\\u200b\"}\n"
+    );
+}
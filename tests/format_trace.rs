@@ -0,0 +1,136 @@
+use fracturedjson::{CommentPolicy, FormatDecision, Formatter, NumberChange, NumberListAlignment};
+use std::sync::Arc;
+
+fn decisions(
+    formatter: &mut Formatter,
+    input: &str,
+) -> Vec<(String, FormatDecision, usize, usize)> {
+    formatter
+        .reformat_with_trace(input, 0)
+        .unwrap()
+        .events
+        .into_iter()
+        .map(|e| (e.path, e.decision, e.first_line, e.last_line))
+        .collect()
+}
+
+#[test]
+fn output_matches_reformat() {
+    let input = r#"{"a": [1, 2, 3], "b": {"c": [true, null]}, "d": "text"}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = 1;
+
+    let trace = formatter.reformat_with_trace(input, 0).unwrap();
+    assert_eq!(trace.output, formatter.reformat(input, 0).unwrap());
+    assert_eq!(trace.line_paths.len(), trace.output.lines().count());
+}
+
+#[test]
+fn layout_decisions_are_recorded() {
+    let input = r#"{
+        // rows
+        "rows": [{"a": 1, "b": 2}, {"a": 3, "b": 4}],
+        "nums": [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+        "s": "x"
+    }"#;
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.max_total_line_length = 30;
+
+    assert_eq!(
+        decisions(&mut formatter, input)
+            .into_iter()
+            .filter(|(path, ..)| !path.starts_with("/nums/"))
+            .collect::<Vec<_>>(),
+        vec![
+            ("".to_string(), FormatDecision::Expanded, 0, 12),
+            ("".to_string(), FormatDecision::Comment, 1, 1),
+            ("/rows".to_string(), FormatDecision::Table, 2, 5),
+            ("/rows/0".to_string(), FormatDecision::TableRow, 3, 3),
+            ("/rows/1".to_string(), FormatDecision::TableRow, 4, 4),
+            ("/nums".to_string(), FormatDecision::CompactMultiline, 6, 10),
+            ("/s".to_string(), FormatDecision::Value, 11, 11),
+        ]
+    );
+}
+
+#[test]
+fn lines_are_tagged_with_innermost_items() {
+    let input = r#"{"a/b": [1, 2, 3, 4, 5, 6], "c": {"d": 1}}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 20;
+    formatter.options.max_inline_complexity = 1;
+
+    let trace = formatter.reformat_with_trace(input, 0).unwrap();
+    let expected_lines = [
+        "{",
+        "    \"a/b\": [",
+        "        1, 2, 3, 4,",
+        "        5, 6",
+        "    ],",
+        "    \"c\"  : {\"d\": 1}",
+        "}",
+    ];
+    assert_eq!(trace.output.trim_end(), expected_lines.join("\n"));
+    assert_eq!(
+        trace.line_paths,
+        vec![
+            vec![""],
+            vec!["/a~1b"],
+            vec!["/a~1b/0", "/a~1b/1", "/a~1b/2", "/a~1b/3"],
+            vec!["/a~1b/4", "/a~1b/5"],
+            vec!["/a~1b"],
+            vec!["/c"],
+            vec![""],
+        ]
+    );
+}
+
+#[test]
+fn lossy_number_changes_are_reported() {
+    let input = r#"{"xs": [1.25, 10.5, 3, 0.1e1], "big": [12345678901234567890.5, 1]}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.number_list_alignment = NumberListAlignment::Normalize;
+    formatter.options.normalize_max_decimal_places = Some(1);
+    formatter.options.max_total_line_length = 30;
+
+    let trace = formatter.reformat_with_trace(input, 0).unwrap();
+    assert_eq!(
+        trace.number_changes,
+        vec![NumberChange {
+            path: "/xs/0".to_string(),
+            original: "1.25".to_string(),
+            written: "1.3".to_string(),
+        },]
+    );
+}
+
+#[test]
+fn number_changes_leave_out_numbers_cut_by_max_output_lines() {
+    let input = r#"{"a": [1.5, 2.5], "b": {"c": 3.5, "d": [4.5, {"e": 5.5}]}, "f": 6.5, "g": 7.5, "h": 8.5}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.max_output_lines = Some(8);
+    formatter.number_format_func = Some(Arc::new(|number: &str| {
+        number.parse::<f64>().unwrap().round().to_string()
+    }));
+
+    let trace = formatter.reformat_with_trace(input, 0).unwrap();
+    let expected_lines = [
+        "{",
+        "    \"a\": [2, 3],",
+        "    \"b\": {",
+        "        \"c\": 4",
+        "        // \u{2026} 1 more item \u{2026}",
+        "    }",
+        "    // \u{2026} 3 more items \u{2026}",
+        "}",
+    ];
+    assert_eq!(trace.output.trim_end(), expected_lines.join("\n"));
+    let paths: Vec<&str> = trace
+        .number_changes
+        .iter()
+        .map(|change| change.path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["/a/0", "/a/1", "/b/c"]);
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use fracturedjson::{Formatter, FracturedJsonOptions};
+
+#[test]
+fn with_options_applies_the_options() {
+    let options = FracturedJsonOptions {
+        colon_padding: false,
+        max_total_line_length: 10,
+        ..Default::default()
+    };
+    let mut formatter = Formatter::with_options(options.clone());
+    assert_eq!(formatter.options(), &options);
+
+    let output = formatter.reformat(r#"{"a":1,"b":2}"#, 0).unwrap();
+    assert_eq!(output, "{\n    \"a\":1,\n    \"b\":2\n}\n");
+}
+
+#[test]
+fn set_options_replaces_previous_options() {
+    let mut formatter = Formatter::new();
+    assert_eq!(formatter.minify("[1, 2]").unwrap(), "[1,2]");
+    assert_eq!(formatter.reformat("[1,2]", 0).unwrap(), "[1, 2]\n");
+
+    formatter.set_options(FracturedJsonOptions {
+        comma_padding: false,
+        ..Default::default()
+    });
+    assert_eq!(formatter.reformat("[1,2]", 0).unwrap(), "[1,2]\n");
+}
+
+#[test]
+fn changes_between_calls_are_picked_up() {
+    let mut formatter = Formatter::new();
+    assert_eq!(formatter.reformat("[1,2]", 0).unwrap(), "[1, 2]\n");
+
+    formatter.options.simple_bracket_padding = true;
+    assert_eq!(formatter.reformat("[1,2]", 0).unwrap(), "[ 1, 2 ]\n");
+
+    formatter.options.prefix_string = "##".to_string();
+    formatter.options.max_total_line_length = 14;
+    assert_eq!(formatter.reformat("[1,2,3]", 0).unwrap(), "##[ 1, 2, 3 ]\n");
+
+    // A wider measure of the prefix no longer fits in the line.
+    formatter.string_length_func = Arc::new(|s: &str| s.chars().count() * 2);
+    assert_eq!(
+        formatter.reformat("[1,2,3]", 0).unwrap(),
+        "##[\n##    1,\n##    2,\n##    3\n##]\n"
+    );
+}
+
+#[test]
+fn diff_friendly_preset_writes_one_element_per_line() {
+    let input = "{\"zip\": \"12345\", \"id\": 7, \"tags\": [\"a\", \"b\"], \"pos\": {\"y\": -4.50, \"x\": 12}, \"none\": []}";
+    let mut formatter = Formatter::with_options(FracturedJsonOptions::diff_friendly());
+
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output,
+        concat!(
+            "{\n",
+            "    \"id\": 7,\n",
+            "    \"none\": [],\n",
+            "    \"pos\": {\n",
+            "        \"x\": 12,\n",
+            "        \"y\": -4.50\n",
+            "    },\n",
+            "    \"tags\": [\n",
+            "        \"a\",\n",
+            "        \"b\"\n",
+            "    ],\n",
+            "    \"zip\": \"12345\"\n",
+            "}\n"
+        )
+    );
+}
@@ -0,0 +1,102 @@
+use fracturedjson::gen::{Generator, GeneratorOptions, NumberStyle};
+use fracturedjson::{CommentPolicy, Formatter};
+
+fn depth_of(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Array(items) => 1 + items.iter().map(depth_of).max().unwrap_or(0),
+        serde_json::Value::Object(map) => 1 + map.values().map(depth_of).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+#[test]
+fn same_seed_gives_same_documents() {
+    let options = GeneratorOptions {
+        seed: 42,
+        comment_density: 0.3,
+        ..Default::default()
+    };
+    let mut first = Generator::new(options.clone());
+    let mut second = Generator::new(options);
+    for _ in 0..5 {
+        assert_eq!(first.document(), second.document());
+    }
+
+    let mut other = Generator::new(GeneratorOptions {
+        seed: 43,
+        comment_density: 0.3,
+        ..Default::default()
+    });
+    assert_ne!(first.document(), other.document());
+}
+
+#[test]
+fn documents_without_comments_are_plain_json() {
+    for seed in 0..50 {
+        let mut generator = Generator::new(GeneratorOptions {
+            seed,
+            max_depth: 3,
+            max_fan_out: 4,
+            ..Default::default()
+        });
+        let document = generator.document();
+        let value: serde_json::Value = serde_json::from_str(&document).unwrap();
+        assert!((1..=3).contains(&depth_of(&value)), "{}", document);
+        if let Some(items) = value.as_array() {
+            assert!(items.len() <= 4);
+        }
+    }
+}
+
+#[test]
+fn commented_documents_parse_with_preserve() {
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+
+    for multiline in [true, false] {
+        let mut generator = Generator::new(GeneratorOptions {
+            seed: 1,
+            comment_density: 0.5,
+            multiline,
+            ..Default::default()
+        });
+        let mut comment_count = 0;
+        for _ in 0..20 {
+            let document = generator.document();
+            comment_count += document.matches("/*").count() + document.matches("//").count();
+            formatter.reformat(&document, 0).unwrap();
+        }
+        assert!(comment_count > 0);
+    }
+
+    let mut generator = Generator::new(GeneratorOptions {
+        comment_density: 0.5,
+        ..Default::default()
+    });
+    let jsonl = generator.jsonl(10);
+    assert_eq!(jsonl.lines().count(), 10);
+    formatter.reformat_jsonl(&jsonl).unwrap();
+}
+
+#[test]
+fn number_style_controls_number_text() {
+    let mut generator = Generator::new(GeneratorOptions {
+        seed: 3,
+        number_style: NumberStyle::Integer,
+        ..Default::default()
+    });
+    let value: serde_json::Value = serde_json::from_str(&generator.document()).unwrap();
+    let mut numbers = Vec::new();
+    collect_numbers(&value, &mut numbers);
+    assert!(!numbers.is_empty());
+    assert!(numbers.iter().all(|n| n.is_i64()));
+}
+
+fn collect_numbers(value: &serde_json::Value, numbers: &mut Vec<serde_json::Number>) {
+    match value {
+        serde_json::Value::Number(n) => numbers.push(n.clone()),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_numbers(v, numbers)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_numbers(v, numbers)),
+        _ => {}
+    }
+}
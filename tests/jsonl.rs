@@ -1,4 +1,4 @@
-use fracturedjson::Formatter;
+use fracturedjson::{CommentPolicy, Formatter};
 
 #[test]
 fn formats_simple_jsonl() {
@@ -194,3 +194,124 @@ fn formats_arrays_inline_when_simple() {
     assert!(lines[0].starts_with("[") && lines[0].ends_with("]"));
     assert!(lines[1].starts_with("[") && lines[1].ends_with("]"));
 }
+
+#[test]
+fn keeps_same_line_comments_with_their_value() {
+    let input = "{\"a\":1} // request id 7\n/* pre */ [1,2] /* post */\n// alone\n3";
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+
+    let output = formatter.reformat_jsonl(input).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "{\"a\": 1} // request id 7",
+            "/* pre */ [1, 2] /* post */",
+            "// alone",
+            "3",
+        ]
+    );
+
+    let output = formatter.minify_jsonl(input).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "{\"a\":1}// request id 7");
+    assert_eq!(lines[1], "/* pre */[1,2]/* post */");
+}
+
+#[test]
+fn same_line_comments_can_be_removed() {
+    let input = "{\"a\":1} // request id 7\n";
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Remove;
+    let output = formatter.reformat_jsonl(input).unwrap();
+    assert_eq!(output.lines().next(), Some("{\"a\": 1}"));
+}
+
+#[test]
+fn reformat_detects_jsonl_when_enabled() {
+    let input = "{\"a\":1}\n\n{\"b\":[1,2]}\n";
+
+    let mut formatter = Formatter::new();
+    assert!(formatter.reformat(input, 0).is_err());
+
+    formatter.options.auto_detect_jsonl = true;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output, "{\"a\": 1}\n\n{ \"b\": [1, 2] }\n");
+}
+
+#[test]
+fn auto_detect_jsonl_ignores_other_multiple_values() {
+    let mut formatter = Formatter::new();
+    formatter.options.auto_detect_jsonl = true;
+
+    // Two values on one line, and a value spanning several lines.
+    assert!(formatter.reformat("[1] [2]\n[3]", 0).is_err());
+    assert!(formatter.reformat("[1]\n{\n\"a\": 2\n}", 0).is_err());
+    assert!(formatter.reformat("[1]\n[2,", 0).is_err());
+
+    let output = formatter.reformat("[1,\n2]", 0).unwrap();
+    assert_eq!(output, "[1, 2]\n");
+}
+
+#[test]
+fn top_level_array_becomes_one_line_per_element() {
+    let input = r#"[
+    {"id": 1, "tags": ["a", "b"], "nested": {"deep": {"deeper": [1, 2, 3]}}},
+    // second
+    {"id": 2}, // last one
+    3
+]"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.max_total_line_length = 20;
+    let output = formatter.reformat_as_jsonl(input).unwrap();
+    let expected_lines = [
+        r#"{ "id": 1, "tags": ["a", "b"], "nested": { "deep": { "deeper": [1, 2, 3] } } }"#,
+        "// second",
+        r#"{"id": 2} // last one"#,
+        "3",
+    ];
+    assert_eq!(output, expected_lines.join("\n") + "\n");
+
+    let output = formatter.minify_as_jsonl(input).unwrap();
+    let expected_lines = [
+        r#"{"id":1,"tags":["a","b"],"nested":{"deep":{"deeper":[1,2,3]}}}"#,
+        "// second",
+        r#"{"id":2}// last one"#,
+        "3",
+    ];
+    assert_eq!(output, expected_lines.join("\n") + "\n");
+
+    // The output reads back as the same elements.
+    let round_trip = formatter.minify_jsonl(&output).unwrap();
+    assert_eq!(round_trip.lines().count(), 4);
+}
+
+#[test]
+fn jsonl_output_needs_a_top_level_array() {
+    let mut formatter = Formatter::new();
+    let err = formatter.reformat_as_jsonl(r#"{"a": [1, 2]}"#).unwrap_err();
+    assert!(err.message.contains("top-level array"));
+}
+
+#[test]
+fn shared_column_widths_align_lines() {
+    let input = "{\"id\":1,\"name\":\"Ann\"}\n\n{\"id\":22,\"name\":\"Bo\",\"tag\":\"x\"}\n";
+    let mut formatter = Formatter::new();
+    formatter.options.jsonl_shared_column_widths = true;
+
+    let output = formatter.reformat_jsonl(input).unwrap();
+    assert_eq!(
+        output,
+        "{\"id\":  1, \"name\": \"Ann\"            }\n\n{\"id\": 22, \"name\": \"Bo\",  \"tag\": \"x\"}\n"
+    );
+
+    // Lines that don't fit together are formatted one by one.
+    formatter.options.max_total_line_length = 30;
+    let output = formatter.reformat_jsonl(input).unwrap();
+    assert!(output.starts_with("{\"id\": 1, \"name\": \"Ann\"}\n"));
+}
@@ -0,0 +1,77 @@
+use fracturedjson::{Formatter, KeyCase};
+
+#[test]
+fn keys_are_converted_at_every_depth() {
+    let input = r#"{"firstName": "a", "home_address": {"zipCode": 1}, "tags": [{"tag-name": 2}]}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.key_case = Some(KeyCase::SnakeCase);
+
+    let output = formatter.minify(input).unwrap();
+    assert_eq!(
+        output,
+        r#"{"first_name":"a","home_address":{"zip_code":1},"tags":[{"tag_name":2}]}"#
+    );
+
+    formatter.options.key_case = Some(KeyCase::CamelCase);
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output,
+        concat!(
+            "{\n",
+            "    \"firstName\"  : \"a\",\n",
+            "    \"homeAddress\": {\"zipCode\": 1},\n",
+            "    \"tags\"       : [ {\"tagName\": 2} ]\n",
+            "}\n"
+        )
+    );
+}
+
+#[test]
+fn excluded_paths_are_left_alone() {
+    let input = r#"{"devDependencies": {"@types/node": 1, "left_pad": 2}, "build_mode": "x", "list": [{"a_b": 1}, {"a_b": 2}]}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.key_case = Some(KeyCase::KebabCase);
+    formatter.options.key_case_exclusions = vec!["/devDependencies".into(), "/list/1/a_b".into()];
+
+    let output = formatter.minify(input).unwrap();
+    assert_eq!(
+        output,
+        r#"{"devDependencies":{"@types/node":1,"left_pad":2},"build-mode":"x","list":[{"a-b":1},{"a_b":2}]}"#
+    );
+}
+
+#[test]
+fn keys_converted_to_the_same_name_are_an_error() {
+    let mut formatter = Formatter::new();
+    formatter.options.key_case = Some(KeyCase::SnakeCase);
+
+    let err = formatter.reformat(r#"{"aB": 1, "a_b": 2}"#, 0).unwrap_err();
+    assert!(err.to_string().contains(r#""aB" and "a_b""#));
+    assert!(formatter.minify(r#"{"x": {"a-b": 1, "aB": 2}}"#).is_err());
+
+    // Keys that are already the same, or apart from an excluded one, are fine.
+    assert!(formatter.minify(r#"{"a_b": 1, "a_b": 2}"#).is_ok());
+    formatter.options.key_case_exclusions = vec!["/aB".into()];
+    let output = formatter.minify(r#"{"aB": 1, "a_b": 2}"#).unwrap();
+    assert_eq!(output, r#"{"aB":1,"a_b":2}"#);
+}
+
+#[test]
+fn values_are_not_converted() {
+    let mut formatter = Formatter::new();
+    formatter.options.key_case = Some(KeyCase::SnakeCase);
+    let output = formatter
+        .minify(r#"["someValue", {"someKey": "someValue"}]"#)
+        .unwrap();
+    assert_eq!(output, r#"["someValue",{"some_key":"someValue"}]"#);
+}
+
+#[test]
+fn serialized_values_are_converted() {
+    let mut formatter = Formatter::new();
+    formatter.options.key_case = Some(KeyCase::CamelCase);
+    let output = formatter
+        .serialize_value(&serde_json::json!({"max_retries": 3}), 0, 100)
+        .unwrap();
+    assert_eq!(output, "{\"maxRetries\": 3}\n");
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use fracturedjson::{Formatter, LayoutHint};
+
+fn hinted_formatter(hints: &[(&str, LayoutHint)]) -> Formatter {
+    let mut formatter = Formatter::new();
+    formatter.options.key_layout_hints = hints
+        .iter()
+        .map(|(key, hint)| (key.to_string(), *hint))
+        .collect::<HashMap<_, _>>();
+    formatter
+}
+
+#[test]
+fn inline_hint_ignores_length_and_complexity() {
+    let input =
+        r#"{"name": "route", "coordinates": [[1.5, 2.5], [3.5, 4.5], [5.5, 6.5], [7.5, 8.5]]}"#;
+    let mut formatter = hinted_formatter(&[("coordinates", LayoutHint::Inline)]);
+    formatter.options.max_total_line_length = 40;
+    formatter.options.max_inline_complexity = 0;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"name\"       : \"route\",",
+            "    \"coordinates\": [ [1.5, 2.5], [3.5, 4.5], [5.5, 6.5], [7.5, 8.5] ]",
+            "}",
+        ]
+    );
+}
+
+#[test]
+fn expand_hint_applies_wherever_the_key_occurs() {
+    let input = r#"[{"id": 1, "tags": ["a", "b"]}, {"id": 2, "meta": {"tags": ["c"]}}]"#;
+    let mut formatter = hinted_formatter(&[("tags", LayoutHint::Expand)]);
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {",
+            "        \"id\"  : 1,",
+            "        \"tags\": [",
+            "            \"a\",",
+            "            \"b\"",
+            "        ]",
+            "    },",
+            "    {",
+            "        \"id\"  : 2,",
+            "        \"meta\": {",
+            "            \"tags\": [",
+            "                \"c\"",
+            "            ]",
+            "        }",
+            "    }",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn table_and_compact_hints_are_used_even_when_inline_fits() {
+    let input = r#"{"rows": [{"a": 1, "b": 2}, {"a": 10, "b": 20}], "grid": [1, 2, 3, 4]}"#;
+    let mut formatter =
+        hinted_formatter(&[("rows", LayoutHint::Table), ("grid", LayoutHint::Compact)]);
+    formatter.options.max_inline_complexity = 3;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"rows\": [",
+            "        {\"a\":  1, \"b\":  2},",
+            "        {\"a\": 10, \"b\": 20}",
+            "    ],",
+            "    \"grid\": [",
+            "        1, 2, 3, 4",
+            "    ]",
+            "}",
+        ]
+    );
+}
@@ -0,0 +1,85 @@
+use fracturedjson::{CommentPolicy, Formatter, KeyCase};
+
+fn ordering_formatter(keys: &[&str]) -> Formatter {
+    let mut formatter = Formatter::new();
+    formatter.options.key_order = keys.iter().map(|key| key.to_string()).collect();
+    formatter
+}
+
+#[test]
+fn listed_keys_come_first_in_template_order() {
+    let input = r#"{"scripts": 1, "version": "1.0", "extra": true, "name": "x"}"#;
+    let mut formatter = ordering_formatter(&["name", "version", "description"]);
+
+    let output = formatter.minify(input).unwrap();
+    assert_eq!(
+        output,
+        r#"{"name":"x","version":"1.0","scripts":1,"extra":true}"#
+    );
+}
+
+#[test]
+fn nested_objects_are_reordered_and_arrays_are_not() {
+    let input = r#"[{"b": 1, "a": {"b": 2, "a": 3}}, ["b", "a"]]"#;
+    let mut formatter = ordering_formatter(&["a", "b"]);
+
+    let output = formatter.minify(input).unwrap();
+    assert_eq!(output, r#"[{"a":{"a":3,"b":2},"b":1},["b","a"]]"#);
+}
+
+#[test]
+fn comments_move_with_the_following_property() {
+    let input = r#"{
+    // about b
+    "b": 1, // after b
+    // about a
+    "a": 2
+    // end
+}"#;
+    let mut formatter = ordering_formatter(&["a"]);
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output,
+        concat!(
+            "{\n",
+            "    // about a\n",
+            "    \"a\": 2,\n",
+            "    // about b\n",
+            "    \"b\": 1   // after b\n",
+            "    // end\n",
+            "}\n"
+        )
+    );
+}
+
+#[test]
+fn keys_are_matched_after_case_conversion() {
+    let input = r#"{"zip_code": 1, "first_name": "x"}"#;
+    let mut formatter = ordering_formatter(&["firstName", "zipCode"]);
+    formatter.options.key_case = Some(KeyCase::CamelCase);
+
+    let output = formatter.minify(input).unwrap();
+    assert_eq!(output, r#"{"firstName":"x","zipCode":1}"#);
+}
+
+#[test]
+fn unlisted_keys_can_be_sorted() {
+    let input = r#"{"zeta": 1, "bar": 2, "name": "x", "alpha": {"d": 1, "c": 2}}"#;
+    let mut formatter = ordering_formatter(&["name"]);
+    formatter.options.sort_keys = true;
+
+    let output = formatter.minify(input).unwrap();
+    assert_eq!(
+        output,
+        r#"{"name":"x","alpha":{"c":2,"d":1},"bar":2,"zeta":1}"#
+    );
+
+    formatter.options.key_order.clear();
+    let output = formatter.minify(input).unwrap();
+    assert_eq!(
+        output,
+        r#"{"alpha":{"c":2,"d":1},"bar":2,"name":"x","zeta":1}"#
+    );
+}
@@ -82,3 +82,231 @@ fn correct_line_count_for_line_length() {
         );
     }
 }
+
+#[test]
+fn primitive_arrays_stay_on_one_line_when_never_wrap_is_set() {
+    let input = normalize_quotes(
+        "{'vec': [1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5, 8.5, 9.5, 10.5, 11.5, 12.5], 'names': ['alpha', 'beta', 'gamma', 'delta', 'epsilon'], 'nested': [[1, 2], [3, 4]]}",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.never_wrap_primitive_arrays = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+
+    assert!(output_lines.contains(
+        &"    \"vec\"   : [1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5, 8.5, 9.5, 10.5, 11.5, 12.5],"
+    ));
+    assert!(output_lines
+        .contains(&"    \"names\" : [\"alpha\", \"beta\", \"gamma\", \"delta\", \"epsilon\"],"));
+    assert!(output_lines.contains(&"    \"nested\": [ [1, 2], [3, 4] ]"));
+
+    formatter.options.never_wrap_primitive_arrays = false;
+    let wrapped = formatter.reformat(&input, 0).unwrap();
+    assert!(wrapped.lines().all(|line| line.len() <= 40));
+}
+
+#[test]
+fn containers_with_many_children_are_expanded() {
+    let input = "{\"short\": [1, 2, 3, 4], \"long\": [1, 2, 3, 4, 5]}";
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_children = Some(4);
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"short\": [1, 2, 3, 4],",
+            "    \"long\" : [",
+            "        1,",
+            "        2,",
+            "        3,",
+            "        4,",
+            "        5",
+            "    ]",
+            "}",
+        ]
+    );
+
+    formatter.options.max_inline_children = Some(5);
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output.trim_end(),
+        "{ \"short\": [1, 2, 3, 4], \"long\": [1, 2, 3, 4, 5] }"
+    );
+}
+
+#[test]
+fn preferred_line_length_is_exceeded_only_to_avoid_expanding() {
+    let input = "{\"numbers\": [100, 200, 300, 400, 500, 600, 700, 800, 900, 1000, 1100, 1200], \
+                 \"point\": {\"x\": 10.5, \"y\": 20.25, \"label\": \"origin point\"}}";
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 70;
+    formatter.options.preferred_line_length = Some(40);
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"numbers\": [",
+            "         100,  200,  300,  400,  500,",
+            "         600,  700,  800,  900, 1000,",
+            "        1100, 1200",
+            "    ],",
+            "    \"point\"  : {\"x\": 10.5, \"y\": 20.25, \"label\": \"origin point\"}",
+            "}",
+        ]
+    );
+
+    formatter.options.max_total_line_length = 60;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.lines().all(|line| line.len() <= 60));
+    assert!(output.contains("        \"label\": \"origin point\"\n"));
+}
+
+#[test]
+fn compact_array_rows_can_be_balanced() {
+    let input = "[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]";
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "     1,  2,  3,  4,  5,  6,",
+            "     7,  8,  9, 10, 11, 12,",
+            "    13, 14",
+            "]",
+        ]
+    );
+
+    formatter.options.balance_compact_array_rows = true;
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "     1,  2,  3,  4,  5,",
+            "     6,  7,  8,  9, 10,",
+            "    11, 12, 13, 14",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn compact_array_items_can_be_aligned_into_columns() {
+    let input = "[\"a\", \"bbbb\", 1, \"cc\", true, \"ddddd\", null, \"e\", \"ffff\", 22, \"g\"]";
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.align_compact_array_items = true;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    \"a\",     \"bbbb\",  1,       \"cc\",",
+            "    true,    \"ddddd\", null,    \"e\",",
+            "    \"ffff\",  22,      \"g\"",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn inline_value_length_ignores_indentation() {
+    let input = r#"{"a": {"b": {"c": [1, 2]}}, "list": [100, 200, 300]}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = 1;
+    formatter.options.max_compact_array_complexity = -1;
+    formatter.options.max_table_row_complexity = -1;
+    formatter.options.max_prop_name_padding = 0;
+    formatter.options.max_inline_value_length = Some(12);
+
+    // The nested array is short enough; the top-level one isn't.
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"a\": {",
+            "        \"b\": {",
+            "            \"c\": [1, 2]",
+            "        }",
+            "    },",
+            "    \"list\": [",
+            "        100,",
+            "        200,",
+            "        300",
+            "    ]",
+            "}",
+        ]
+    );
+}
+
+#[test]
+fn inline_value_length_replaces_the_line_length_check() {
+    let input = r#"{"a": {"b": {"c": [1, 2]}}}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 20;
+    formatter.options.max_inline_complexity = 1;
+    formatter.options.max_compact_array_complexity = -1;
+    formatter.options.max_table_row_complexity = -1;
+
+    // `"c": [1, 2]` is 23 columns wide with its indentation.
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.contains("\"c\": [\n"));
+
+    formatter.options.max_inline_value_length = Some(12);
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"a\": {",
+            "        \"b\": {",
+            "            \"c\": [1, 2]",
+            "        }",
+            "    }",
+            "}",
+        ]
+    );
+}
+
+#[test]
+fn output_can_be_cut_short_for_previews() {
+    let input = r#"{"name": "big", "rows": [[1, 2], [3, 4], [5, 6], [7, 8], [9, 10]], "tags": ["a", "b"], "extra": 1}"#;
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.max_output_lines = Some(8);
+
+    // The elements before each marker lose their commas.
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"name\" : \"big\",",
+            "    \"rows\" : [",
+            "        [1, 2]",
+            "        // \u{2026} 4 more items \u{2026}",
+            "    ]",
+            "    // \u{2026} 2 more items \u{2026}",
+            "}",
+        ]
+    );
+}
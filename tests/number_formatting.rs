@@ -1,8 +1,10 @@
 mod helpers;
 
 use fracturedjson::{
-    EolStyle, Formatter, FracturedJsonOptions, NumberListAlignment, TableCommaPlacement,
+    DecimalRounding, EolStyle, ExponentStyle, Formatter, FracturedJsonOptions, NormalizeFallback,
+    NumberListAlignment, NumberNotation, TableCommaPlacement,
 };
+use std::sync::Arc;
 
 #[test]
 fn inline_array_doesnt_justify_numbers() {
@@ -59,10 +61,25 @@ fn big_numbers_invalidate_alignment_1() {
 }
 
 #[test]
-fn big_numbers_invalidate_alignment_2() {
+fn big_numbers_can_keep_their_text() {
+    let input = "[1, 2.1, 3, 1e+99]";
+    let expected_output = "[\n      1.0,   2.1,   3.0, 1e+99\n]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = -1;
+    formatter.options.json_eol_style = EolStyle::Lf;
+    formatter.options.number_list_alignment = NumberListAlignment::Normalize;
+    formatter.options.normalize_fallback = NormalizeFallback::KeepText;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), expected_output);
+}
+
+#[test]
+fn big_integers_align_as_text() {
     let input = "[1, 2.1, 3, 12345678901234567]";
     let expected_output =
-        "[\n    1                , 2.1              , 3                , 12345678901234567\n]";
+        "[\n                    1.0,                 2.1,                 3.0, 12345678901234567.0\n]";
 
     let mut formatter = Formatter::new();
     formatter.options.max_inline_complexity = -1;
@@ -192,6 +209,41 @@ fn normalize_align_matches_expected() {
     test_alignment(NumberListAlignment::Normalize, &expected_rows);
 }
 
+#[test]
+fn normalize_can_round_to_fewer_decimal_places() {
+    let input = "[[123.456, 0.995], [234567.8, -0.004], [3, 2], [5.6789, 0.5]]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.json_eol_style = EolStyle::Lf;
+    formatter.options.number_list_alignment = NumberListAlignment::Normalize;
+    formatter.options.table_comma_placement = TableCommaPlacement::AfterPadding;
+    formatter.options.max_total_line_length = 40;
+    formatter.options.normalize_max_decimal_places = Some(2);
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        [
+            "[",
+            "    [   123.46, 1.00],",
+            "    [234567.80, 0.00],",
+            "    [     3.00, 2.00],",
+            "    [     5.68, 0.50]",
+            "]",
+        ]
+    );
+
+    formatter.options.normalize_rounding = DecimalRounding::TowardZero;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.contains("[   123.45, 0.99],"));
+    assert!(output.contains("[234567.80, 0.00],"));
+
+    formatter.options.normalize_rounding = DecimalRounding::AwayFromZero;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.contains("[234567.80, -0.01],"));
+}
+
 fn test_alignment(align: NumberListAlignment, expected_rows: &[&str]) {
     let input_rows = [
         "[",
@@ -215,3 +267,281 @@ fn test_alignment(align: NumberListAlignment, expected_rows: &[&str]) {
 
     assert_eq!(output_rows, expected_rows);
 }
+
+#[test]
+fn exponent_style_is_applied_to_every_number() {
+    let input = r#"{"a": 1.5E+10, "b": [2e-3, 4E5, 7], "c": "1E+2"}"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.exponent_style = ExponentStyle::Lowercase;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(
+        output,
+        "{ \"a\": 1.5e10, \"b\": [2e-3, 4e5, 7], \"c\": \"1E+2\" }\n"
+    );
+
+    formatter.options.exponent_style = ExponentStyle::UppercaseSigned;
+    let output = formatter.minify(input).unwrap();
+    assert_eq!(output, r#"{"a":1.5E+10,"b":[2E-3,4E+5,7],"c":"1E+2"}"#);
+}
+
+#[test]
+fn trailing_zeros_can_be_trimmed() {
+    let input = "[[1.50, 2.0], [10.250, 3.75], [0.5, 100.00]]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.table_comma_placement = TableCommaPlacement::AfterPadding;
+    formatter.options.trim_trailing_zeros = true;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        [
+            "[",
+            "    [ 1.5 ,   2   ],",
+            "    [10.25,   3.75],",
+            "    [ 0.5 , 100   ]",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn numbers_can_be_written_in_one_notation() {
+    let input = "[7e2, 700, 0.0007, 1.50E+4]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.number_notation = NumberNotation::Fixed;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), "[700, 700, 0.0007, 15000]");
+
+    formatter.options.number_notation = NumberNotation::Scientific;
+    formatter.options.exponent_style = ExponentStyle::UppercaseSigned;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), "[7E+2, 7E+2, 7E-4, 1.5E+4]");
+
+    formatter.options.number_notation_threshold = Some(3);
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), "[700, 700, 7E-4, 1.5E+4]");
+}
+
+#[test]
+fn huge_exponents_are_kept_in_fixed_notation() {
+    let input = "[1e9223372036854775807, 1e1000000, 2.5]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.number_notation = NumberNotation::Fixed;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), "[1e9223372036854775807, 1e1000000, 2.5]");
+}
+
+#[test]
+fn exponents_can_be_aligned() {
+    let input = "[[1.5e10, 1], [2E-3, 2], [123.25e4, 3], [7, 4]]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.number_list_alignment = NumberListAlignment::Exponent;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        [
+            "[",
+            "    [  1.50e10, 1],",
+            "    [  2.00E-3, 2],",
+            "    [123.25e4 , 3],",
+            "    [  7      , 4]",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn aligned_exponents_keep_numbers_valid() {
+    let input = "[[1.5e10, 22e3, 3.25e-7], [-4e2, 0.5, 6E+1], [7, 8.125e0, 9]]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.number_list_alignment = NumberListAlignment::Exponent;
+    formatter.options.verify_round_trip = true;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(input).unwrap();
+    let actual: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn integer_columns_can_be_zero_padded() {
+    let input = "[[7, 1.5], [-42, 2], [null, 3], [123, 4]]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.zero_pad_integers = true;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        [
+            "[",
+            "    [ 007, 1.5],",
+            "    [-042, 2  ],",
+            "    [null, 3  ],",
+            "    [ 123, 4  ]",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn zero_padding_widens_columns_for_signs() {
+    let input = "[7, -42, 123, 5]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = -1;
+    formatter.options.zero_pad_integers = true;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), "[\n     007, -042,  123,  005\n]");
+
+    formatter.options.number_list_alignment = NumberListAlignment::Left;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), "[\n    007 , -042, 123 , 005\n]");
+}
+
+#[test]
+fn negative_zeros_can_lose_their_sign() {
+    let input = "[-0, -0.0, 1.25, -3]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = -1;
+    formatter.options.number_list_alignment = NumberListAlignment::Normalize;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), "[\n    -0.00, -0.00,  1.25, -3.00\n]");
+
+    formatter.options.normalize_negative_zero = true;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output.trim_end(), "[\n     0.00,  0.00,  1.25, -3.00\n]");
+}
+
+#[test]
+fn numbers_can_be_rewritten_by_a_function() {
+    let input = "[[1.5e+3, 2], [12, 3.25e-2]]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 24;
+    formatter.options.exponent_style = ExponentStyle::LowercaseSigned;
+    formatter.number_format_func = Some(Arc::new(|number: &str| number.replace("e+", "E")));
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        ["[", "    [ 1.5E3, 2      ],", "    [12    , 3.25e-2]", "]"]
+    );
+}
+
+#[test]
+fn number_columns_can_reserve_integer_digits() {
+    let input = r#"[{"id": 1, "x": 2.5}, {"id": 2, "x": -1}, {"id": 3, "x": 10}]"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.number_min_integer_digits = 4;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        [
+            "[",
+            "    {\"id\":    1, \"x\":    2.5},",
+            "    {\"id\":    2, \"x\":   -1  },",
+            "    {\"id\":    3, \"x\":   10  }",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn inline_arrays_can_align_numbers() {
+    let input = r#"{"xs": [1.5, 10, -2.25], "rows": [[1, [2.5, 30]], [2, [4, 5]]]}"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.align_inline_numbers = true;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        [
+            "{",
+            "    \"xs\"  : [ 1.5 , 10   , -2.25],",
+            "    \"rows\": [",
+            "        [ 1, [2.5, 30] ],",
+            "        [ 2, [4  ,  5] ]",
+            "    ]",
+            "}",
+        ]
+    );
+
+    formatter.options.align_inline_numbers = false;
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.contains("[1.5, 10, -2.25]"));
+}
+
+#[test]
+fn picked_integers_can_be_written_in_hex() {
+    let input = r#"{"flags": 255, "mask": [15, 16], "other": {"flags": -1, "size": 10}}"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = 1;
+    formatter.options.hex_integer_keys = vec!["flags".to_string(), "/mask/1".to_string()];
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        [
+            "{",
+            "    \"flags\": \"0xFF\",",
+            "    \"mask\" : [15, \"0x10\"],",
+            "    \"other\": {\"flags\": -1, \"size\": 10}",
+            "}",
+        ]
+    );
+}
+
+#[test]
+fn numbers_can_be_measured_for_digit_grouping() {
+    let input = "[[1234567.5, 1], [8.25, 2], [-1234, 3]]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.number_width_func = Some(Arc::new(|number: &str| {
+        let digits = number
+            .trim_start_matches('-')
+            .split(['.', 'e', 'E'])
+            .next()
+            .unwrap_or_default()
+            .len();
+        number.len() + digits.saturating_sub(1) / 3
+    }));
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_rows: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_rows,
+        [
+            "[",
+            "    [1234567.5 , 1],",
+            "    [        8.25, 2],",
+            "    [   -1234   , 3]",
+            "]",
+        ]
+    );
+}
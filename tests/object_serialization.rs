@@ -53,6 +53,46 @@ fn handles_sparse_arrays() {
     assert_eq!(nice, "[\"val1\", null, null, \"val2\"]\n");
 }
 
+#[test]
+fn null_and_empty_properties_can_be_omitted() {
+    #[derive(Serialize)]
+    struct Settings {
+        name: &'static str,
+        nickname: Option<&'static str>,
+        tags: Vec<&'static str>,
+        extra: Option<Box<Settings>>,
+        values: Vec<Option<u32>>,
+    }
+
+    let settings = Settings {
+        name: "main",
+        nickname: None,
+        tags: vec![],
+        extra: Some(Box::new(Settings {
+            name: "",
+            nickname: None,
+            tags: vec![],
+            extra: None,
+            values: vec![],
+        })),
+        values: vec![Some(1), None],
+    };
+
+    let mut formatter = Formatter::new();
+    formatter.options.omit_null_values = true;
+    let output = formatter.serialize(&settings, 0, 100).unwrap();
+    assert_eq!(
+        output,
+        "{ \"extra\": {\"name\": \"\", \"tags\": [], \"values\": []}, \"name\": \"main\", \"tags\": [], \"values\": [1, null] }\n"
+    );
+
+    // Objects left empty are omitted too; elements of arrays are always kept.
+    formatter.options.omit_empty_containers = true;
+    let value = json!({"a": {"b": null, "c": {}}, "d": [null, []], "e": 0});
+    let output = formatter.serialize_value(&value, 0, 100).unwrap();
+    assert_eq!(output, "{ \"d\": [null, []], \"e\": 0 }\n");
+}
+
 #[test]
 fn file_data_matches_native_stringify_when_minimized() {
     let base_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/StandardJsonFiles");
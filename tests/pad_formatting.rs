@@ -1,6 +1,6 @@
 mod helpers;
 
-use fracturedjson::Formatter;
+use fracturedjson::{CommentPolicy, Formatter};
 use std::fs;
 use std::path::Path;
 
@@ -46,3 +46,156 @@ fn simple_bracket_padding_works_for_tables() {
     assert!(output_lines[1].contains("[1, 2]"));
     assert!(output_lines[2].contains("[3, 4]"));
 }
+
+#[test]
+fn custom_colon_and_comment_separator_are_used() {
+    let input = "{\"a\": 1, // one\n\"bb\": 22 // two\n}";
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.colon_token = Some(" : ".to_string());
+    formatter.options.comment_separator = Some("    ".to_string());
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+
+    assert_eq!(output_lines[1], "    \"a\"  :  1,     // one");
+    assert_eq!(output_lines[2], "    \"bb\" : 22      // two");
+}
+
+#[test]
+fn spaces_before_eol_comment_only_affect_postfix_comments() {
+    let input = "{/*x*/ \"a\": 1, // one\n\"bb\": 22 // two\n}";
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.spaces_before_eol_comment = Some(2);
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+
+    assert_eq!(output_lines[1], "    /*x*/ \"a\" :  1,  // one");
+    assert_eq!(output_lines[2], "          \"bb\": 22   // two");
+
+    // The gap is counted from the comma, or from the value if there's none.
+    let input = "{\"a\": [1, 2], // one\n\"b\": {\"c\": 3} // two\n}";
+    formatter.options.max_table_row_complexity = -1;
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(output_lines[1], "    \"a\": [1, 2],  // one");
+    assert_eq!(output_lines[2], "    \"b\": {\"c\": 3}  // two");
+
+    formatter.options.spaces_before_eol_comment = Some(0);
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(output_lines[1], "    \"a\": [1, 2],// one");
+}
+
+#[test]
+fn custom_dummy_comma_is_used() {
+    let input = "[[1,2], // a\n[3,4] // b\n]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.max_inline_complexity = -1;
+    formatter.options.dummy_comma = Some("  ".to_string());
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+
+    assert_eq!(output_lines[1], "    [1, 2],  // a");
+    assert_eq!(output_lines[2], "    [3, 4]   // b");
+}
+
+#[test]
+fn custom_indent_string_is_used() {
+    let input = "{\"a\": [1, 2], \"b\": {\"c\": 3}}";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = 0;
+    formatter.options.max_compact_array_complexity = 0;
+    formatter.options.max_table_row_complexity = -1;
+    formatter.options.use_tab_to_indent = true;
+    formatter.options.indent_string = Some("\t ".to_string());
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+
+    assert_eq!(output_lines[1], "\t \"a\": [");
+    assert_eq!(output_lines[2], "\t \t 1,");
+    assert_eq!(output_lines[6], "\t \t \"c\": 3");
+}
+
+#[test]
+fn indent_string_width_counts_against_line_length() {
+    let input = "[[1, 2, 3]]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = 1;
+    formatter.options.max_total_line_length = 11;
+    formatter.options.indent_string = Some("  ".to_string());
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output, "[\n  [1, 2, 3]\n]\n");
+
+    formatter.options.indent_string = Some("   ".to_string());
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_ne!(output, "[\n   [1, 2, 3]\n]\n");
+}
+
+#[test]
+fn indentation_can_vary_by_depth() {
+    let input = "{\"a\": {\"b\": [1, 2]}}";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_inline_complexity = 0;
+    formatter.options.max_compact_array_complexity = 0;
+    formatter.options.max_table_row_complexity = -1;
+    formatter.options.indent_spaces_by_depth = vec![2];
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let expected_lines = [
+        "{",
+        "  \"a\": {",
+        "      \"b\": [",
+        "          1,",
+        "          2",
+        "      ]",
+        "  }",
+        "}",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+
+    // Widths count against the line length, so a 12-column row fits at depth 2
+    // only with the narrower indentation.
+    let input = "[[[1, 2, 3]]]";
+    formatter.options.max_inline_complexity = 1;
+    formatter.options.max_total_line_length = 12;
+    formatter.options.indent_spaces_by_depth = vec![1, 1];
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.contains("\n  [1, 2, 3]\n"));
+
+    formatter.options.indent_spaces_by_depth = vec![1];
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(!output.contains("[1, 2, 3]"));
+}
+
+#[test]
+fn custom_tokens_that_would_break_json_are_rejected() {
+    let bad_options: Vec<fn(&mut Formatter)> = vec![
+        |f| f.options.colon_token = Some(" = ".to_string()),
+        |f| f.options.colon_token = Some("::".to_string()),
+        |f| f.options.comment_separator = Some(" ; ".to_string()),
+        |f| f.options.comment_separator = Some("\n".to_string()),
+        |f| f.options.dummy_comma = Some(",".to_string()),
+        |f| f.options.dummy_comma = Some("\t".to_string()),
+        |f| f.options.dummy_comma = Some("    ".to_string()),
+        |f| f.options.indent_string = Some("--".to_string()),
+        |f| f.options.indent_string = Some("\n".to_string()),
+    ];
+
+    for set_option in bad_options {
+        let mut formatter = Formatter::new();
+        set_option(&mut formatter);
+        assert!(formatter.reformat("{\"a\": 1}", 0).is_err());
+    }
+}
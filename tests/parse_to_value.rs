@@ -0,0 +1,87 @@
+use fracturedjson::{CommentPolicy, Formatter, Trivia};
+use serde_json::json;
+
+fn jsonc_formatter() -> Formatter {
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.preserve_blank_lines = true;
+    formatter
+}
+
+#[test]
+fn value_matches_comment_free_parse() {
+    let input = r#"
+        // leading
+        {
+            "a": [1, 2.5, "x", null, true],  // after a
+            /* before b */ "b": { "c": false }
+        }
+    "#;
+    let formatter = jsonc_formatter();
+    let (value, _) = formatter.parse_to_value(input).unwrap();
+    assert_eq!(
+        value,
+        json!({ "a": [1, 2.5, "x", null, true], "b": { "c": false } })
+    );
+}
+
+#[test]
+fn comments_are_keyed_by_json_pointer() {
+    let input = r#"// top
+{
+    "list": [
+        1,
+
+        // before second
+        2 // after second
+        // end of list
+    ],
+    "a/b~c": /* mid */ 3
+}
+// bottom"#;
+    let formatter = jsonc_formatter();
+    let (_, comments) = formatter.parse_to_value(input).unwrap();
+
+    let root = &comments[""];
+    assert_eq!(root.leading, vec![Trivia::Comment("// top".into())]);
+    assert_eq!(root.trailing, vec![Trivia::Comment("// bottom".into())]);
+
+    let second = &comments["/list/1"];
+    assert_eq!(
+        second.leading,
+        vec![
+            Trivia::BlankLine,
+            Trivia::Comment("// before second".into())
+        ]
+    );
+    assert_eq!(second.postfix_comment.as_deref(), Some("// after second"));
+
+    let list = &comments["/list"];
+    assert_eq!(
+        list.trailing,
+        vec![Trivia::Comment("// end of list".into())]
+    );
+
+    let escaped = &comments["/a~1b~0c"];
+    assert_eq!(escaped.middle_comment.as_deref(), Some("/* mid */"));
+
+    assert!(!comments.contains_key("/list/0"));
+}
+
+#[test]
+fn comments_follow_comment_policy() {
+    let input = "[1, /* note */ 2]";
+    let mut formatter = Formatter::new();
+    assert!(formatter.parse_to_value(input).is_err());
+
+    formatter.options.comment_policy = CommentPolicy::Remove;
+    let (value, comments) = formatter.parse_to_value(input).unwrap();
+    assert_eq!(value, json!([1, 2]));
+    assert!(comments.is_empty());
+}
+
+#[test]
+fn error_if_no_top_level_value() {
+    let formatter = jsonc_formatter();
+    assert!(formatter.parse_to_value("// only a comment").is_err());
+}
@@ -0,0 +1,103 @@
+use fracturedjson::{CommentPolicy, Formatter};
+use std::sync::Arc;
+
+#[test]
+fn prefix_with_line_break_is_rejected() {
+    let mut formatter = Formatter::new();
+    formatter.options.prefix_string = "//\n".to_string();
+    assert!(formatter.reformat("[1, 2]", 0).is_err());
+
+    formatter.options.prefix_string = "\r".to_string();
+    assert!(formatter
+        .serialize_value(&serde_json::json!([1]), 0, 100)
+        .is_err());
+}
+
+#[test]
+fn prefix_width_counts_against_line_length() {
+    let input = "[1, 2, 3, 4]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 14;
+    formatter.options.prefix_string = "\t\t".to_string();
+    let output = formatter.reformat(input, 0).unwrap();
+    assert_eq!(output, "\t\t[1, 2, 3, 4]\n");
+
+    // A wider measure for tabs pushes the array over the limit.
+    formatter.string_length_func =
+        Arc::new(|s: &str| s.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum());
+    let output = formatter.reformat(input, 0).unwrap();
+    assert!(output.lines().count() > 1);
+    assert!(output.lines().all(|line| line.starts_with("\t\t")));
+}
+
+#[test]
+fn whitespace_prefix_leaves_no_trailing_whitespace() {
+    let input = "[\n1,\n\n2\n]";
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.preserve_blank_lines = true;
+    formatter.options.prefix_string = " \t".to_string();
+    let output = formatter.reformat(input, 0).unwrap();
+
+    assert!(output.lines().any(|line| line.is_empty()));
+    assert!(output.lines().all(|line| line.trim_end() == line));
+}
+
+#[test]
+fn fragment_lines_all_carry_indent_prefix() {
+    let input = r#"{"name":"Alice","scores":[95,87,92]}"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.prefix_string = "// ".to_string();
+    let output = formatter.reformat_fragment(input, "        ").unwrap();
+
+    let lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines.iter().all(|line| line.starts_with("        // ")));
+    assert!(lines.iter().all(|line| line.chars().count() <= 40));
+    assert_eq!(lines[2], "        //     \"scores\": [95, 87, 92]");
+    assert_eq!(lines[3], "        // }");
+
+    // The caller's options are left untouched.
+    assert_eq!(formatter.options.prefix_string, "// ");
+}
+
+#[test]
+fn fragment_rejects_multiline_indent() {
+    let mut formatter = Formatter::new();
+    assert!(formatter.reformat_fragment("[1]", "\n  ").is_err());
+    assert!(formatter.options.prefix_string.is_empty());
+}
+
+#[test]
+fn line_prefix_func_gets_line_number_and_depth() {
+    let input = "{'a': [1, 2], // note\n\n'b': {'c': 3}}".replace('\'', "\"");
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.preserve_blank_lines = true;
+    formatter.options.always_expand_depth = 1;
+    formatter.options.prefix_string = " *".to_string();
+    formatter.line_prefix_func = Some(Arc::new(|line, depth| format!("{}:{} ", line, depth)));
+    let output = formatter.reformat(&input, 0).unwrap();
+    let expected_lines = [
+        " *0:0 {",
+        " *1:1     \"a\": [",
+        " *2:2         1,",
+        " *3:2         2",
+        " *4:1     ],  // note",
+        " *5:1",
+        " *6:1     \"b\": {",
+        " *7:2         \"c\": 3",
+        " *8:1     }",
+        " *9:0 }",
+    ];
+    assert_eq!(output.trim_end(), expected_lines.join("\n"));
+
+    // The prefix can't be stripped again to check the output.
+    formatter.options.verify_round_trip = true;
+    assert!(formatter.reformat(&input, 0).is_err());
+}
@@ -0,0 +1,69 @@
+use fracturedjson::{CommentPolicy, Formatter, NumberListAlignment};
+
+const COMMENTED_INPUT: &str = r#"
+// Settings
+{
+    "name": "demo", // inline
+    /* before */ "sizes": [1, 2.5, 10, -3e2],
+
+    "rows": [
+        {"id": 1, "tags": ["a", "b"]},
+        {"id": 2, "tags": []} /* last */
+    ],
+    "nested": {"deep": {"deeper": [null, true, false]}}
+    /* multi
+       line */
+}
+"#;
+
+fn verifying_formatter() -> Formatter {
+    let mut formatter = Formatter::new();
+    formatter.options.verify_round_trip = true;
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.preserve_blank_lines = true;
+    formatter
+}
+
+#[test]
+fn formatting_passes_verification() {
+    for max_length in [20, 60, 120] {
+        for alignment in [
+            NumberListAlignment::Left,
+            NumberListAlignment::Decimal,
+            NumberListAlignment::Normalize,
+        ] {
+            let mut formatter = verifying_formatter();
+            formatter.options.max_total_line_length = max_length;
+            formatter.options.number_list_alignment = alignment;
+
+            formatter.reformat(COMMENTED_INPUT, 0).unwrap();
+            formatter.minify(COMMENTED_INPUT).unwrap();
+        }
+    }
+}
+
+#[test]
+fn verification_handles_prefix_strings_and_fragments() {
+    let mut formatter = verifying_formatter();
+    formatter.options.prefix_string = "// ".to_string();
+    formatter.reformat(COMMENTED_INPUT, 0).unwrap();
+
+    formatter.options.prefix_string = String::new();
+    formatter
+        .reformat_fragment(COMMENTED_INPUT, "        ")
+        .unwrap();
+}
+
+#[test]
+fn verification_covers_other_entry_points() {
+    let mut formatter = verifying_formatter();
+    formatter
+        .reformat_stream("{\"a\":1} /* x */ [1,2]", 0)
+        .unwrap();
+    formatter
+        .reformat_jsonl("/* a */ /* b */ {\"a\":1} // c\n[1]\n")
+        .unwrap();
+    formatter
+        .serialize_value(&serde_json::json!({"a": [1.5, "x"]}), 0, 100)
+        .unwrap();
+}
@@ -0,0 +1,108 @@
+use fracturedjson::{CommentPolicy, Formatter, NumberNotation};
+
+#[test]
+fn object_rows_are_exported_as_csv() {
+    let input = r#"{
+        "people": [
+            {"name": "Ann", "age": 31, "tags": ["a", "b"]},
+            {"name": "Smith, \"Bo\"", "age": null},
+            {"age": 7, "name": "Cy", "city": "Oslo"}
+        ]
+    }"#;
+
+    let mut formatter = Formatter::new();
+    let csv = formatter.to_csv(input, "/people").unwrap();
+
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "name,age,tags,city",
+            r#"Ann,31,"[""a"",""b""]","#,
+            r#""Smith, ""Bo""",,,"#,
+            "Cy,7,,Oslo",
+        ]
+    );
+}
+
+#[test]
+fn array_rows_are_exported_as_tsv() {
+    let input = r#"[
+        // header-less data
+        [1, "x", true],
+        [2, "y"]
+    ]"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    let tsv = formatter.to_tsv(input, "").unwrap();
+
+    assert_eq!(tsv, "0\t1\t2\n1\tx\ttrue\n2\ty\t\n");
+}
+
+#[test]
+fn display_options_leave_exported_values_alone() {
+    let input = r#"[{"name": "Annabel", "score": 1.50}, {"name": "Bo", "score": 1e3}]"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_string_display_length = Some(3);
+    formatter.options.number_notation = NumberNotation::Scientific;
+    formatter.options.trim_trailing_zeros = true;
+    let csv = formatter.to_csv(input, "").unwrap();
+
+    assert_eq!(csv, "name,score\nAnnabel,1.50\nBo,1e3\n");
+}
+
+#[test]
+fn export_needs_uniform_rows() {
+    let mut formatter = Formatter::new();
+
+    let err = formatter.to_csv(r#"[{"a": 1}, [2]]"#, "").unwrap_err();
+    assert!(err.to_string().contains("isn't tabular"));
+
+    let err = formatter.to_csv(r#"{"rows": []}"#, "/missing").unwrap_err();
+    assert!(err.to_string().contains("No value found at '/missing'"));
+}
+
+#[test]
+fn rows_are_exported_as_markdown() {
+    let input = r#"[
+        {"item": "Widget", "price": 4.5, "note": "a|b"},
+        {"item": "Gizmo", "price": 12, "note": "two\nlines"}
+    ]"#;
+
+    let mut formatter = Formatter::new();
+    let table = formatter.to_markdown_table(input, "").unwrap();
+
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "| item   | price | note         |",
+            "| ------ | ----: | ------------ |",
+            "| Widget |   4.5 | a\\|b         |",
+            "| Gizmo  |    12 | two<br>lines |",
+        ]
+    );
+}
+
+#[test]
+fn display_options_leave_markdown_cells_alone() {
+    let input = r#"[{"name": "Annabel", "score": 1.50}, {"name": "Bo", "score": 1e3}]"#;
+
+    let mut formatter = Formatter::new();
+    formatter.options.max_string_display_length = Some(3);
+    formatter.options.number_notation = NumberNotation::Scientific;
+    let table = formatter.to_markdown_table(input, "").unwrap();
+
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "| name    | score |",
+            "| ------- | ----: |",
+            "| Annabel |  1.50 |",
+            "| Bo      |   1e3 |",
+        ]
+    );
+}
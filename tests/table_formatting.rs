@@ -1,7 +1,11 @@
 mod helpers;
 
-use fracturedjson::{CommentPolicy, EolStyle, Formatter, NumberListAlignment, TableCommaPlacement};
+use fracturedjson::{
+    CellAlignment, CommentPolicy, EolStyle, Formatter, NumberListAlignment, StringColumnAlignment,
+    TableColumnKind, TableColumnOrder, TableCommaPlacement,
+};
 use helpers::{do_instances_line_up, normalize_quotes};
+use std::sync::Arc;
 
 #[test]
 fn nested_elements_line_up() {
@@ -421,3 +425,725 @@ fn single_columns_with_numbers_work() {
     assert_eq!(output_lines.len(), 7);
     assert!(do_instances_line_up(&output_lines, "."));
 }
+
+#[test]
+fn truncated_strings_line_up_in_tables() {
+    let input = normalize_quotes(
+        "[{'msg': 'connection reset by peer while reading', 'code': 104}, {'msg': 'ok', 'code': 0}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.max_string_display_length = Some(10);
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"msg\": \"connectio…\", \"code\": 104},",
+            "    {\"msg\": \"ok\",         \"code\":   0}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn outlier_rows_dont_widen_table_columns() {
+    let input = normalize_quotes(
+        "[{'msg': 'ok', 'code': 0}, {'msg': 'connection reset by peer', 'code': 104}, {'msg': 'timeout', 'code': 110}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 60;
+    formatter.options.max_table_column_width = Some(12);
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"msg\": \"ok\",      \"code\":   0},",
+            "    {\"msg\": \"connection reset by peer\", \"code\": 104},",
+            "    {\"msg\": \"timeout\", \"code\": 110}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn wide_rows_too_long_for_the_line_are_written_in_place() {
+    let input = normalize_quotes(
+        "[{'msg': 'ok', 'code': 0}, {'msg': 'the connection was reset by the peer', 'code': 104}, {'msg': 'timeout', 'code': 110}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.max_table_column_width = Some(12);
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"msg\": \"ok\",      \"code\":   0},",
+            "    {",
+            "        \"msg\": \"the connection was reset by the peer\",",
+            "        \"code\": 104",
+            "    },",
+            "    {\"msg\": \"timeout\", \"code\": 110}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn string_columns_can_be_right_aligned() {
+    let input = normalize_quotes(
+        "[{'id': 'a1', 'code': 'U+41'}, {'id': 'b22', 'code': 'U+1F600'}, {'id': 'c', 'code': 'U+E9'}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.string_column_alignment = StringColumnAlignment::Right;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"id\":  \"a1\", \"code\":    \"U+41\"},",
+            "    {\"id\": \"b22\", \"code\": \"U+1F600\"},",
+            "    {\"id\":   \"c\", \"code\":    \"U+E9\"}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn forced_tables_can_exceed_line_length() {
+    let input = normalize_quotes(
+        "[{'name': 'alpha', 'value': 1}, {'name': 'a much longer name here', 'value': 22}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.force_table_formatting = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"name\": \"alpha\",                   \"value\":  1},",
+            "    {\"name\": \"a much longer name here\", \"value\": 22}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn tables_can_start_with_a_header_comment() {
+    let input = normalize_quotes(
+        "[{'name': 'Ann', 'age': 31, 'city': 'Oslo'}, {'name': 'Bartholomew', 'age': 7, 'city': 'Rome'}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 60;
+    formatter.options.table_header_comments = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    // name                  age        city",
+            "    {\"name\": \"Ann\",         \"age\": 31, \"city\": \"Oslo\"},",
+            "    {\"name\": \"Bartholomew\", \"age\":  7, \"city\": \"Rome\"}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn header_comments_line_up_with_named_rows() {
+    let input = normalize_quotes(
+        "{'ann': {'age': 31, 'city': 'Oslo'}, 'bartholomew': {'age': 7, 'city': 'Rome'}}",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 50;
+    formatter.options.table_header_comments = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    //               age        city",
+            "    \"ann\"        : {\"age\": 31, \"city\": \"Oslo\"},",
+            "    \"bartholomew\": {\"age\":  7, \"city\": \"Rome\"}",
+            "}",
+        ]
+    );
+}
+
+#[test]
+fn tables_can_require_matching_keys() {
+    let input = normalize_quotes(
+        "[{'a': 1, 'b': 2}, {'a': 3, 'c': 4}, {'a': 5, 'b': 6}, {'a': 7, 'b': 8}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.min_table_key_match_percent = Some(75);
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.contains("{\"a\": 3,         \"c\": 4}"));
+
+    formatter.options.min_table_key_match_percent = Some(80);
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\": 1, \"b\": 2},",
+            "    {\"a\": 3, \"c\": 4},",
+            "    {\"a\": 5, \"b\": 6},",
+            "    {\"a\": 7, \"b\": 8}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn table_columns_can_be_reordered() {
+    let input = normalize_quotes("[{'b': 1, 'id': 2, 'a': 3}, {'a': 4, 'c': 5, 'id': 6}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.table_column_order = TableColumnOrder::Alphabetical;
+    formatter.options.table_column_keys = vec!["id".to_string()];
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"id\": 2, \"a\": 3, \"b\": 1        },",
+            "    {\"id\": 6, \"a\": 4,         \"c\": 5}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn boolean_columns_are_right_aligned() {
+    let input =
+        normalize_quotes("[{'id': 1, 'ok': true}, {'id': 2, 'ok': false}, {'id': 3, 'ok': null}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"id\": 1, \"ok\":  true},",
+            "    {\"id\": 2, \"ok\": false},",
+            "    {\"id\": 3, \"ok\":  null}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn null_cells_can_be_left_blank() {
+    let input = normalize_quotes("[{'a': 1, 'b': null, 'c': 'x'}, {'a': null, 'b': 2, 'c': null}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.blank_table_nulls = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\": 1,         \"c\": \"x\"},",
+            "    {        \"b\": 2          }",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn blank_null_cells_keep_the_column_order() {
+    let input = normalize_quotes(
+        "[{'a': null, 'b': 1}, {'a': 1000, 'b': null}, {'a': 5, 'b': 6}, {'a': null, 'b': null}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.blank_table_nulls = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {           \"b\": 1},",
+            "    {\"a\": 1000        },",
+            "    {\"a\":    5, \"b\": 6},",
+            "    {                 }",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn array_rows_keep_their_nulls() {
+    let input = "[[1, null, 3], [null, 20, 30]]";
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.blank_table_nulls = true;
+
+    let output = formatter.reformat(input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec!["[", "    [   1, null,  3],", "    [null,   20, 30]", "]"]
+    );
+}
+
+#[test]
+fn tables_can_require_a_minimum_row_count() {
+    let input = normalize_quotes("[{'a': 1, 'bb': 2}, {'a': 333, 'bb': 4}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.min_table_rows = 3;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\": 1, \"bb\": 2},",
+            "    {\"a\": 333, \"bb\": 4}",
+            "]"
+        ]
+    );
+
+    formatter.options.min_table_rows = 2;
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.contains("{\"a\":   1, \"bb\": 2}"));
+}
+
+#[test]
+fn outlier_rows_can_be_expanded_within_tables() {
+    let input = normalize_quotes(
+        "[{'a': 1, 'b': 'x'}, {'a': 22, 'b': 'a string far too long to fit'}, {'a': 333, 'b': 'yy'}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.max_table_outlier_rows = 1;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\":   1, \"b\": \"x\" },",
+            "    {",
+            "        \"a\": 22,",
+            "        \"b\": \"a string far too long to fit\"",
+            "    },",
+            "    {\"a\": 333, \"b\": \"yy\"}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn analyze_reports_table_columns() {
+    let input = normalize_quotes(
+        "{'points': [{'x': 1, 'y': [1, 2], 'ok': true}, {'x': 22, 'label': 'far', 'ok': false}], 'n': 3}",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 100;
+
+    let tables = formatter.analyze(&input).unwrap();
+    assert_eq!(tables.len(), 1);
+    let table = &tables[0];
+    assert_eq!(table.path, "/points");
+    assert_eq!(table.row_count, 2);
+    assert_eq!(table.row_kind, TableColumnKind::Object);
+
+    let columns: Vec<(Option<&str>, TableColumnKind, usize)> = table
+        .columns
+        .iter()
+        .map(|column| (column.name.as_deref(), column.kind, column.row_count))
+        .collect();
+    assert_eq!(
+        columns,
+        vec![
+            (Some("x"), TableColumnKind::Number, 2),
+            (Some("y"), TableColumnKind::Array, 1),
+            (Some("ok"), TableColumnKind::Boolean, 2),
+            (Some("label"), TableColumnKind::String, 1),
+        ]
+    );
+    assert_eq!(table.columns[0].width, "\"x\": 22".len());
+
+    // The rows of the output are as wide as the analysis says.
+    let output = formatter.reformat(&input, 0).unwrap();
+    let row = output.lines().nth(2).unwrap().trim_start();
+    assert_eq!(row.len(), table.row_width + 1);
+}
+
+#[test]
+fn table_rows_can_end_without_padding() {
+    let input = normalize_quotes("[{'a': 1, 'b': 'xyz'}, {'a': 22, 'b': 'q'}, {'a': 333}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.trim_table_row_padding = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\":   1, \"b\": \"xyz\"},",
+            "    {\"a\":  22, \"b\": \"q\"},",
+            "    {\"a\": 333}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn tables_can_fall_back_to_tight_padding() {
+    let input = normalize_quotes("[{'a': 1, 'b': 'yyyyyy'}, {'a': 333333, 'b': 'x'}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 32;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.contains("{\"a\": 1, \"b\": \"yyyyyy\"}"));
+
+    formatter.options.tight_table_fallback = true;
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\":     1, \"b\":\"yyyyyy\"},",
+            "    {\"a\":333333, \"b\":\"x\"     }",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn numeric_strings_can_align_with_numbers() {
+    let input = normalize_quotes("[['1.5', 'x'], [22, 'yy'], [3.25, 'zzz']]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 20;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.contains("[\"1.5\", \"x\"  ]"));
+
+    formatter.options.align_numeric_strings = true;
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    [\"1.5\", \"x\"  ],",
+            "    [22   , \"yy\" ],",
+            "    [ 3.25, \"zzz\"]",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn unit_strings_can_align_on_their_numbers() {
+    let input = normalize_quotes(
+        "[{'f': 'a', 'size': '12.5 MB'}, {'f': 'b', 'size': '3 KB'}, {'f': 'c', 'size': '100 MiB'}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.align_unit_strings = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"f\": \"a\", \"size\": \"12.5 MB\" },",
+            "    {\"f\": \"b\", \"size\":    \"3 KB\" },",
+            "    {\"f\": \"c\", \"size\":  \"100 MiB\"}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn table_rows_can_be_sorted_by_a_column() {
+    let input = normalize_quotes(
+        "[{'id': 3, 'n': 'c'}, {'id': 10, 'n': 'a'}, {'n': 'z'}, {'id': 1, 'n': 'b'}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.table_sort_column = Some("id".into());
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"id\":  1, \"n\": \"b\"},",
+            "    {\"id\":  3, \"n\": \"c\"},",
+            "    {\"id\": 10, \"n\": \"a\"},",
+            "    {          \"n\": \"z\"}",
+            "]",
+        ]
+    );
+
+    let input = normalize_quotes("[['b', 1], ['c', 2], ['a', 3]]");
+    formatter.options.table_sort_column = Some("0".into());
+    formatter.options.table_sort_descending = true;
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    [\"c\", 2],",
+            "    [\"b\", 1],",
+            "    [\"a\", 3]",
+            "]"
+        ]
+    );
+}
+
+#[test]
+fn missing_table_keys_can_be_filled_with_nulls() {
+    let input = normalize_quotes("[{'a': 1, 'b': 'x'}, {'a': 22}, {'b': 'yy', 'c': true}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.fill_missing_table_keys = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\":    1, \"b\": \"x\",  \"c\": null},",
+            "    {\"a\":   22, \"b\": null, \"c\": null},",
+            "    {\"a\": null, \"b\": \"yy\", \"c\": true}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn tables_can_be_limited_to_a_maximum_row_count() {
+    let input = normalize_quotes("[{'a': 1, 'bb': 2}, {'a': 333, 'bb': 4}, {'a': 5, 'bb': 66}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.max_table_rows = Some(2);
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"a\": 1, \"bb\": 2},",
+            "    {\"a\": 333, \"bb\": 4},",
+            "    {\"a\": 5, \"bb\": 66}",
+            "]"
+        ]
+    );
+
+    formatter.options.max_table_rows = Some(3);
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.contains("{\"a\": 333, \"bb\":  4}"));
+}
+
+#[test]
+fn inner_arrays_can_require_equal_lengths_to_align() {
+    let input = normalize_quotes("[{'id': 1, 'v': [1, 2]}, {'id': 22, 'v': [33, 4, 5, 6]}]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.contains("{ \"id\":  1, \"v\": [ 1, 2      ] },"));
+
+    formatter.options.require_equal_array_lengths = true;
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    { \"id\":  1, \"v\": [1, 2]        },",
+            "    { \"id\": 22, \"v\": [33, 4, 5, 6] }",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn columns_needing_too_much_padding_are_unaligned() {
+    let input = normalize_quotes(
+        "[{'id': 1, 'note': 'ok', 'n': 5}, {'id': 22, 'note': 'a much longer note', 'n': 66}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 60;
+    formatter.options.max_column_padding = Some(8);
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"id\":  1, \"note\": \"ok\", \"n\": 5},",
+            "    {\"id\": 22, \"note\": \"a much longer note\", \"n\": 66}",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn datetime_columns_stay_left_aligned() {
+    let input = normalize_quotes(
+        "[{'at': '2024-05-01T12:30:00Z', 'v': 'a'}, {'at': '2024-05-01T12:30:00.250Z', 'v': 'bbb'}]",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 60;
+    formatter.options.string_column_alignment = StringColumnAlignment::Right;
+    formatter.options.detect_datetime_columns = true;
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    {\"at\": \"2024-05-01T12:30:00Z\",     \"v\":   \"a\"},",
+            "    {\"at\": \"2024-05-01T12:30:00.250Z\", \"v\": \"bbb\"}",
+            "]",
+        ]
+    );
+
+    let tables = formatter.analyze(&input).unwrap();
+    assert_eq!(tables[0].columns[0].kind, TableColumnKind::DateTime);
+    assert_eq!(tables[0].columns[1].kind, TableColumnKind::String);
+}
+
+#[test]
+fn analyze_leaves_out_tables_cut_by_max_output_lines() {
+    let input = normalize_quotes(
+        "{'small': [{'x': 1}, {'x': 2}], 'big': [{'y': 1}, {'y': 2}, {'y': 3}, {'y': 4}]}",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+    formatter.options.max_output_lines = Some(8);
+
+    let tables = formatter.analyze(&input).unwrap();
+    let paths: Vec<&str> = tables.iter().map(|table| table.path.as_str()).collect();
+    assert_eq!(paths, vec!["/small"]);
+}
+
+#[test]
+fn string_cells_can_be_classified() {
+    let input = normalize_quotes("[['2 kg', '#fff'], ['12.5 kg', '#00ff00'], [3, '#f00']]");
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.string_cell_classifier = Some(Arc::new(|value: &str| {
+        if value.ends_with(" kg") {
+            Some(CellAlignment::Number)
+        } else if value.starts_with('#') {
+            Some(CellAlignment::Right)
+        } else {
+            None
+        }
+    }));
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "[",
+            "    [ \"2 kg\"  ,    \"#fff\"],",
+            "    [\"12.5 kg\", \"#00ff00\"],",
+            "    [  3      ,    \"#f00\"]",
+            "]",
+        ]
+    );
+}
+
+#[test]
+fn table_columns_can_be_spaced_apart() {
+    let input = normalize_quotes(
+        "{'rows': [{'a': 1, 'b': 'xyz'}, {'a': 22, 'b': 'q'}], 'list': [1, 2, 3]}",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 40;
+    formatter.options.table_column_spacing = Some(2);
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"rows\": [",
+            "        {\"a\":  1,  \"b\": \"xyz\"},",
+            "        {\"a\": 22,  \"b\": \"q\"  }",
+            "    ],",
+            "    \"list\": [1, 2, 3]",
+            "}",
+        ]
+    );
+}
+
+#[test]
+fn long_row_names_can_be_left_unpadded() {
+    let input = normalize_quotes(
+        "{'a': {'x': 1, 'y': 2}, 'bb': {'x': 10, 'y': 20}, 'a_much_longer_key': {'x': 3, 'y': 4}}",
+    );
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 50;
+    formatter.options.max_table_name_padding = Some(4);
+
+    let output = formatter.reformat(&input, 0).unwrap();
+    let output_lines: Vec<&str> = output.trim_end().split('\n').collect();
+    assert_eq!(
+        output_lines,
+        vec![
+            "{",
+            "    \"a\": {\"x\":  1, \"y\":  2},",
+            "    \"bb\": {\"x\": 10, \"y\": 20},",
+            "    \"a_much_longer_key\": {\"x\":  3, \"y\":  4}",
+            "}",
+        ]
+    );
+
+    formatter.options.max_table_name_padding = Some(16);
+    let output = formatter.reformat(&input, 0).unwrap();
+    assert!(output.contains("    \"a\"                : {\"x\":  1, \"y\":  2},"));
+}
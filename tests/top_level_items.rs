@@ -1,4 +1,4 @@
-use fracturedjson::{CommentPolicy, Formatter};
+use fracturedjson::{CommentPolicy, EolStyle, Formatter};
 
 #[test]
 fn error_if_multiple_top_level_elements() {
@@ -32,3 +32,56 @@ fn comments_after_top_level_element_are_preserved() {
     assert!(minified_output.contains("/*b*/"));
     assert!(minified_output.contains("//c"));
 }
+
+#[test]
+fn stream_formats_each_top_level_element() {
+    let input = "{\"a\":1} [1,2]\n\"x\"\n";
+    let mut formatter = Formatter::new();
+    let output = formatter.reformat_stream(input, 0).unwrap();
+
+    assert_eq!(output, "{\"a\": 1}\n[1, 2]\n\"x\"\n");
+}
+
+#[test]
+fn stream_still_rejects_separating_commas() {
+    let input = "[1,2], [3,4]";
+    let mut formatter = Formatter::new();
+    assert!(formatter.reformat_stream(input, 0).is_err());
+}
+
+#[test]
+fn error_if_document_exceeds_max_size() {
+    let mut formatter = Formatter::new();
+    formatter.options.max_document_size = 10;
+    assert!(formatter.reformat("[1, 2, 3]", 0).is_ok());
+
+    let err = formatter.reformat("[1, 2, 3, 4]", 0).unwrap_err();
+    assert_eq!(err.input_position.unwrap().index, 10);
+}
+
+#[test]
+fn final_newline_can_be_forced_or_suppressed() {
+    let mut formatter = Formatter::new();
+    assert_eq!(formatter.reformat("[1,2]", 0).unwrap(), "[1, 2]\n");
+    assert_eq!(formatter.minify("[1, 2]").unwrap(), "[1,2]");
+
+    formatter.options.ensure_final_newline = Some(true);
+    assert_eq!(formatter.reformat("[1,2]", 0).unwrap(), "[1, 2]\n");
+    assert_eq!(formatter.minify("[1, 2]").unwrap(), "[1,2]\n");
+    assert_eq!(formatter.reformat_jsonl("[1,2]").unwrap(), "[1, 2]\n");
+
+    formatter.options.ensure_final_newline = Some(false);
+    assert_eq!(formatter.reformat("[1,2]", 0).unwrap(), "[1, 2]");
+    assert_eq!(formatter.minify("[1, 2]").unwrap(), "[1,2]");
+    assert_eq!(
+        formatter.reformat_jsonl("[1,2]\n[3]\n").unwrap(),
+        "[1, 2]\n[3]"
+    );
+
+    formatter.options.json_eol_style = EolStyle::Crlf;
+    formatter.options.max_total_line_length = 4;
+    assert_eq!(
+        formatter.reformat("[1,2]", 0).unwrap(),
+        "[\r\n    1,\r\n    2\r\n]"
+    );
+}
@@ -0,0 +1,122 @@
+use fracturedjson::{Formatter, SkeletonLanguage};
+
+#[test]
+fn rust_skeleton_infers_field_types() {
+    let input = r#"{"name": "a", "count": 3, "ratio": 0.5, "ok": true, "tags": ["x", "y"]}"#;
+    let formatter = Formatter::new();
+    let output = formatter
+        .type_skeleton(input, SkeletonLanguage::Rust)
+        .unwrap();
+
+    assert!(output.contains("pub struct Root {"));
+    assert!(output.contains("    pub name: String,"));
+    assert!(output.contains("    pub count: i64,"));
+    assert!(output.contains("    pub ratio: f64,"));
+    assert!(output.contains("    pub ok: bool,"));
+    assert!(output.contains("    pub tags: Vec<String>,"));
+}
+
+#[test]
+fn nested_objects_become_named_definitions() {
+    let input = r#"{"geo": {"lat": 1.5, "lng": 2}}"#;
+    let formatter = Formatter::new();
+
+    let rust = formatter
+        .type_skeleton(input, SkeletonLanguage::Rust)
+        .unwrap();
+    assert!(rust.contains("    pub geo: Geo,"));
+    assert!(rust.contains("pub struct Geo {"));
+
+    let ts = formatter
+        .type_skeleton(input, SkeletonLanguage::TypeScript)
+        .unwrap();
+    assert!(ts.contains("    geo: Geo;"));
+    assert!(ts.contains("export interface Geo {"));
+    assert!(ts.contains("    lat: number;"));
+}
+
+#[test]
+fn missing_keys_and_nulls_make_fields_optional() {
+    let input = r#"[{"id": 1, "note": null}, {"id": 2, "note": "x", "extra": false}]"#;
+    let formatter = Formatter::new();
+
+    let rust = formatter
+        .type_skeleton(input, SkeletonLanguage::Rust)
+        .unwrap();
+    assert!(rust.starts_with("pub type Root = Vec<RootItem>;\n"));
+    assert!(rust.contains("    pub id: i64,"));
+    assert!(rust.contains("    pub note: Option<String>,"));
+    assert!(rust.contains("    pub extra: Option<bool>,"));
+
+    let ts = formatter
+        .type_skeleton(input, SkeletonLanguage::TypeScript)
+        .unwrap();
+    assert!(ts.starts_with("export type Root = RootItem[];\n"));
+    assert!(ts.contains("    id: number;"));
+    assert!(ts.contains("    note: string | null;"));
+    assert!(ts.contains("    extra?: boolean;"));
+}
+
+#[test]
+fn conflicting_types_fall_back_to_dynamic_values() {
+    let input = r#"[{"n": 1, "v": 1}, {"n": 2.5, "v": "one"}]"#;
+    let formatter = Formatter::new();
+
+    let rust = formatter
+        .type_skeleton(input, SkeletonLanguage::Rust)
+        .unwrap();
+    assert!(rust.contains("    pub n: f64,"));
+    assert!(rust.contains("    pub v: serde_json::Value,"));
+
+    let ts = formatter
+        .type_skeleton(input, SkeletonLanguage::TypeScript)
+        .unwrap();
+    assert!(ts.contains("    n: number;"));
+    assert!(ts.contains("    v: unknown;"));
+}
+
+#[test]
+fn awkward_keys_are_renamed_or_quoted() {
+    let input = r#"{"userName": 1, "type": 2, "x y": 3}"#;
+    let formatter = Formatter::new();
+
+    let rust = formatter
+        .type_skeleton(input, SkeletonLanguage::Rust)
+        .unwrap();
+    assert!(rust.contains("    #[serde(rename = \"userName\")]\n    pub user_name: i64,"));
+    assert!(rust.contains("    pub r#type: i64,"));
+    assert!(rust.contains("    #[serde(rename = \"x y\")]\n    pub x_y: i64,"));
+
+    let ts = formatter
+        .type_skeleton(input, SkeletonLanguage::TypeScript)
+        .unwrap();
+    assert!(ts.contains("    userName: number;"));
+    assert!(ts.contains("    \"x y\": number;"));
+
+    let ts = formatter
+        .type_skeleton(r#"{"a\u001fb": 1, "é\"": 2}"#, SkeletonLanguage::TypeScript)
+        .unwrap();
+    assert!(ts.contains("    \"a\\u001fb\": number;"));
+    assert!(ts.contains("    \"é\\\"\": number;"));
+}
+
+#[test]
+fn rust_fields_with_the_same_identifier_get_a_suffix() {
+    let input = r#"{"a-b": 1, "a_b": "x", "A B": true}"#;
+    let formatter = Formatter::new();
+
+    let rust = formatter
+        .type_skeleton(input, SkeletonLanguage::Rust)
+        .unwrap();
+    assert!(rust.contains("    #[serde(rename = \"a-b\")]\n    pub a_b: i64,"));
+    assert!(rust.contains("    #[serde(rename = \"a_b\")]\n    pub a_b_2: String,"));
+    assert!(rust.contains("    #[serde(rename = \"A B\")]\n    pub a_b_3: bool,"));
+}
+
+#[test]
+fn skeleton_reports_parse_errors() {
+    let formatter = Formatter::new();
+    assert!(formatter
+        .type_skeleton("{\"a\": }", SkeletonLanguage::Rust)
+        .is_err());
+}
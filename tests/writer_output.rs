@@ -0,0 +1,134 @@
+use std::io::{self, Write};
+
+use fracturedjson::{CommentPolicy, Formatter, KeyCase};
+use serde_json::json;
+
+const INPUT: &str = r#"{
+    // settings
+    "name": "Alice",
+    "scores": [95, 87, 92, 100, 64, 78, 81, 99, 90, 88, 91, 85, 70, 77, 83, 96, 72, 68, 94],
+    "address": {"street": "1 Main St", "city": "Springfield", "zip": "12345", "country": "US"}
+}"#;
+
+fn to_writer_output(formatter: &mut Formatter, minify: bool) -> String {
+    let mut output = Vec::new();
+    if minify {
+        formatter.minify_to_writer(INPUT, &mut output).unwrap();
+    } else {
+        formatter.reformat_to_writer(INPUT, 0, &mut output).unwrap();
+    }
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn writer_output_matches_string_output() {
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+    formatter.options.max_total_line_length = 60;
+
+    for final_newline in [None, Some(true), Some(false)] {
+        formatter.options.ensure_final_newline = final_newline;
+        assert_eq!(
+            to_writer_output(&mut formatter, false),
+            formatter.reformat(INPUT, 0).unwrap()
+        );
+        assert_eq!(
+            to_writer_output(&mut formatter, true),
+            formatter.minify(INPUT).unwrap()
+        );
+    }
+
+    formatter.options.verify_round_trip = true;
+    assert_eq!(
+        to_writer_output(&mut formatter, false),
+        formatter.reformat(INPUT, 0).unwrap()
+    );
+}
+
+#[test]
+fn serialize_to_writer_matches_serialize() {
+    let value = json!({"name": "Alice", "scores": [95, 87, 92], "active": true});
+    let mut formatter = Formatter::new();
+    formatter.options.max_total_line_length = 30;
+
+    let mut output = Vec::new();
+    formatter
+        .serialize_to_writer(&value, 0, 100, &mut output)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        formatter.serialize(&value, 0, 100).unwrap()
+    );
+}
+
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("disk full"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn write_errors_are_reported() {
+    let mut formatter = Formatter::new();
+    let err = formatter
+        .reformat_to_writer("[1, 2]", 0, FailingWriter)
+        .unwrap_err();
+    assert_eq!(err.message, "Failed to write output: disk full");
+    assert!(err.input_position.is_none());
+}
+
+#[test]
+fn top_level_comments_are_written_like_string_output() {
+    let input = "// header\n\n/* about */\n[1, 2, 3] // after\n\n// trailer\n";
+    let mut formatter = Formatter::new();
+    formatter.options.comment_policy = CommentPolicy::Preserve;
+
+    for final_newline in [None, Some(true), Some(false)] {
+        formatter.options.ensure_final_newline = final_newline;
+        let mut output = Vec::new();
+        formatter.reformat_to_writer(input, 0, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            formatter.reformat(input, 0).unwrap()
+        );
+    }
+}
+
+#[test]
+fn jsonl_is_written_line_by_line() {
+    let input = "{\"a\":1}\n\n{\"b\":[1,2]}\n";
+    let mut formatter = Formatter::new();
+    formatter.options.auto_detect_jsonl = true;
+
+    for final_newline in [None, Some(true), Some(false)] {
+        formatter.options.ensure_final_newline = final_newline;
+        let mut output = Vec::new();
+        formatter.reformat_to_writer(input, 0, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            formatter.reformat_jsonl(input).unwrap()
+        );
+    }
+
+    // The lines before one that fails are already written.
+    formatter.options.key_case = Some(KeyCase::SnakeCase);
+    let mut output = Vec::new();
+    let err = formatter
+        .reformat_to_writer(
+            "{\"a\":1}\n{\"b\":2}\n{\"cD\":3,\"c_d\":4}\n",
+            0,
+            &mut output,
+        )
+        .unwrap_err();
+    assert!(err.message.starts_with("line 3:"));
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "{\"a\": 1}\n{\"b\": 2}\n"
+    );
+}